@@ -0,0 +1,132 @@
+//! Benchmarks of encoding, decoding and updating across the shapes of codes and the block sizes.
+//!
+//! Run with `cargo bench --manifest-path benches/Cargo.toml`.
+//! The shapes and the block sizes can be overridden by the environment variables, e.g.
+//! `EC_BENCH_SHAPES=4x2,10x4 EC_BENCH_BLOCK_SIZES=4096,1048576`.
+use std::hint::black_box;
+use std::num::NonZeroUsize;
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use erasure_isa_l::erasure::ErasureCode;
+use erasure_isa_l::features::cpu_features;
+
+const DEFAULT_SHAPES: [(usize, usize); 4] = [(4, 2), (6, 3), (10, 4), (12, 4)];
+const DEFAULT_BLOCK_SIZES: [usize; 4] = [4 << 10, 64 << 10, 1 << 20, 4 << 20];
+
+fn shapes() -> Vec<(usize, usize)> {
+    match std::env::var("EC_BENCH_SHAPES") {
+        Ok(shapes) => shapes
+            .split(',')
+            .map(|shape| {
+                let (k, m) = shape
+                    .trim()
+                    .split_once('x')
+                    .expect("a shape is formatted as <k>x<m>");
+                (
+                    k.parse().expect("invalid source number"),
+                    m.parse().expect("invalid code number"),
+                )
+            })
+            .collect(),
+        Err(_) => DEFAULT_SHAPES.to_vec(),
+    }
+}
+
+fn block_sizes() -> Vec<usize> {
+    match std::env::var("EC_BENCH_BLOCK_SIZES") {
+        Ok(sizes) => sizes
+            .split(',')
+            .map(|size| size.trim().parse().expect("invalid block size"))
+            .collect(),
+        Err(_) => DEFAULT_BLOCK_SIZES.to_vec(),
+    }
+}
+
+fn make_rand_blk(n: usize, blk_size: usize) -> Vec<Vec<u8>> {
+    (0..n)
+        .map(|_| rand::random_iter().take(blk_size).collect::<Vec<u8>>())
+        .collect()
+}
+
+/// The codes under the benchmarks, with the label of each one.
+fn codes() -> Vec<(String, ErasureCode)> {
+    shapes()
+        .into_iter()
+        .map(|(k, m)| {
+            let ec = ErasureCode::with_reed_solomon(
+                NonZeroUsize::new(k).unwrap(),
+                NonZeroUsize::new(m).unwrap(),
+            )
+            .expect("invalid shape");
+            (format!("rs({k},{m})"), ec)
+        })
+        .collect()
+}
+
+fn bench_encode(c: &mut Criterion) {
+    println!("isa-l kernels: {:?}", cpu_features());
+    let mut group = c.benchmark_group("encode");
+    for (label, ec) in codes() {
+        for block_size in block_sizes() {
+            let data = make_rand_blk(ec.source_num(), block_size);
+            let mut parity = vec![vec![0_u8; block_size]; ec.code_num()];
+            group.throughput(Throughput::Bytes((ec.source_num() * block_size) as u64));
+            group.bench_function(BenchmarkId::new(&label, block_size), |b| {
+                b.iter(|| ec.encode(black_box(&data), &mut parity).unwrap())
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode");
+    for (label, ec) in codes() {
+        // erase as many source blocks as possible, which is the most expensive pattern
+        let erasures = (0..ec.code_num().min(ec.source_num())).collect::<Vec<_>>();
+        let table = ec.make_decode_table(&erasures).unwrap();
+        for block_size in block_sizes() {
+            let mut data = make_rand_blk(ec.source_num(), block_size);
+            let mut parity = ec.encode_to_owned(&data).unwrap();
+            group.throughput(Throughput::Bytes((ec.source_num() * block_size) as u64));
+            group.bench_function(
+                BenchmarkId::new(format!("{label}/make-table"), block_size),
+                |b| {
+                    b.iter(|| {
+                        ec.decode(&mut data, &mut parity, black_box(&erasures))
+                            .unwrap()
+                    })
+                },
+            );
+            group.bench_function(
+                BenchmarkId::new(format!("{label}/with-table"), block_size),
+                |b| {
+                    b.iter(|| {
+                        ec.decode_with_table(&mut data, &mut parity, &table, black_box(&erasures))
+                            .unwrap()
+                    })
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+fn bench_update(c: &mut Criterion) {
+    let mut group = c.benchmark_group("update");
+    for (label, ec) in codes() {
+        for block_size in block_sizes() {
+            let data = make_rand_blk(ec.source_num(), block_size);
+            let mut parity = ec.encode_to_owned(&data).unwrap();
+            let delta = make_rand_blk(1, block_size).pop().unwrap();
+            group.throughput(Throughput::Bytes(block_size as u64));
+            group.bench_function(BenchmarkId::new(&label, block_size), |b| {
+                b.iter(|| ec.update(0, black_box(&delta), &mut parity).unwrap())
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_encode, bench_decode, bench_update);
+criterion_main!(benches);
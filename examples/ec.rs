@@ -3,7 +3,7 @@ use std::num::NonZeroUsize;
 use erasure_isa_l::erasure::ErasureCode;
 
 fn usage(cmd: &str) {
-    let cmd = cmd.split('/').last().unwrap_or(cmd);
+    let cmd = cmd.split('/').next_back().unwrap_or(cmd);
     println!("Usage: {cmd} <source_num> <code_num> <block size(MB)> <test_load>");
     println!("Example: {cmd} 10 4 1 1024");
 }
@@ -0,0 +1,96 @@
+//! Fuzzes the decode path with random geometries, matrix kinds, block contents and erasure sets.
+//!
+//! The erasure sets may contain out-of-range and duplicated indices, and more indices than the code number,
+//! every input must either be rejected with an `Error` or round-trip to the original blocks.
+#![no_main]
+
+use erasure_isa_l::erasure::{ErasureCode, MatrixKind};
+use libfuzzer_sys::arbitrary::{Result, Unstructured};
+use libfuzzer_sys::fuzz_target;
+
+/// The maximum source number and code number, a larger geometry only slows down the fuzzing.
+const MAX_SOURCE_NUM: usize = 16;
+const MAX_CODE_NUM: usize = 8;
+/// The maximum block length, blocks shorter than the SIMD width are included.
+const MAX_BLOCK_LEN: usize = 256;
+/// The byte erased blocks are filled with, the decoding must not depend on it.
+const GARBAGE: u8 = 0xA5;
+
+fuzz_target!(|input: &[u8]| {
+    let _ = fuzz_decode(&mut Unstructured::new(input));
+});
+
+fn fuzz_decode(u: &mut Unstructured) -> Result<()> {
+    let k = u.int_in_range(1..=MAX_SOURCE_NUM)?;
+    let m = u.int_in_range(1..=MAX_CODE_NUM)?;
+    let kind = *u.choose(&[
+        MatrixKind::ReedSolomon,
+        MatrixKind::AdjustedReedSolomon,
+        MatrixKind::Vandermonde,
+        MatrixKind::Cauchy,
+    ])?;
+    let len = u.int_in_range(0..=MAX_BLOCK_LEN)?;
+    // out-of-range indices up to `block_num() + 3`, duplicates come for free
+    let erasure_num = u.int_in_range(0..=k + m + 1)?;
+    let erasures = (0..erasure_num)
+        .map(|_| u.int_in_range(0..=k + m + 3))
+        .collect::<Result<Vec<_>>>()?;
+    let data = (0..k)
+        .map(|_| (0..len).map(|_| u.arbitrary()).collect())
+        .collect::<Result<Vec<Vec<u8>>>>()?;
+
+    let Ok(ec) = ErasureCode::builder()
+        .source_num(k)
+        .code_num(m)
+        .matrix_kind(kind)
+        .build()
+    else {
+        return Ok(());
+    };
+    let Ok(parity) = ec.encode_to_owned(&data) else {
+        return Ok(());
+    };
+
+    let mut sorted = erasures.clone();
+    sorted.sort_unstable();
+    sorted.dedup();
+    let valid = sorted.len() <= m && sorted.iter().all(|e| *e < k + m);
+
+    let mut erased_data = data.clone();
+    let mut erased_parity = parity.clone();
+    for &e in sorted.iter().filter(|e| **e < k + m) {
+        match e {
+            e if e < k => erased_data[e].fill(GARBAGE),
+            e => erased_parity[e - k].fill(GARBAGE),
+        }
+    }
+    // the owned variant recovers the same blocks, ignoring the content of the erased blocks
+    if let Ok(recovered) = ec.decode_to_owned(&erased_data, &erased_parity, &erasures) {
+        assert!(
+            valid,
+            "invalid erasures {erasures:?} are accepted by decode_to_owned"
+        );
+        for (i, block) in recovered {
+            let orig = if i < k { &data[i] } else { &parity[i - k] };
+            assert_eq!(
+                &block, orig,
+                "block {i} is not recovered by decode_to_owned"
+            );
+        }
+    }
+
+    match ec.decode(&mut erased_data, &mut erased_parity, &erasures) {
+        Ok(recovered) => {
+            assert!(valid, "invalid erasures {erasures:?} are accepted");
+            assert_eq!(recovered, sorted);
+            assert_eq!(erased_data, data);
+            assert_eq!(erased_parity, parity);
+        }
+        // any sub matrix of a cauchy matrix is invertible
+        Err(e) => assert!(
+            !valid || kind != MatrixKind::Cauchy,
+            "valid erasures {erasures:?} of a cauchy code are rejected: {e}"
+        ),
+    }
+    Ok(())
+}
@@ -28,7 +28,8 @@ pub mod ec {
     /// * `rows` - The number of output vectors to concurrently encode/decode.
     /// * `a` - Pointer to sets of arrays of input coefficients used to encode or decode data.
     /// * `gf_tbls` - Pointer to start of space for concatenated output tables
-    ///   generated from input coefficients. Must be of size 32*k*rows.
+    ///   generated from input coefficients. Must be of size 32*k*rows, see
+    ///   [`ErasureCode::gf_table_len`](crate::erasure::ErasureCode::gf_table_len).
     pub fn init_tables(k: i32, rows: i32, a: &[u8], gf_tbls: &mut [u8]) {
         unsafe {
             erasure_isa_l_sys::ec_init_tables(k, rows, a.as_ptr() as *mut u8, gf_tbls.as_mut_ptr());
@@ -127,7 +128,26 @@ pub mod ec {
 /// for Galois Field operations. If you are looking for a higher-level
 /// interface, consider using the [`erasure`](crate::erasure) module instead,
 /// which provides a more user-friendly API for working with Galois Field operations.
+///
+/// # Field
+/// isa-l works over GF(2^8), of [`gf::FIELD_ORDER`] elements, constructed with the primitive polynomial
+/// [`gf::PRIMITIVE_POLY`] `x^8 + x^4 + x^3 + x^2 + 1` (0x11D), whose root [`gf::GENERATOR`] 2 generates
+/// the multiplicative group. It is also the default field of `w = 8` in jerasure, so another library
+/// agrees on the arithmetic if it multiplies `2 * 0x80` into `0x1D`.
 pub mod gf {
+    use alloc::format;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    /// The number of elements of the field GF(2^8).
+    pub const FIELD_ORDER: usize = 256;
+
+    /// The primitive polynomial of the field, `x^8 + x^4 + x^3 + x^2 + 1`.
+    pub const PRIMITIVE_POLY: u16 = 0x11D;
+
+    /// The generator of the multiplicative group of the field, which is the root of [`PRIMITIVE_POLY`].
+    pub const GENERATOR: u8 = 2;
+
     /// Single element GF(2^8) multiply.
     ///
     /// # Parameters
@@ -155,6 +175,39 @@ pub mod gf {
         unsafe { erasure_isa_l_sys::gf_inv(a) }
     }
 
+    /// Exponentiation of the generator in GF(2^8).
+    ///
+    /// # Parameters
+    ///
+    /// * `n` - Exponent
+    ///
+    /// # Returns
+    ///
+    /// [`GENERATOR`] to the power of `n`, which has the period 255, so `exp(255)` is 1
+    pub fn exp(n: u8) -> u8 {
+        (0..n).fold(1, |acc, _| mul(acc, GENERATOR))
+    }
+
+    /// Discrete logarithm to the base of the generator in GF(2^8).
+    ///
+    /// # Parameters
+    ///
+    /// * `a` - Input element
+    ///
+    /// # Returns
+    ///
+    /// The exponent `n` in `0..255` such that `exp(n) == a`, or `None` if `a` is 0
+    pub fn log(a: u8) -> Option<u8> {
+        let mut power = 1;
+        for n in 0..u8::MAX {
+            if power == a {
+                return Some(n);
+            }
+            power = mul(power, GENERATOR);
+        }
+        None
+    }
+
     /// Generate a matrix of coefficients to be used for encoding.
     ///
     /// Vandermonde matrix example of encoding coefficients where high portion of
@@ -190,6 +243,109 @@ pub mod gf {
         }
     }
 
+    /// Generate the [m x k] matrix of [`gen_rs_matrix`] into a newly allocated buffer.
+    ///
+    /// # Parameters
+    ///
+    /// * `m` - Number of rows in matrix corresponding to srcs + parity
+    /// * `k` - Number of columns in matrix corresponding to srcs
+    ///
+    /// # Returns
+    ///
+    /// The `m * k` coefficients in row-major order
+    ///
+    /// # Panics
+    ///
+    /// Panics if `m` or `k` is negative.
+    pub fn rs_matrix(m: i32, k: i32) -> Vec<u8> {
+        let mut a = vec![0_u8; matrix_len(m, k)];
+        gen_rs_matrix(&mut a, m, k);
+        a
+    }
+
+    /// Generate the [m x k] Vandermonde-style matrix of [`gen_rs_matrix`] with a configurable generator base.
+    ///
+    /// The high portion of the matrix is the identity matrix I, and the coefficient of source `j`
+    /// in parity row `r` is `base^(r * j)`, computed in pure Rust by [`mul`].
+    /// `gen_rs_matrix` fixes the base to 2, so `rs_matrix_with_base(m, k, 2)` is the same as [`rs_matrix`],
+    /// and any other base reproduces the matrix of an encoder built on a different generator element,
+    /// which can then be passed to [`ErasureCode::with_matrix`](crate::erasure::ErasureCode::with_matrix).
+    ///
+    /// Like `gen_rs_matrix`, the matrix does not guarantee that every sub matrix is invertable,
+    /// and a base of a smaller multiplicative order than 2, such as 0 or 1, repeats the coefficients
+    /// and makes more erasure patterns unrecoverable.
+    ///
+    /// # Parameters
+    ///
+    /// * `m` - Number of rows in matrix corresponding to srcs + parity
+    /// * `k` - Number of columns in matrix corresponding to srcs
+    /// * `base` - The generator element the rows are the powers of
+    ///
+    /// # Returns
+    ///
+    /// The `m * k` coefficients in row-major order
+    ///
+    /// # Panics
+    ///
+    /// Panics if `m` or `k` is negative.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::gf;
+    /// assert_eq!(gf::rs_matrix_with_base(6, 4, 2), gf::rs_matrix(6, 4));
+    /// let matrix = gf::rs_matrix_with_base(6, 4, 3);
+    /// assert_eq!(&matrix[16..20], &[1, 1, 1, 1]);
+    /// assert_eq!(&matrix[20..24], &[1, 3, 5, 15]);
+    /// ```
+    pub fn rs_matrix_with_base(m: i32, k: i32, base: u8) -> Vec<u8> {
+        let mut a = vec![0_u8; matrix_len(m, k)];
+        let (m, k) = (m as usize, k as usize);
+        for i in 0..k.min(m) {
+            a[k * i + i] = 1;
+        }
+        // the base of the parity row `r` is `base^r`
+        let mut row_base = 1;
+        for row in a
+            .chunks_exact_mut(k.max(1))
+            .skip(k)
+            .take(m.saturating_sub(k))
+        {
+            let mut p = 1;
+            for c in row {
+                *c = p;
+                p = mul(p, row_base);
+            }
+            row_base = mul(row_base, base);
+        }
+        a
+    }
+
+    /// Generate the [m x k] matrix of [`gen_cauchy1_matrix`] into a newly allocated buffer.
+    ///
+    /// # Parameters
+    ///
+    /// * `m` - Number of rows in matrix corresponding to srcs + parity
+    /// * `k` - Number of columns in matrix corresponding to srcs
+    ///
+    /// # Returns
+    ///
+    /// The `m * k` coefficients in row-major order
+    ///
+    /// # Panics
+    ///
+    /// Panics if `m` or `k` is negative.
+    pub fn cauchy1_matrix(m: i32, k: i32) -> Vec<u8> {
+        let mut a = vec![0_u8; matrix_len(m, k)];
+        gen_cauchy1_matrix(&mut a, m, k);
+        a
+    }
+
+    fn matrix_len(m: i32, k: i32) -> usize {
+        let m = usize::try_from(m).expect("the number of rows must not be negative");
+        let k = usize::try_from(k).expect("the number of columns must not be negative");
+        m * k
+    }
+
     /// Invert a matrix in GF(2^8)
     ///
     /// Attempts to construct an n x n inverse of the input matrix.
@@ -212,6 +368,71 @@ pub mod gf {
         res == 0
     }
 
+    /// The error of [`invert_matrix_diagnostic`], reporting where the inversion failed.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+    #[error("singular matrix: no pivot found for row {pivot_row}")]
+    pub struct InversionError {
+        /// The row at which Gaussian elimination failed to find a nonzero pivot.
+        pub pivot_row: usize,
+    }
+
+    /// Invert a matrix in GF(2^8), reporting the pivot row on failure.
+    ///
+    /// This is a Rust implementation of the Gauss-Jordan elimination done by [`invert_matrix`],
+    /// with the same row swapping strategy, intended for diagnosing why a matrix is singular.
+    /// For non-singular matrices it produces the same result as [`invert_matrix`],
+    /// which remains the fast path and should be preferred otherwise.
+    ///
+    /// # Parameters
+    ///
+    /// * `input` - Input matrix, which is left untouched
+    /// * `n` - Size of matrix \[nxn\]
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(output)` - The output matrix such that \[input\] x \[output\] = \[I\] - identity matrix
+    /// * `Err(InversionError)` - If input matrix is singular, with the row at which the elimination failed
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `input` is not `n * n`.
+    pub fn invert_matrix_diagnostic(input: &[u8], n: usize) -> Result<Vec<u8>, InversionError> {
+        assert_eq!(input.len(), n * n, "input matrix must be n x n");
+        let mut input = input.to_vec();
+        let mut output = vec![0_u8; n * n];
+        (0..n).for_each(|i| output[i * n + i] = 1);
+        for i in 0..n {
+            // find a row with a nonzero pivot and swap it into place
+            if input[i * n + i] == 0 {
+                let j = ((i + 1)..n)
+                    .find(|j| input[j * n + i] != 0)
+                    .ok_or(InversionError { pivot_row: i })?;
+                for c in 0..n {
+                    input.swap(i * n + c, j * n + c);
+                    output.swap(i * n + c, j * n + c);
+                }
+            }
+            // scale the pivot row to make the pivot 1
+            let inv = self::inv(input[i * n + i]);
+            for c in 0..n {
+                input[i * n + c] = self::mul(input[i * n + c], inv);
+                output[i * n + c] = self::mul(output[i * n + c], inv);
+            }
+            // eliminate the pivot column from the other rows
+            for j in (0..n).filter(|j| *j != i) {
+                let coef = input[j * n + i];
+                if coef == 0 {
+                    continue;
+                }
+                for c in 0..n {
+                    input[j * n + c] ^= self::mul(coef, input[i * n + c]);
+                    output[j * n + c] ^= self::mul(coef, output[i * n + c]);
+                }
+            }
+        }
+        Ok(output)
+    }
+
     /// GF(2^8) vector dot product, runs appropriate version.
     ///
     /// Does a GF(2^8) dot product across each byte of the input array and a constant
@@ -242,6 +463,53 @@ pub mod gf {
         }
     }
 
+    /// GF(2^8) vector dot products of the same sources with several sets of coefficients at once.
+    ///
+    /// Computes a [`vect_dot_prod`] for each pair of table and destination, but in a single pass
+    /// over the sources by [`ec::encode_data`](crate::ec::encode_data), which is more efficient than
+    /// a call of `vect_dot_prod` per destination.
+    ///
+    /// # Parameters
+    ///
+    /// * `len` - Length of each vector in bytes.
+    /// * `vlen` - Number of vector sources.
+    /// * `gf_tbls` - The 32*vlen byte arrays of pre-calculated constants, one for each destination,
+    ///   as for [`vect_dot_prod`].
+    /// * `src` - Array of pointers to source inputs.
+    /// * `dest` - Array of pointers to destination data arrays, one for each table.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the numbers of `gf_tbls` and `dest` differ, or any table is shorter than 32*vlen bytes.
+    pub fn vect_dot_prod_multi(
+        len: i32,
+        vlen: i32,
+        gf_tbls: &[&[u8]],
+        src: &[*const u8],
+        dest: &mut [*mut u8],
+    ) {
+        assert_eq!(
+            gf_tbls.len(),
+            dest.len(),
+            "one gf table for each destination"
+        );
+        let tbl_len = 32 * usize::try_from(vlen).unwrap();
+        // `ec_encode_data` takes the tables of all the rows back to back
+        let mut tables = Vec::with_capacity(tbl_len * gf_tbls.len());
+        for tbl in gf_tbls {
+            assert!(tbl.len() >= tbl_len, "gf table must hold 32*vlen bytes");
+            tables.extend_from_slice(&tbl[..tbl_len]);
+        }
+        crate::ec::encode_data(
+            len,
+            vlen,
+            dest.len().try_into().unwrap(),
+            &tables,
+            src,
+            dest,
+        );
+    }
+
     /// GF(2^8) vector multiply accumulate, runs appropriate version.
     ///
     /// Does a GF(2^8) multiply across each byte of input source with expanded
@@ -275,4 +543,171 @@ pub mod gf {
             );
         }
     }
+
+    /// Initialize 32-byte constant array for GF(2^8) vector multiply.
+    ///
+    /// Calculates array {C{00}, C{01}, C{02}, ... , C{0f} }, {C{00}, C{10},
+    /// C{20}, ... , C{f0} } as required by other fast vector multiply
+    /// functions, such as [`vect_mul`].
+    ///
+    /// # Parameters
+    ///
+    /// * `c` - Constant to be multiplied by
+    /// * `gf_tbl` - Table array of 32 bytes to hold the pre-calculated constants
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `gf_tbl` is less than 32.
+    pub fn vect_mul_init(c: u8, gf_tbl: &mut [u8]) {
+        assert!(gf_tbl.len() >= 32, "gf table must hold 32 bytes");
+        unsafe {
+            erasure_isa_l_sys::gf_vect_mul_init(c, gf_tbl.as_mut_ptr());
+        }
+    }
+
+    /// GF(2^8) vector multiply by constant, runs appropriate version.
+    ///
+    /// Does a GF(2^8) vector multiply b = Ca where a and b are arrays and C
+    /// is a single field element in GF(2^8). Can be used for RAID6 and
+    /// scaling a buffer by a single coefficient. Function requires
+    /// pre-calculation of a 32 byte constant array based on the input
+    /// coefficient by [`vect_mul_init`].
+    ///
+    /// Unlike [`vect_dot_prod`] and [`vect_mad`], it takes a single source,
+    /// so there is no vector count or index.
+    ///
+    /// This function determines what instruction sets are enabled and
+    /// selects the appropriate version at runtime.
+    ///
+    /// # Parameters
+    ///
+    /// * `len` - Length of vector in bytes. Must be aligned to 32B.
+    /// * `gf_tbl` - Pointer to 32-byte array of pre-calculated constants based on C.
+    /// * `src` - Pointer to src data array.
+    /// * `dest` - Pointer to destination data array.
+    ///
+    /// # Returns
+    ///
+    /// * `true` - On success
+    /// * `false` - If `len` is negative or not aligned to 32B, and nothing is written
+    ///
+    /// # Panics
+    ///
+    /// Panics if `gf_tbl` holds less than 32 bytes, or `src` or `dest` holds less than `len` bytes.
+    pub fn vect_mul(len: i32, gf_tbl: &[u8], src: &[u8], dest: &mut [u8]) -> bool {
+        let Ok(len_usize) = usize::try_from(len) else {
+            return false;
+        };
+        assert!(gf_tbl.len() >= 32, "gf table must hold 32 bytes");
+        assert!(
+            src.len() >= len_usize && dest.len() >= len_usize,
+            "source and destination must hold len bytes"
+        );
+        let res = unsafe {
+            erasure_isa_l_sys::gf_vect_mul(
+                len,
+                gf_tbl.as_ptr() as *mut u8,
+                src.as_ptr() as *mut core::ffi::c_void,
+                dest.as_mut_ptr() as *mut core::ffi::c_void,
+            )
+        };
+        res == 0
+    }
+
+    /// XOR two byte slices into a third, which is the addition in GF(2^8).
+    ///
+    /// This is handy to compute the delta of an updated source block for
+    /// [`update`](crate::erasure::ErasureCode::update), that is the xor of the old and new data.
+    ///
+    /// # Errors
+    ///
+    /// * `Error::InvalidArguments` - If `a`, `b` and `dest` do not have the same length.
+    pub fn xor(a: &[u8], b: &[u8], dest: &mut [u8]) -> Result<(), crate::Error> {
+        if a.len() != b.len() || a.len() != dest.len() {
+            return Err(crate::Error::invalid_arguments(format!(
+                "xor operands must be equal, got {}, {} and destination {}",
+                a.len(),
+                b.len(),
+                dest.len()
+            )));
+        }
+        dest.iter_mut()
+            .zip(a.iter().zip(b))
+            .for_each(|(d, (a, b))| *d = a ^ b);
+        Ok(())
+    }
+
+    /// XOR a byte slice into another in place, which is the addition in GF(2^8).
+    ///
+    /// # Errors
+    ///
+    /// * `Error::InvalidArguments` - If `dest` and `src` do not have the same length.
+    pub fn xor_into(dest: &mut [u8], src: &[u8]) -> Result<(), crate::Error> {
+        if dest.len() != src.len() {
+            return Err(crate::Error::invalid_arguments(format!(
+                "xor operands must be equal, got destination {} and {}",
+                dest.len(),
+                src.len()
+            )));
+        }
+        dest.iter_mut().zip(src).for_each(|(d, s)| *d ^= s);
+        Ok(())
+    }
+
+    /// Multiply two matrices in GF(2^8), `out = a x b`.
+    ///
+    /// This composes the matrices of the codes, e.g. multiplying a transform into an encode matrix
+    /// passed to [`with_matrix`](crate::erasure::ErasureCode::with_matrix), with the same field
+    /// arithmetic as the rest of the crate.
+    ///
+    /// # Parameters
+    ///
+    /// * `a` - [a_rows x a_cols] matrix in row-major order
+    /// * `a_rows` - Number of rows of `a`
+    /// * `a_cols` - Number of columns of `a`, which is also the number of rows of `b`
+    /// * `b` - [a_cols x b_cols] matrix in row-major order
+    /// * `b_cols` - Number of columns of `b`
+    /// * `out` - [a_rows x b_cols] matrix to hold the product in row-major order
+    ///
+    /// # Errors
+    ///
+    /// * `Error::InvalidArguments` - If the length of `a`, `b` or `out` does not match its shape,
+    ///   including a shape whose size overflows.
+    pub fn mat_mul(
+        a: &[u8],
+        a_rows: usize,
+        a_cols: usize,
+        b: &[u8],
+        b_cols: usize,
+        out: &mut [u8],
+    ) -> Result<(), crate::Error> {
+        for (name, len, rows, cols) in [
+            ("a", a.len(), a_rows, a_cols),
+            ("b", b.len(), a_cols, b_cols),
+            ("out", out.len(), a_rows, b_cols),
+        ] {
+            if rows.checked_mul(cols) != Some(len) {
+                return Err(crate::Error::invalid_arguments(format!(
+                    "matrix {} length {} is not equal to {} x {}",
+                    name, len, rows, cols
+                )));
+            }
+        }
+        out.fill(0);
+        if a_cols == 0 || b_cols == 0 {
+            return Ok(());
+        }
+        for (a_row, out_row) in a.chunks_exact(a_cols).zip(out.chunks_exact_mut(b_cols)) {
+            for (coef, b_row) in a_row.iter().zip(b.chunks_exact(b_cols)) {
+                if *coef == 0 {
+                    continue;
+                }
+                out_row
+                    .iter_mut()
+                    .zip(b_row)
+                    .for_each(|(o, b)| *o ^= mul(*coef, *b));
+            }
+        }
+        Ok(())
+    }
 }
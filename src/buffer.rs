@@ -0,0 +1,113 @@
+//! This module provides byte buffers aligned for the SIMD kernels of isa-l.
+//!
+//! The kernels of `ec_encode_data`, such as `gf_4vect_dot_prod_avx2` and `gf_6vect_dot_prod_avx512`,
+//! and the kernels of `ec_encode_data_update`, such as `gf_vect_mad_avx2`, load and store the blocks
+//! a vector of 32 or 64 bytes at a time. They accept any alignment, but a block which is not aligned to
+//! 64 bytes makes some of the vectors straddle two cache lines, which costs an extra access on each of them.
+//! A `Vec<u8>` gives no alignment guarantee, so use [`AlignedBuf`] for the blocks of the hot paths.
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+
+/// The alignment in bytes of an [`AlignedBuf`], which is the width of an AVX-512 vector and a cache line.
+pub const ALIGNMENT: usize = 64;
+
+/// A chunk of the storage, whose alignment is the alignment of the buffer.
+#[derive(Clone, Copy)]
+#[repr(C, align(64))]
+struct Chunk([u8; ALIGNMENT]);
+
+/// AlignedBuf is a fixed length byte buffer whose start is aligned to [`ALIGNMENT`] bytes.
+///
+/// It derefs to `[u8]`, and can be used as a data or code block of [`ErasureCode`](crate::erasure::ErasureCode)
+/// wherever a `Vec<u8>` is accepted.
+///
+/// # Examples
+/// ```rust
+/// # use erasure_isa_l::buffer::{aligned_block, AlignedBuf, ALIGNMENT};
+/// # use erasure_isa_l::erasure::ErasureCode;
+/// # use std::num::NonZeroUsize;
+/// const BLOCK_LEN: usize = 1024;
+/// let k = NonZeroUsize::new(4).unwrap();
+/// let m = NonZeroUsize::new(2).unwrap();
+/// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+/// let data: Vec<AlignedBuf> = (0..k.get()).map(|i| AlignedBuf::from(&[i as u8; BLOCK_LEN][..])).collect();
+/// let mut parity: Vec<AlignedBuf> = (0..m.get()).map(|_| aligned_block(BLOCK_LEN)).collect();
+/// ec.encode(&data, &mut parity).expect("Encoding failed");
+/// assert!(parity.iter().all(|p| p.as_ptr() as usize % ALIGNMENT == 0));
+/// assert_eq!(parity.iter().map(|p| p.to_vec()).collect::<Vec<_>>(), ec.encode_to_owned(&data).unwrap());
+/// ```
+#[derive(Clone)]
+pub struct AlignedBuf {
+    chunks: Vec<Chunk>,
+    len: usize,
+}
+
+impl AlignedBuf {
+    /// Creates a new zeroed `AlignedBuf` of `len` bytes.
+    pub fn new(len: usize) -> Self {
+        Self {
+            chunks: vec![Chunk([0; ALIGNMENT]); len.div_ceil(ALIGNMENT)],
+            len,
+        }
+    }
+}
+
+/// Allocates a zeroed block of `len` bytes aligned to [`ALIGNMENT`] bytes.
+///
+/// This is a shorthand of [`AlignedBuf::new`].
+pub fn aligned_block(len: usize) -> AlignedBuf {
+    AlignedBuf::new(len)
+}
+
+impl Deref for AlignedBuf {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        // SAFETY: the chunks are plain bytes holding at least `len` bytes
+        unsafe { core::slice::from_raw_parts(self.chunks.as_ptr().cast(), self.len) }
+    }
+}
+
+impl DerefMut for AlignedBuf {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        // SAFETY: the chunks are plain bytes holding at least `len` bytes
+        unsafe { core::slice::from_raw_parts_mut(self.chunks.as_mut_ptr().cast(), self.len) }
+    }
+}
+
+impl AsRef<[u8]> for AlignedBuf {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl AsMut<[u8]> for AlignedBuf {
+    fn as_mut(&mut self) -> &mut [u8] {
+        self
+    }
+}
+
+impl From<&[u8]> for AlignedBuf {
+    /// Copies the bytes into a new `AlignedBuf`.
+    fn from(bytes: &[u8]) -> Self {
+        let mut buf = Self::new(bytes.len());
+        buf.copy_from_slice(bytes);
+        buf
+    }
+}
+
+impl PartialEq for AlignedBuf {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for AlignedBuf {}
+
+impl fmt::Debug for AlignedBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AlignedBuf").field(&&**self).finish()
+    }
+}
@@ -0,0 +1,257 @@
+//! This module provides caches of [`DecodeTable`]s, so that the tables of the recurring erasure
+//! patterns are generated only once.
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use crate::Error;
+use crate::erasure::{DecodeTable, ErasureCode};
+
+/// SharedDecodeCache is a thread-safe cache of [`DecodeTable`]s keyed by the erasure pattern.
+///
+/// The tables are generated lazily on the first decode of each erasure pattern, and shared
+/// among the threads through [`Arc`]. Lookups only take a read lock, and the write lock is only
+/// taken to insert the table of a new erasure pattern.
+///
+/// # Note
+/// The cache is keyed only by the erasure pattern, so it must be used with a single codec,
+/// or codecs which are [`compatible_with`](ErasureCode::compatible_with) each other.
+///
+/// The cache never evicts any table, and each table takes `32 * source_num() * erasures` bytes.
+/// Its memory grows with the number of distinct erasure patterns, up to the number of
+/// combinations of at most `code_num()` erased blocks. If the erasure patterns are not bounded
+/// in practice, use an [`LruDecodeCache`] instead.
+#[derive(Default)]
+pub struct SharedDecodeCache {
+    tables: RwLock<HashMap<Vec<usize>, Arc<DecodeTable>>>,
+}
+
+impl SharedDecodeCache {
+    /// Creates a new empty `SharedDecodeCache`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of cached tables.
+    pub fn len(&self) -> usize {
+        self.tables
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Returns `true` if no table is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all the cached tables.
+    pub fn clear(&self) {
+        self.tables
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    /// Returns the cached table of the erasures, generating and caching it on a miss.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::TooManyErasure` - If the number of erasures is larger than the code number.
+    /// * `Error::InvalidArguments` - If the erasure indices are out of range.
+    /// * `Error::Singular` - If the sub matrix of the surviving blocks is not invertible for the erasures.
+    pub fn get_or_make(
+        &self,
+        ec: &ErasureCode,
+        erasures: impl AsRef<[usize]>,
+    ) -> Result<Arc<DecodeTable>, Error> {
+        let erasures = sorted_erasures(erasures.as_ref());
+        // a poisoned lock still holds a consistent map, as each insertion is done at once
+        if let Some(table) = self
+            .tables
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&erasures)
+        {
+            return Ok(table.clone());
+        }
+        let mut tables = self.tables.write().unwrap_or_else(PoisonError::into_inner);
+        // another thread may have inserted the table before the write lock is acquired
+        if let Some(table) = tables.get(&erasures) {
+            return Ok(table.clone());
+        }
+        let table = Arc::new(ec.make_decode_table(&erasures)?);
+        tables.insert(erasures, table.clone());
+        Ok(table)
+    }
+
+    /// Decode the erased blocks with the cached table of the erasures.
+    ///
+    /// This works like [`ErasureCode::decode`], except that the decode table is taken from the cache,
+    /// or generated and cached on a miss.
+    ///
+    /// # Arguments
+    /// * `ec` - The erasure code.
+    /// * `data` - The data blocks.
+    /// * `code` - The code blocks.
+    /// * `erasures` - The indices of the erased blocks.
+    ///
+    /// # Returns
+    /// The sorted and deduplicated indices of the recovered blocks.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::TooManyErasure` - If the number of erasures is larger than the code number.
+    /// * `Error::InvalidArguments` - If the erasure indices are out of range.
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number,
+    ///   or the code blocks number is not equal to the code number.
+    /// * `Error::InvalidArguments` - If the input data or code blocks do not have the same length
+    /// * `Error::Singular` - If the sub matrix of the surviving blocks is not invertible for the erasures.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::cache::SharedDecodeCache;
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let cache = SharedDecodeCache::new();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    /// let mut erased_data = data.clone();
+    /// erased_data[2].fill(0);
+    /// let mut erased_parity = parity.clone();
+    /// cache.decode(&ec, &mut erased_data, &mut erased_parity, vec![2]).expect("Decoding failed");
+    /// assert_eq!(&data, &erased_data);
+    /// assert_eq!(cache.len(), 1);
+    /// ```
+    pub fn decode<U: AsMut<[u8]>>(
+        &self,
+        ec: &ErasureCode,
+        data: impl AsMut<[U]>,
+        code: impl AsMut<[U]>,
+        erasures: impl AsRef<[usize]>,
+    ) -> Result<Vec<usize>, Error> {
+        let erasures = erasures.as_ref();
+        let table = self.get_or_make(ec, erasures)?;
+        ec.decode_with_table(data, code, &table, erasures)
+    }
+}
+
+/// LruDecodeCache is a cache of [`DecodeTable`]s keyed by the erasure pattern, bounded by a capacity.
+///
+/// When the cache is full, the least recently used table is evicted to make room for a new one,
+/// so its memory is bounded by `capacity` tables of `32 * source_num() * erasures` bytes each.
+/// It is not thread-safe by itself, wrap it in a lock to share it, or use [`SharedDecodeCache`]
+/// if the erasure patterns are bounded.
+///
+/// An [`ErasureCode`] can hold one internally, see [`ErasureCode::set_cache_capacity`].
+///
+/// # Note
+/// The cache is keyed only by the erasure pattern, so it must be used with a single codec,
+/// or codecs which are [`compatible_with`](ErasureCode::compatible_with) each other.
+///
+/// # Examples
+/// ```rust
+/// # use erasure_isa_l::cache::LruDecodeCache;
+/// # use erasure_isa_l::erasure::ErasureCode;
+/// # use std::num::NonZeroUsize;
+/// let k = NonZeroUsize::new(4).unwrap();
+/// let m = NonZeroUsize::new(2).unwrap();
+/// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+/// let mut cache = LruDecodeCache::new(NonZeroUsize::new(2).unwrap());
+/// cache.get_or_make(&ec, [0]).unwrap();
+/// cache.get_or_make(&ec, [1]).unwrap();
+/// cache.get_or_make(&ec, [0]).unwrap();
+/// // the table of [1] is the least recently used one, and it is evicted
+/// cache.get_or_make(&ec, [2]).unwrap();
+/// assert_eq!(cache.len(), 2);
+/// assert!(cache.contains([0]));
+/// assert!(!cache.contains([1]));
+/// ```
+pub struct LruDecodeCache {
+    capacity: NonZeroUsize,
+    /// The cached tables with the tick of their last use.
+    tables: HashMap<Vec<usize>, (Arc<DecodeTable>, u64)>,
+    tick: u64,
+}
+
+impl LruDecodeCache {
+    /// Creates a new empty `LruDecodeCache` holding up to `capacity` tables.
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            capacity,
+            tables: HashMap::with_capacity(capacity.get()),
+            tick: 0,
+        }
+    }
+
+    /// Returns the maximum number of cached tables.
+    pub fn capacity(&self) -> NonZeroUsize {
+        self.capacity
+    }
+
+    /// Returns the number of cached tables.
+    pub fn len(&self) -> usize {
+        self.tables.len()
+    }
+
+    /// Returns `true` if no table is cached.
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+
+    /// Returns `true` if the table of the erasures is cached, without marking it as used.
+    pub fn contains(&self, erasures: impl AsRef<[usize]>) -> bool {
+        self.tables
+            .contains_key(&sorted_erasures(erasures.as_ref()))
+    }
+
+    /// Removes all the cached tables.
+    pub fn clear(&mut self) {
+        self.tables.clear();
+    }
+
+    /// Returns the cached table of the erasures, generating and caching it on a miss.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::TooManyErasure` - If the number of erasures is larger than the code number.
+    /// * `Error::InvalidArguments` - If the erasure indices are out of range.
+    /// * `Error::Singular` - If the sub matrix of the surviving blocks is not invertible for the erasures.
+    pub fn get_or_make(
+        &mut self,
+        ec: &ErasureCode,
+        erasures: impl AsRef<[usize]>,
+    ) -> Result<Arc<DecodeTable>, Error> {
+        let erasures = sorted_erasures(erasures.as_ref());
+        self.tick += 1;
+        if let Some((table, tick)) = self.tables.get_mut(&erasures) {
+            *tick = self.tick;
+            return Ok(table.clone());
+        }
+        let table = Arc::new(ec.make_decode_table(&erasures)?);
+        if self.tables.len() == self.capacity.get() {
+            // evict the least recently used table
+            let lru = self
+                .tables
+                .iter()
+                .min_by_key(|(_, (_, tick))| *tick)
+                .map(|(erasures, _)| erasures.clone())
+                .unwrap();
+            self.tables.remove(&lru);
+        }
+        self.tables.insert(erasures, (table.clone(), self.tick));
+        Ok(table)
+    }
+}
+
+/// Returns a sorted and deduplicated copy of the erasures, which is the key of the caches.
+fn sorted_erasures(erasures: &[usize]) -> Vec<usize> {
+    let mut erasures = erasures.to_vec();
+    erasures.sort_unstable();
+    erasures.dedup();
+    erasures
+}
@@ -0,0 +1,196 @@
+//! This module provides an integrity layer on top of [`ErasureCode`] to detect the corrupted blocks.
+//!
+//! An erasure code recovers the erasures, that is, the blocks known to be lost, but it cannot tell
+//! which block is silently corrupted. With a CRC32 checksum stored alongside each block, the blocks
+//! whose checksums mismatch are detected, treated as erasures and reconstructed,
+//! so up to `code_num()` corrupted blocks are corrected.
+use alloc::format;
+use alloc::vec::Vec;
+
+use crate::Error;
+use crate::erasure::ErasureCode;
+
+/// ChecksumedCode is an erasure code which corrects the corrupted blocks, detected by their CRC32 checksums.
+///
+/// The checksums are computed by [`encode_with_checksums`](Self::encode_with_checksums),
+/// one for each block in the order of the source data blocks followed by the code blocks,
+/// and are expected to be stored apart from the blocks or along with them.
+///
+/// # Note
+/// The checksums themselves are trusted. A corrupted checksum makes the intact block it belongs to
+/// be reconstructed needlessly, which still succeeds if the other corruptions are within `code_num()`.
+///
+/// # Examples
+/// ```rust
+/// # use erasure_isa_l::checksum::ChecksumedCode;
+/// # use erasure_isa_l::erasure::ErasureCode;
+/// # use std::num::NonZeroUsize;
+/// const BLOCK_LEN: usize = 1024;
+/// let k = NonZeroUsize::new(4).unwrap();
+/// let m = NonZeroUsize::new(2).unwrap();
+/// let code = ChecksumedCode::new(ErasureCode::with_reed_solomon(k, m).unwrap());
+/// let mut data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+/// let mut parity = vec![vec![0_u8; BLOCK_LEN]; m.get()];
+/// let checksums = code.encode_with_checksums(&data, &mut parity).expect("Encoding failed");
+/// let orig = data.clone();
+/// // Corrupt a data block and a parity block silently
+/// data[1][7] ^= 0x01;
+/// parity[0][42] ^= 0x80;
+/// let corrected = code.decode(&mut data, &mut parity, &checksums).expect("Decoding failed");
+/// assert_eq!(corrected, vec![1, 4]);
+/// assert_eq!(data, orig);
+/// ```
+pub struct ChecksumedCode {
+    ec: ErasureCode,
+}
+
+impl ChecksumedCode {
+    /// Creates a new `ChecksumedCode` over the erasure code `ec`.
+    pub fn new(ec: ErasureCode) -> Self {
+        Self { ec }
+    }
+
+    /// Returns the underlying erasure code.
+    pub fn codec(&self) -> &ErasureCode {
+        &self.ec
+    }
+
+    /// Consumes the `ChecksumedCode` and returns the underlying erasure code.
+    pub fn into_inner(self) -> ErasureCode {
+        self.ec
+    }
+
+    /// Returns the CRC32 checksum of a block.
+    pub fn checksum(block: &[u8]) -> u32 {
+        crc32fast::hash(block)
+    }
+
+    /// Encodes the source data into code blocks, and returns the checksums of all the blocks.
+    ///
+    /// # Arguments
+    /// * `data` - The source data blocks to be encoded.
+    /// * `code` - The code blocks to be filled with the encoded data.
+    ///
+    /// # Returns
+    /// The `block_num()` checksums of the source data blocks followed by the code blocks.
+    ///
+    /// # Errors
+    /// The same as [`ErasureCode::encode`].
+    pub fn encode_with_checksums<T: AsRef<[u8]>, U: AsMut<[u8]>>(
+        &self,
+        data: impl AsRef<[T]>,
+        mut code: impl AsMut<[U]>,
+    ) -> Result<Vec<u32>, Error> {
+        self.ec.encode(&data, &mut code)?;
+        let data = data.as_ref().iter().map(|b| Self::checksum(b.as_ref()));
+        let code = code.as_mut().iter_mut().map(|b| Self::checksum(b.as_mut()));
+        Ok(data.chain(code).collect())
+    }
+
+    /// Verifies the blocks against their checksums, and returns the indices of the corrupted blocks.
+    ///
+    /// # Arguments
+    /// * `data` - The source data blocks.
+    /// * `code` - The code blocks.
+    /// * `checksums` - The checksums returned by [`encode_with_checksums`](Self::encode_with_checksums).
+    ///
+    /// # Returns
+    /// The sorted indices of the blocks whose checksums mismatch.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number,
+    ///   or the code blocks number is not equal to the code number.
+    /// * `Error::InvalidArguments` - If the checksums number is not equal to the block number.
+    pub fn verify<T: AsRef<[u8]>>(
+        &self,
+        data: impl AsRef<[T]>,
+        code: impl AsRef<[T]>,
+        checksums: impl AsRef<[u32]>,
+    ) -> Result<Vec<usize>, Error> {
+        let (data, code, checksums) = (data.as_ref(), code.as_ref(), checksums.as_ref());
+        self.check_blocks(data.len(), code.len(), checksums.len())?;
+        Ok(data
+            .iter()
+            .chain(code.iter())
+            .zip(checksums)
+            .enumerate()
+            .filter(|(_, (block, checksum))| Self::checksum(block.as_ref()) != **checksum)
+            .map(|(i, _)| i)
+            .collect())
+    }
+
+    /// Detects the corrupted blocks by their checksums, and reconstructs them as erasures.
+    ///
+    /// The content of the corrupted blocks will be recovered and written back,
+    /// and the content of the intact blocks will not be changed.
+    ///
+    /// # Arguments
+    /// * `data` - The source data blocks.
+    /// * `code` - The code blocks.
+    /// * `checksums` - The checksums returned by [`encode_with_checksums`](Self::encode_with_checksums).
+    ///
+    /// # Returns
+    /// The sorted indices of the corrected blocks, empty if all the blocks are intact.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::TooManyErasure` - If the number of corrupted blocks is larger than the code number.
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number,
+    ///   or the code blocks number is not equal to the code number.
+    /// * `Error::InvalidArguments` - If the checksums number is not equal to the block number.
+    /// * `Error::InvalidArguments` - If the input data or code blocks do not have the same length.
+    /// * `Error::Singular` - If the sub matrix of the intact blocks is not invertible for the corrupted ones.
+    pub fn decode<U: AsMut<[u8]>>(
+        &self,
+        mut data: impl AsMut<[U]>,
+        mut code: impl AsMut<[U]>,
+        checksums: impl AsRef<[u32]>,
+    ) -> Result<Vec<usize>, Error> {
+        let (data, code, checksums) = (data.as_mut(), code.as_mut(), checksums.as_ref());
+        self.check_blocks(data.len(), code.len(), checksums.len())?;
+        let corrupted = data
+            .iter_mut()
+            .chain(code.iter_mut())
+            .map(|block| Self::checksum(block.as_mut()))
+            .zip(checksums)
+            .enumerate()
+            .filter(|(_, (actual, checksum))| actual != *checksum)
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        self.ec.decode(data, code, corrupted)
+    }
+}
+
+/// private implementation of ChecksumedCode
+impl ChecksumedCode {
+    fn check_blocks(
+        &self,
+        data_num: usize,
+        code_num: usize,
+        checksum_num: usize,
+    ) -> Result<(), Error> {
+        if data_num != self.ec.source_num() {
+            return Err(Error::invalid_arguments(format!(
+                "data length {} is not equal to source number {}",
+                data_num,
+                self.ec.source_num()
+            )));
+        }
+        if code_num != self.ec.code_num() {
+            return Err(Error::invalid_arguments(format!(
+                "code length {} is not equal to code number {}",
+                code_num,
+                self.ec.code_num()
+            )));
+        }
+        if checksum_num != self.ec.block_num() {
+            return Err(Error::invalid_arguments(format!(
+                "checksums length {} is not equal to block number {}",
+                checksum_num,
+                self.ec.block_num()
+            )));
+        }
+        Ok(())
+    }
+}
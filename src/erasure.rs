@@ -2,9 +2,26 @@
 //!
 //! It allows users to encode and decode data with erasure codes, handling the complexities of the underlying `isa-l` library.
 //! And it do more checks to ensure the input data is valid.
-use std::num::NonZeroUsize;
+use alloc::format;
+use alloc::string::ToString;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::num::NonZeroUsize;
+use core::ops::Range;
+#[cfg(feature = "std")]
+use std::io;
+#[cfg(feature = "std")]
+use std::sync::{Mutex, PoisonError};
+#[cfg(feature = "std")]
+use std::time::Instant;
 
-use crate::{Error, galois};
+pub use crate::Result;
+#[cfg(feature = "std")]
+use crate::cache::LruDecodeCache;
+use crate::{Error, galois, reference};
 use crate::{ec, gf};
 
 /// ErasureCode is a struct that implements erasure coding by isa-l.
@@ -13,46 +30,579 @@ use crate::{ec, gf};
 /// and do not require the user to deal with the low-level details of the isa-l library.
 ///
 /// It do some checks to ensure the input data is valid, and will return an error if the input is invalid.
+///
+/// # Thread Safety
+/// `ErasureCode` is `Send` and `Sync`, so a single codec can be shared by threads, e.g. in an [`Arc`],
+/// to encode and decode their own buffers concurrently. The matrix and the gf tables are never mutated after
+/// the construction, and the block pointers passed to isa-l live only within each call. The only interior
+/// mutability is the opt-in decode cache, which is guarded by a lock, see [`set_cache_capacity`](Self::set_cache_capacity).
 pub struct ErasureCode {
     k: i32,
     m: i32,
     encode_matrix: Vec<u8>,
     encode_gf_table: galois::GaloisFiledTable,
+    /// The kind of the encode matrix, `None` if it is supplied by the user.
+    matrix_kind: Option<MatrixKind>,
+    /// The opt-in cache of the decode tables, see `set_cache_capacity`.
+    #[cfg(feature = "std")]
+    decode_cache: Option<Mutex<LruDecodeCache>>,
 }
 
-enum CodeType {
+// Sharing a codec or a decode table between threads is part of the API.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<ErasureCode>();
+    assert_send_sync::<DecodeTable>();
+};
+
+/// The kind of the encode matrix of an [`ErasureCode`], see [`ErasureCodeBuilder::matrix_kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MatrixKind {
+    /// The matrix of `gf_gen_rs_matrix`, see [`ErasureCode::with_reed_solomon`].
+    #[default]
     ReedSolomon,
+    /// The matrix of `gf_gen_rs_matrix` with the rows breaking invertibility adjusted,
+    /// see [`ErasureCode::with_adjusted_reed_solomon`].
+    AdjustedReedSolomon,
+    /// The matrix of `gf_gen_rs_matrix`, see [`ErasureCode::with_vandermonde`].
+    Vandermonde,
+    /// The matrix of `gf_gen_cauchy1_matrix`, see [`ErasureCode::with_cauchy`].
     Cauchy,
 }
 
+/// The role of a block index in `0..block_num()` of an [`ErasureCode`], see [`ErasureCode::block_role`].
+///
+/// The data blocks take the indices `0..source_num()`, and the code blocks follow them,
+/// so the code block `i` in the `code` argument of the methods is at the index `source_num() + i`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlockRole {
+    /// The source data block at the index in `0..source_num()`.
+    Data(usize),
+    /// The code block at the index in `0..code_num()`.
+    Parity(usize),
+}
+
+impl fmt::Display for BlockRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Data(i) => write!(f, "data block {}", i),
+            Self::Parity(i) => write!(f, "parity block {}", i),
+        }
+    }
+}
+
+/// The length of the chunks that a recovered block is computed in when it is streamed to a writer.
+#[cfg(feature = "std")]
+const DECODE_CHUNK_LEN: usize = 64 * 1024;
+
+/// The length in bytes of each block gathered at a time by [`ErasureCode::encode_interleaved`].
+const INTERLEAVE_WINDOW_LEN: usize = 4 * 1024;
+
+/// The minimum vector length in bytes accepted by the SIMD kernels of isa-l,
+/// such as `gf_vect_mad` used by `ec_encode_data_update`.
+const MIN_VECT_LEN: usize = 64;
+
+/// The maximum number of parity sub matrices inverted when adjusting a Reed-Solomon matrix.
+///
+/// Each sub matrix costs a small matrix inversion, so the codes needing more fail to be adjusted.
+const RS_CHECK_LIMIT: usize = 1 << 16;
+
+/// The maximum number of blocks (source + code) supported by an erasure code over GF(2^8).
+pub const MAX_BLOCK_NUM: usize = 255;
+
+/// The default fraction of the source data blocks changed at which [`ErasureCode::apply_writes`]
+/// re-encodes the stripe instead of updating the parities by the deltas.
+pub const DEFAULT_REENCODE_RATIO: f64 = 0.5;
+
+/// Checks whether a pair of `source_num` and `code_num` is a valid erasure code geometry.
+///
+/// A geometry is valid if both numbers are at least 1 and the total number of blocks
+/// does not exceed [`MAX_BLOCK_NUM`].
+///
+/// This is a `const fn`, so it can be used to reject an invalid geometry at compile time.
+///
+/// # Examples
+/// ```rust
+/// use erasure_isa_l::erasure::validate_geometry;
+/// const K: usize = 10;
+/// const M: usize = 4;
+/// const _: () = assert!(validate_geometry(K, M), "invalid erasure code geometry");
+/// ```
+///
+/// An invalid geometry fails to compile:
+/// ```compile_fail
+/// use erasure_isa_l::erasure::validate_geometry;
+/// const K: usize = 200;
+/// const M: usize = 100;
+/// const _: () = assert!(validate_geometry(K, M), "invalid erasure code geometry");
+/// ```
+pub const fn validate_geometry(source_num: usize, code_num: usize) -> bool {
+    source_num >= 1
+        && code_num >= 1
+        && code_num <= MAX_BLOCK_NUM
+        && source_num <= MAX_BLOCK_NUM - code_num
+}
+
 /// DecodeTable is a struct that contains the decode table for acclerating coding.
 ///
 /// It is generated by the [`ErasureCode::make_decode_table`].
-pub struct DecodeTable(crate::galois::GaloisFiledTable);
+///
+/// With the `serde` feature enabled, it can be serialized and deserialized, so that the tables
+/// of the recurring erasure patterns can be persisted instead of being generated on each boot.
+/// The serialized form carries a magic and a format version, as well as the geometry and the
+/// matrix of the code which generated it, so that a table can not silently be used with
+/// a mismatched codec.
+///
+/// The table also records the erasures it is generated for, and [`ErasureCode::decode_with_table`]
+/// rejects a table generated for other erasures, or by a code of another matrix.
+pub struct DecodeTable {
+    source_num: usize,
+    code_num: usize,
+    /// The sorted and deduplicated erasures the table is generated for.
+    erasures: Vec<usize>,
+    /// The matrix kind and the digest of a user supplied matrix of the code which generated the table,
+    /// `None` if the code is unknown, as for a table restored by [`from_bytes`](Self::from_bytes).
+    matrix: Option<(Option<MatrixKind>, Option<u64>)>,
+    table: crate::galois::GaloisFiledTable,
+}
+
+impl DecodeTable {
+    /// Creates an empty `DecodeTable` for a `(source_num, code_num)` code, with the capacity
+    /// of the table of up to `code_num` erasures.
+    ///
+    /// The table holds no erasures until it is filled by [`ErasureCode::regenerate_decode_table`],
+    /// which then reuses its buffers instead of allocating new ones.
+    pub fn with_capacity(source_num: usize, code_num: usize) -> Self {
+        Self {
+            source_num,
+            code_num,
+            erasures: Vec::with_capacity(code_num),
+            matrix: None,
+            table: Vec::with_capacity(32 * source_num * code_num).into(),
+        }
+    }
+
+    /// Returns the sorted and deduplicated indices of the erased blocks the table is generated for.
+    pub fn erasures(&self) -> &[usize] {
+        &self.erasures
+    }
+
+    /// Returns the raw bytes of the gf table, which hold `32 * source_num` bytes for each erasure.
+    ///
+    /// Together with the geometry of the code and [`erasures`](Self::erasures), the bytes can be
+    /// persisted in a custom format and restored by [`from_bytes`](Self::from_bytes).
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.table
+    }
+
+    /// Restores a `DecodeTable` from the raw bytes returned by [`as_bytes`](Self::as_bytes).
+    ///
+    /// # Arguments
+    /// * `bytes` - The raw bytes of the gf table.
+    /// * `source_num` - The number of source data blocks of the code which generated the table.
+    /// * `code_num` - The number of code blocks of the code which generated the table.
+    /// * `erasures` - The sorted and deduplicated erasures the table is generated for.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::InvalidArguments` - If the geometry is not valid, see [`validate_geometry`].
+    /// * `Error::InvalidArguments` - If the erasures are not sorted and deduplicated, out of range,
+    ///   or more than `code_num`.
+    /// * `Error::InvalidArguments` - If the length of `bytes` is not `32 * source_num * erasures.len()`,
+    ///   e.g. the bytes are truncated.
+    ///
+    /// # Note
+    /// The content of the bytes can not be checked, a corrupted table decodes to wrong blocks.
+    /// Neither can the matrix of the code which generated the table, so the restored table
+    /// is accepted by any code of the geometry.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::{DecodeTable, ErasureCode};
+    /// # use std::num::NonZeroUsize;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let table = ec.make_decode_table([1, 4]).unwrap();
+    /// let bytes = table.as_bytes().to_vec();
+    /// let restored = DecodeTable::from_bytes(bytes.clone(), 4, 2, table.erasures()).unwrap();
+    /// assert_eq!(restored.as_bytes(), table.as_bytes());
+    /// // truncated bytes are rejected
+    /// assert!(DecodeTable::from_bytes(bytes[1..].to_vec(), 4, 2, [1, 4]).is_err());
+    /// ```
+    pub fn from_bytes(
+        bytes: Vec<u8>,
+        source_num: usize,
+        code_num: usize,
+        erasures: impl AsRef<[usize]>,
+    ) -> Result<Self, Error> {
+        let erasures = erasures.as_ref();
+        if !validate_geometry(source_num, code_num) {
+            return Err(Error::invalid_arguments(format!(
+                "invalid decode table geometry ({}, {})",
+                source_num, code_num
+            )));
+        }
+        if erasures.len() > code_num
+            || erasures.windows(2).any(|w| w[0] >= w[1])
+            || erasures.last().is_some_and(|e| *e >= source_num + code_num)
+        {
+            return Err(Error::invalid_arguments(format!(
+                "invalid decode table erasures {:?}",
+                erasures
+            )));
+        }
+        // each erasure takes a row of 32 bytes per source block
+        let row_len = 32 * source_num;
+        if bytes.len() != row_len * erasures.len() {
+            return Err(Error::invalid_arguments(format!(
+                "invalid decode table length {}, expected {}",
+                bytes.len(),
+                row_len * erasures.len()
+            )));
+        }
+        Ok(Self {
+            source_num,
+            code_num,
+            erasures: erasures.to_vec(),
+            matrix: None,
+            table: bytes.into(),
+        })
+    }
+}
+
+/// EncodeScratch holds the reusable buffers which gather the block pointers for encoding.
+///
+/// The block pointers have to be gathered on each call since the buffers may move, but the capacity
+/// of the pointer buffers can be reused across the calls of [`ErasureCode::encode_into`], to avoid
+/// the allocations when encoding many small stripes in a tight loop.
+#[derive(Debug, Default)]
+pub struct EncodeScratch {
+    data_ptrs: Vec<*const u8>,
+    code_ptrs: Vec<*mut u8>,
+}
+
+// SAFETY: the pointer buffers are only read within a single encoding, which clears them on entry
+// before gathering the pointers of its own blocks. A pointer left behind by a panicking encoding
+// is never dereferenced, so sharing or sending an `EncodeScratch` never exposes the blocks.
+unsafe impl Send for EncodeScratch {}
+unsafe impl Sync for EncodeScratch {}
+
+impl EncodeScratch {
+    /// Creates a new `EncodeScratch` with the capacity for the blocks of `ec`.
+    pub fn new(ec: &ErasureCode) -> Self {
+        Self {
+            data_ptrs: Vec::with_capacity(ec.source_num()),
+            code_ptrs: Vec::with_capacity(ec.code_num()),
+        }
+    }
+}
+
+/// EncodeContext encodes many stripes of a codec, reusing its pointer buffers across the calls.
+///
+/// It is created by [`ErasureCode::encode_context`], and borrows the codec immutably,
+/// so each thread can own a context of a shared codec.
+/// It works like [`ErasureCode::encode_into`] with an owned [`EncodeScratch`].
+///
+/// # Examples
+/// ```rust
+/// # use erasure_isa_l::erasure::ErasureCode;
+/// # use std::num::NonZeroUsize;
+/// const BLOCK_LEN: usize = 64;
+/// let k = NonZeroUsize::new(4).unwrap();
+/// let m = NonZeroUsize::new(2).unwrap();
+/// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+/// let mut ctx = ec.encode_context();
+/// let mut parity: Vec<Vec<u8>> = vec![vec![0u8; BLOCK_LEN]; m.get()];
+/// for i in 0..16 {
+///     let data: Vec<Vec<u8>> = (0..k.get()).map(|j| vec![(i + j) as u8; BLOCK_LEN]).collect();
+///     ctx.encode(&data, &mut parity).expect("Encoding failed");
+///     assert_eq!(parity, ec.encode_to_owned(&data).unwrap());
+/// }
+/// ```
+pub struct EncodeContext<'a> {
+    ec: &'a ErasureCode,
+    scratch: EncodeScratch,
+}
+
+impl<'a> EncodeContext<'a> {
+    /// Returns the codec the context encodes with.
+    pub fn codec(&self) -> &'a ErasureCode {
+        self.ec
+    }
+
+    /// Encodes the source data into code blocks, see [`ErasureCode::encode`].
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number,
+    ///   or the code blocks number is not equal to the code number.
+    /// * `Error::InvalidArguments` - If the input data or code blocks do not have the same length.
+    pub fn encode<T: AsRef<[u8]>, U: AsMut<[u8]>>(
+        &mut self,
+        data: impl AsRef<[T]>,
+        code: impl AsMut<[U]>,
+    ) -> Result<(), Error> {
+        self.ec.encode_into(data, code, &mut self.scratch)
+    }
+}
+
+/// DecodePlan describes which surviving blocks are read to recover the erased blocks.
+///
+/// It is generated by the [`ErasureCode::plan_decode_zoned`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodePlan {
+    erasures: Vec<usize>,
+    survivors: Vec<usize>,
+}
+
+impl DecodePlan {
+    /// Returns the sorted and deduplicated indices of the erased blocks.
+    pub fn erasures(&self) -> &[usize] {
+        &self.erasures
+    }
+
+    /// Returns the sorted indices of the `source_num()` surviving blocks to be read.
+    pub fn survivors(&self) -> &[usize] {
+        &self.survivors
+    }
+}
+
+/// DecodeOutcome reports which erased blocks are recovered by [`ErasureCode::decode_best_effort`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeOutcome {
+    recovered: Vec<usize>,
+    unrecovered: Vec<usize>,
+}
+
+impl DecodeOutcome {
+    /// Returns the sorted indices of the recovered blocks.
+    pub fn recovered(&self) -> &[usize] {
+        &self.recovered
+    }
+
+    /// Returns the sorted indices of the erased blocks which cannot be recovered from the surviving blocks.
+    pub fn unrecovered(&self) -> &[usize] {
+        &self.unrecovered
+    }
+
+    /// Returns `true` if all the erased blocks are recovered.
+    pub fn is_complete(&self) -> bool {
+        self.unrecovered.is_empty()
+    }
+}
+
+/// DecodeStats reports where the time of a decoding is spent, see [`ErasureCode::decode_with_stats`].
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DecodeStats {
+    table_gen_ns: u64,
+    compute_ns: u64,
+    bytes_processed: u64,
+}
+
+#[cfg(feature = "std")]
+impl DecodeStats {
+    /// Returns the nanoseconds spent generating the decode table.
+    pub fn table_gen_ns(&self) -> u64 {
+        self.table_gen_ns
+    }
+
+    /// Returns the nanoseconds spent computing the erased blocks with the decode table.
+    pub fn compute_ns(&self) -> u64 {
+        self.compute_ns
+    }
+
+    /// Returns the number of bytes recovered, that is the block length times the number of erasures.
+    pub fn bytes_processed(&self) -> u64 {
+        self.bytes_processed
+    }
+}
+
+/// CodecKey identifies the configuration of an [`ErasureCode`], see [`ErasureCode::key`].
+///
+/// Two codecs with equal keys have the same encode matrix, so a key can be used to look up
+/// the codecs or the decode tables in a map, without hashing the matrix or the gf tables.
+///
+/// # Note
+/// The codecs created from a user supplied matrix have no matrix kind, so their keys carry the
+/// encode matrix itself instead, which is compared by value and only hashed as a 64-bit digest.
+/// Such a key can only be taken from the codec by [`ErasureCode::key`].
+///
+/// # Examples
+/// ```rust
+/// # use erasure_isa_l::erasure::{CodecKey, ErasureCode, MatrixKind};
+/// # use std::collections::HashMap;
+/// # use std::num::NonZeroUsize;
+/// let k = NonZeroUsize::new(4).unwrap();
+/// let m = NonZeroUsize::new(2).unwrap();
+/// let mut registry = HashMap::new();
+/// let ec = ErasureCode::with_cauchy(k, m).unwrap();
+/// registry.insert(ec.key(), ec);
+/// let key = CodecKey::new(4, 2, Some(MatrixKind::Cauchy));
+/// assert!(registry.contains_key(&key));
+/// assert!(!registry.contains_key(&CodecKey::new(4, 2, Some(MatrixKind::ReedSolomon))));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodecKey {
+    source_num: usize,
+    code_num: usize,
+    matrix_kind: Option<MatrixKind>,
+    matrix_digest: Option<u64>,
+    matrix: Option<Arc<[u8]>>,
+}
+
+impl Hash for CodecKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // the equal matrices have equal digests, so the matrix itself is not hashed
+        self.source_num.hash(state);
+        self.code_num.hash(state);
+        self.matrix_kind.hash(state);
+        self.matrix_digest.hash(state);
+    }
+}
+
+impl CodecKey {
+    /// Creates a new `CodecKey` of a configuration.
+    ///
+    /// A key with `None` as the matrix kind matches no codec, since the key of a codec with
+    /// a user supplied matrix also carries the digest of the matrix.
+    pub fn new(source_num: usize, code_num: usize, matrix_kind: Option<MatrixKind>) -> Self {
+        Self {
+            source_num,
+            code_num,
+            matrix_kind,
+            matrix_digest: None,
+            matrix: None,
+        }
+    }
+
+    /// Returns the number of source data blocks.
+    pub fn source_num(&self) -> usize {
+        self.source_num
+    }
+
+    /// Returns the number of code blocks.
+    pub fn code_num(&self) -> usize {
+        self.code_num
+    }
+
+    /// Returns the kind of the encode matrix, `None` for a user supplied matrix.
+    pub fn matrix_kind(&self) -> Option<MatrixKind> {
+        self.matrix_kind
+    }
+}
+
+/// ErasureCodeBuilder configures and creates an [`ErasureCode`].
+///
+/// The source number and the code number must be set, the other options have defaults:
+/// * `matrix_kind` - [`MatrixKind::ReedSolomon`].
+/// * `cache_capacity` - 0, the decode cache is disabled, see [`ErasureCode::set_cache_capacity`].
+///
+/// # Examples
+/// ```rust
+/// # use erasure_isa_l::erasure::{ErasureCodeBuilder, MatrixKind};
+/// let ec = ErasureCodeBuilder::new()
+///     .source_num(4)
+///     .code_num(2)
+///     .matrix_kind(MatrixKind::Cauchy)
+///     .cache_capacity(16)
+///     .build()
+///     .unwrap();
+/// assert_eq!(ec.source_num(), 4);
+/// assert_eq!(ec.code_num(), 2);
+/// assert_eq!(ec.cache_capacity(), 16);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ErasureCodeBuilder {
+    source_num: usize,
+    code_num: usize,
+    matrix_kind: MatrixKind,
+    #[cfg(feature = "std")]
+    cache_capacity: usize,
+}
+
+impl ErasureCodeBuilder {
+    /// Creates a new `ErasureCodeBuilder` with the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of source data blocks.
+    pub fn source_num(mut self, source_num: usize) -> Self {
+        self.source_num = source_num;
+        self
+    }
+
+    /// Sets the number of code blocks.
+    pub fn code_num(mut self, code_num: usize) -> Self {
+        self.code_num = code_num;
+        self
+    }
+
+    /// Sets the kind of the encode matrix.
+    pub fn matrix_kind(mut self, matrix_kind: MatrixKind) -> Self {
+        self.matrix_kind = matrix_kind;
+        self
+    }
+
+    /// Sets the capacity of the decode cache, 0 disables it.
+    #[cfg(feature = "std")]
+    pub fn cache_capacity(mut self, capacity: usize) -> Self {
+        self.cache_capacity = capacity;
+        self
+    }
+
+    /// Creates the `ErasureCode` with the options.
+    ///
+    /// # Errors
+    /// * `Error::InvalidArguments` - If the geometry is not valid, see [`validate_geometry`],
+    ///   including the source number or the code number being not set.
+    /// * `Error::InternalError` - If no invertible matrix can be found by adjusting the parity rows
+    ///   of [`MatrixKind::AdjustedReedSolomon`].
+    pub fn build(&self) -> Result<ErasureCode, Error> {
+        #[allow(unused_mut)]
+        let mut ec = ErasureCode::new(self.source_num, self.code_num, self.matrix_kind)?;
+        #[cfg(feature = "std")]
+        ec.set_cache_capacity(self.cache_capacity);
+        Ok(ec)
+    }
+}
 
 impl ErasureCode {
+    /// Returns a new [`ErasureCodeBuilder`] to configure and create an `ErasureCode`.
+    pub fn builder() -> ErasureCodeBuilder {
+        ErasureCodeBuilder::new()
+    }
+
     /// Creates a new `ErasureCode` instance with cauchy matrix.
     ///
     /// # Arguments
     /// * `source_num` - The number of source data blocks.
     /// * `code_num` - The number of code blocks.
     ///
+    /// # Errors
+    /// * `Error::InvalidArguments` - If `source_num + code_num` exceeds [`MAX_BLOCK_NUM`].
+    ///
     /// # Note
     /// Any sub matrix from a cauchy matrix is always invertable, and it is suitable for large pairs
     /// of `source_num` and `code_num`.
     pub fn with_cauchy(source_num: NonZeroUsize, code_num: NonZeroUsize) -> Result<Self, Error> {
-        Self::new(
-            source_num.get().try_into().unwrap(),
-            code_num.get().try_into().unwrap(),
-            CodeType::Cauchy,
-        )
+        Self::builder()
+            .source_num(source_num.get())
+            .code_num(code_num.get())
+            .matrix_kind(MatrixKind::Cauchy)
+            .build()
     }
 
     /// Creates a new `ErasureCode` instance with reed solomon matrix.
     ///
     /// # Arguments
     /// * `source_num` - The number of source data blocks.
-    /// * `code_num` - The number of code blocks.  
+    /// * `code_num` - The number of code blocks.
+    ///
+    /// # Errors
+    /// * `Error::InvalidArguments` - If `source_num + code_num` exceeds [`MAX_BLOCK_NUM`].
     ///
     /// # Note
     /// For large pairs of `source_num` and `code_num`, it is possible to find
@@ -66,17 +616,206 @@ impl ErasureCode {
     /// * `source_num`= 21, `code_num` =4
     /// * `code_num` <= 3
     ///
-    /// If your pair does not satisfy the above inequalities, and you don't want to adjust it,
-    /// you can use the [`with_cauchy`](Self::with_cauchy) method instead, which is always invertable.
+    /// If your pair does not satisfy the above inequalities, you can use the
+    /// [`with_adjusted_reed_solomon`](Self::with_adjusted_reed_solomon) method to adjust it,
+    /// as long as the pair is small enough to be checked, or the [`with_cauchy`](Self::with_cauchy)
+    /// method instead, which is always invertable.
     pub fn with_reed_solomon(
         source_num: NonZeroUsize,
         code_num: NonZeroUsize,
     ) -> Result<Self, Error> {
-        Self::new(
-            source_num.get().try_into().unwrap(),
-            code_num.get().try_into().unwrap(),
-            CodeType::ReedSolomon,
-        )
+        Self::builder()
+            .source_num(source_num.get())
+            .code_num(code_num.get())
+            .matrix_kind(MatrixKind::ReedSolomon)
+            .build()
+    }
+
+    /// Creates a new `ErasureCode` instance with reed solomon matrix, whose parity rows breaking
+    /// invertibility are adjusted.
+    ///
+    /// # Arguments
+    /// * `source_num` - The number of source data blocks.
+    /// * `code_num` - The number of code blocks.
+    ///
+    /// # Errors
+    /// * `Error::InvalidArguments` - If `source_num + code_num` exceeds [`MAX_BLOCK_NUM`].
+    /// * `Error::InternalError` - If no invertible matrix can be found by adjusting the parity rows,
+    ///   or the pair is too large to be checked.
+    ///
+    /// # Note
+    /// For the pairs of `source_num` and `code_num` listed in
+    /// [`with_reed_solomon`](Self::with_reed_solomon), the matrix is the same as the one of it.
+    /// Otherwise, every erasure pattern is checked, and a parity row which makes any decode matrix
+    /// singular is regenerated with the next power of the generator as its base, until all the
+    /// decode matrices are invertable. So the matrix, and the code blocks encoded with it,
+    /// may differ from the ones of [`with_reed_solomon`](Self::with_reed_solomon).
+    ///
+    /// The check inverts at most 65536 sub matrices of the parity rows, which covers codes such as
+    /// `(20, 4)`. A code needing more, or whose matrix cannot be adjusted, such as `(10, 6)`, fails
+    /// with `Error::InternalError` rather than being left unchecked.
+    /// Use the [`with_cauchy`](Self::with_cauchy) method for such pairs instead.
+    pub fn with_adjusted_reed_solomon(
+        source_num: NonZeroUsize,
+        code_num: NonZeroUsize,
+    ) -> Result<Self, Error> {
+        Self::builder()
+            .source_num(source_num.get())
+            .code_num(code_num.get())
+            .matrix_kind(MatrixKind::AdjustedReedSolomon)
+            .build()
+    }
+
+    /// Creates a new `ErasureCode` instance with the Vandermonde-style matrix of `gf_gen_rs_matrix`.
+    ///
+    /// This maps one-to-one to `gf_gen_rs_matrix` of the C API: the top of the matrix is the identity,
+    /// and the coefficient of source block `j` in code block `i` is `2^(i * j)`.
+    /// It generates the same matrix as [`with_reed_solomon`](Self::with_reed_solomon), under the name
+    /// used by other isa-l bindings.
+    ///
+    /// # Arguments
+    /// * `source_num` - The number of source data blocks.
+    /// * `code_num` - The number of code blocks.
+    ///
+    /// # Errors
+    /// * `Error::InvalidArguments` - If `source_num + code_num` exceeds [`MAX_BLOCK_NUM`].
+    ///
+    /// # Note
+    /// The matrix is not a systematic Vandermonde matrix derived by inverting the top part, so it is not
+    /// guaranteed that every sub matrix is invertible. See [`with_reed_solomon`](Self::with_reed_solomon)
+    /// for the pairs of `source_num` and `code_num` which are safe, or use [`with_cauchy`](Self::with_cauchy)
+    /// which is always invertable.
+    pub fn with_vandermonde(
+        source_num: NonZeroUsize,
+        code_num: NonZeroUsize,
+    ) -> Result<Self, Error> {
+        Self::builder()
+            .source_num(source_num.get())
+            .code_num(code_num.get())
+            .matrix_kind(MatrixKind::Vandermonde)
+            .build()
+    }
+
+    /// Creates a new `ErasureCode` instance with a user supplied encode matrix.
+    ///
+    /// # Arguments
+    /// * `source_num` - The number of source data blocks.
+    /// * `code_num` - The number of code blocks.
+    /// * `matrix` - The `(source_num + code_num) x source_num` encode matrix in row-major order,
+    ///   see [`encode_matrix`](Self::encode_matrix) for the layout.
+    ///
+    /// # Errors
+    /// * `Error::InvalidArguments` - If the geometry is not valid, see [`validate_geometry`].
+    /// * `Error::InvalidArguments` - If the length of `matrix` is not `(source_num + code_num) * source_num`.
+    /// * `Error::InvalidArguments` - If the top `source_num x source_num` sub matrix is not the identity matrix.
+    ///
+    /// # Note
+    /// The matrix is not checked for invertibility of its sub matrices,
+    /// a failure to decode some erasure patterns is reported when decoding.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// let k = NonZeroUsize::new(2).unwrap();
+    /// let m = NonZeroUsize::new(1).unwrap();
+    /// // a single xor parity
+    /// let matrix = [1, 0, 0, 1, 1, 1];
+    /// let ec = ErasureCode::with_matrix(k, m, &matrix).unwrap();
+    /// let parity = ec.encode_to_owned(&[[1_u8; 32], [2_u8; 32]]).unwrap();
+    /// assert_eq!(parity, vec![vec![3_u8; 32]]);
+    /// ```
+    pub fn with_matrix(
+        source_num: NonZeroUsize,
+        code_num: NonZeroUsize,
+        matrix: &[u8],
+    ) -> Result<Self, Error> {
+        let (k, m) = (source_num.get(), code_num.get());
+        Self::check_encode_matrix(k, m, matrix)?;
+        Self::from_encode_matrix(k, m, matrix.to_vec(), None)
+    }
+
+    /// Creates a new `ErasureCode` instance from an encode matrix and its precomputed gf table.
+    ///
+    /// Generating the gf table is the expensive part of creating a codec. When many codecs of the same
+    /// configuration are created, the table can be generated once, taken from a codec by
+    /// [`encode_gf_table`](Self::encode_gf_table), and reused to create the others cheaply.
+    ///
+    /// # Arguments
+    /// * `source_num` - The number of source data blocks.
+    /// * `code_num` - The number of code blocks.
+    /// * `encode_matrix` - The `(source_num + code_num) x source_num` encode matrix in row-major order,
+    ///   see [`encode_matrix`](Self::encode_matrix) for the layout.
+    /// * `encode_gf_table` - The `32 * source_num * code_num` bytes gf table of the parity part of the matrix,
+    ///   see [`gf_table_len`](Self::gf_table_len).
+    /// * `matrix_kind` - The kind of the matrix, see [`matrix_kind`](Self::matrix_kind),
+    ///   `None` for a user supplied matrix.
+    ///
+    /// # Errors
+    /// * `Error::InvalidArguments` - If the geometry is not valid, see [`validate_geometry`].
+    /// * `Error::InvalidArguments` - If the length of `encode_matrix` is not `(source_num + code_num) * source_num`.
+    /// * `Error::InvalidArguments` - If the top `source_num x source_num` sub matrix is not the identity matrix.
+    /// * `Error::InvalidArguments` - If `matrix_kind` is given, and `encode_matrix` is not the matrix of it.
+    /// * `Error::InvalidArguments` - If the length of `encode_gf_table` is not `32 * source_num * code_num`.
+    /// * `Error::InternalError` - If `matrix_kind` is [`MatrixKind::AdjustedReedSolomon`], and no invertible
+    ///   matrix can be found by adjusting the parity rows.
+    ///
+    /// # Note
+    /// Only the length of the gf table is checked, a table not generated from the matrix encodes wrong parities.
+    /// The matrix is regenerated to be checked against `matrix_kind`, which is cheap compared to the gf table.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let template = ErasureCode::with_cauchy(k, m).unwrap();
+    /// let codecs = (0..8)
+    ///     .map(|_| {
+    ///         let matrix = template.encode_matrix().to_vec();
+    ///         let gf_table = template.encode_gf_table().to_vec();
+    ///         ErasureCode::from_parts(k, m, matrix, gf_table, template.matrix_kind()).unwrap()
+    ///     })
+    ///     .collect::<Vec<_>>();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; 64]).collect();
+    /// assert_eq!(codecs[7].encode_to_owned(&data).unwrap(), template.encode_to_owned(&data).unwrap());
+    /// assert_eq!(codecs[7].key(), template.key());
+    /// ```
+    pub fn from_parts(
+        source_num: NonZeroUsize,
+        code_num: NonZeroUsize,
+        encode_matrix: Vec<u8>,
+        encode_gf_table: Vec<u8>,
+        matrix_kind: Option<MatrixKind>,
+    ) -> Result<Self, Error> {
+        let (k, m) = (source_num.get(), code_num.get());
+        Self::check_encode_matrix(k, m, &encode_matrix)?;
+        if let Some(kind) = matrix_kind
+            && Self::generate_matrix(k, m, kind)? != encode_matrix
+        {
+            return Err(Error::invalid_arguments(format!(
+                "encode matrix is not the {:?} matrix of ({}, {}) code",
+                kind, k, m
+            )));
+        }
+        if encode_gf_table.len() != 32 * k * m {
+            return Err(Error::invalid_arguments(format!(
+                "gf table length {} is not equal to 32 x {} x {}",
+                encode_gf_table.len(),
+                k,
+                m
+            )));
+        }
+        Ok(Self {
+            k: to_i32(k, "source number")?,
+            m: to_i32(m, "code number")?,
+            encode_matrix,
+            encode_gf_table: encode_gf_table.into(),
+            matrix_kind,
+            #[cfg(feature = "std")]
+            decode_cache: None,
+        })
     }
 
     /// Returns the number of source data blocks.
@@ -94,18 +833,267 @@ impl ErasureCode {
         (self.k + self.m) as usize
     }
 
-    /// Computes the dot product of the source data blocks with the encoding matrix.
-    ///
-    /// # See also
-    /// [galois::dot_prod] for more details on the dot product operation.
-    pub fn dot_prod<T>(&self, sources: impl AsRef<[T]>, dest: &mut [u8]) -> Result<(), Error>
-    where
-        T: AsRef<[u8]>,
-    {
-        galois::dot_prod(&self.encode_gf_table, sources, dest)
+    /// Returns the kind of the encode matrix, or `None` if the matrix is supplied by the user,
+    /// by [`with_matrix`](Self::with_matrix) or [`from_parts`](Self::from_parts) without a kind.
+    pub fn matrix_kind(&self) -> Option<MatrixKind> {
+        self.matrix_kind
     }
 
-    /// Computes the multiply and add operation of the source data blocks with the encoding matrix.
+    /// Returns the key of the configuration of the code, see [`CodecKey`].
+    pub fn key(&self) -> CodecKey {
+        let mut key = CodecKey::new(self.source_num(), self.code_num(), self.matrix_kind);
+        if self.matrix_kind.is_none() {
+            key.matrix_digest = self.matrix_stamp().1;
+            key.matrix = Some(Arc::from(self.encode_matrix.as_slice()));
+        }
+        key
+    }
+
+    /// Returns the role of the block at `index`, or `None` if `index` is not less than `block_num()`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::{BlockRole, ErasureCode};
+    /// # use std::num::NonZeroUsize;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// assert_eq!(ec.block_role(3), Some(BlockRole::Data(3)));
+    /// assert_eq!(ec.block_role(5), Some(BlockRole::Parity(1)));
+    /// assert_eq!(ec.block_role(6), None);
+    /// assert_eq!(BlockRole::Parity(1).to_string(), "parity block 1");
+    /// ```
+    pub fn block_role(&self, index: usize) -> Option<BlockRole> {
+        match index {
+            i if i < self.source_num() => Some(BlockRole::Data(i)),
+            i if i < self.block_num() => Some(BlockRole::Parity(i - self.source_num())),
+            _ => None,
+        }
+    }
+
+    /// Returns the coding rate, that is the fraction of the stored bytes holding source data,
+    /// `source_num() / block_num()`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// assert_eq!(ec.rate(), 4.0 / 6.0);
+    /// assert_eq!(ec.overhead_ratio(), 0.5);
+    /// assert_eq!(ec.fault_tolerance(), 2);
+    /// ```
+    pub fn rate(&self) -> f64 {
+        self.source_num() as f64 / self.block_num() as f64
+    }
+
+    /// Returns the storage overhead of the code blocks relative to the source data, `code_num() / source_num()`.
+    pub fn overhead_ratio(&self) -> f64 {
+        self.code_num() as f64 / self.source_num() as f64
+    }
+
+    /// Returns the number of erased blocks that can be tolerated, that is `code_num()`.
+    ///
+    /// This holds for any erasure pattern only if every sub matrix of the encode matrix is invertible,
+    /// which is guaranteed for [`with_cauchy`](Self::with_cauchy) and
+    /// [`with_adjusted_reed_solomon`](Self::with_adjusted_reed_solomon), and for
+    /// [`with_reed_solomon`](Self::with_reed_solomon) and [`with_vandermonde`](Self::with_vandermonde)
+    /// only within the pairs listed in [`with_reed_solomon`](Self::with_reed_solomon).
+    /// Other pairs or a custom matrix may fail some patterns, see [`is_recoverable`](Self::is_recoverable).
+    pub fn fault_tolerance(&self) -> usize {
+        self.code_num()
+    }
+
+    /// Returns the encode matrix of the code.
+    ///
+    /// The matrix has `block_num()` rows and `source_num()` columns in row-major order,
+    /// that is, the coefficient of source block `j` in block `i` is at index `i * source_num() + j`.
+    /// The first `source_num()` rows are the identity matrix, and the following `code_num()` rows
+    /// are the coefficients of the code blocks.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let matrix = ec.encode_matrix();
+    /// assert_eq!(matrix.len(), ec.block_num() * ec.source_num());
+    /// // The first code block is the xor of the source blocks
+    /// assert_eq!(&matrix[16..20], &[1, 1, 1, 1]);
+    /// ```
+    pub fn encode_matrix(&self) -> &[u8] {
+        &self.encode_matrix
+    }
+
+    /// Returns the coefficients of the parity block `parity_index` in the encode matrix.
+    ///
+    /// This is the row `source_num() + parity_index` of the [`encode_matrix`](Self::encode_matrix),
+    /// that is the `source_num()` bytes at offset `source_num() * (source_num() + parity_index)`,
+    /// where the coefficient `j` applies to the source block `j`.
+    ///
+    /// # Errors
+    /// * `Error::InvalidArguments` - If `parity_index` is not less than `code_num()`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use erasure_isa_l::{ec, gf};
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_cauchy(k, m).unwrap();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let parity = ec.encode_to_owned(&data).unwrap();
+    /// // compute the parity block 1 by a dot product
+    /// let coefficients = ec.parity_coefficients(1).unwrap();
+    /// let mut gf_tbls = vec![0_u8; 32 * k.get()];
+    /// ec::init_tables(4, 1, coefficients, &mut gf_tbls);
+    /// let src = data.iter().map(|b| b.as_ptr()).collect::<Vec<_>>();
+    /// let mut dest = vec![0_u8; BLOCK_LEN];
+    /// gf::vect_dot_prod(BLOCK_LEN as i32, 4, &gf_tbls, &src, &mut dest);
+    /// assert_eq!(dest, parity[1]);
+    /// ```
+    pub fn parity_coefficients(&self, parity_index: usize) -> Result<&[u8], Error> {
+        if parity_index >= self.code_num() {
+            return Err(Error::invalid_arguments(format!(
+                "parity index {} is out of range, code number is {}",
+                parity_index,
+                self.code_num()
+            )));
+        }
+        let k = self.source_num();
+        let offset = k * (k + parity_index);
+        Ok(&self.encode_matrix[offset..offset + k])
+    }
+
+    /// Returns the gf table of the parity part of the encode matrix, of [`gf_table_len`](Self::gf_table_len) bytes.
+    ///
+    /// The table can be passed to [`from_parts`](Self::from_parts) along with the encode matrix,
+    /// to create codecs of the same configuration without generating the table again.
+    pub fn encode_gf_table(&self) -> &[u8] {
+        &self.encode_gf_table
+    }
+
+    /// Returns the length in bytes of the gf table of the encode matrix, that is `32 * source_num() * code_num()`.
+    ///
+    /// Each coefficient of the `code_num() x source_num()` parity part of the encode matrix expands to
+    /// 32 bytes of constants, so this is the size of the `gf_tbls` slice to be filled by
+    /// [`ec::init_tables`] for encoding with the low-level API.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use erasure_isa_l::ec;
+    /// # use std::num::NonZeroUsize;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let mut gf_tbls = vec![0_u8; ec.gf_table_len()];
+    /// let parity_matrix = &ec.encode_matrix()[k.get() * k.get()..];
+    /// ec::init_tables(4, 2, parity_matrix, &mut gf_tbls);
+    /// assert_eq!(gf_tbls.len(), 32 * 4 * 2);
+    /// ```
+    pub fn gf_table_len(&self) -> usize {
+        self.decode_table_len(self.code_num())
+    }
+
+    /// Returns the length in bytes of the gf table to decode `num_erasures` erased blocks,
+    /// that is `32 * source_num() * num_erasures`.
+    ///
+    /// Each erased block is recovered from `source_num()` surviving blocks, and each coefficient
+    /// expands to 32 bytes of constants.
+    pub fn decode_table_len(&self, num_erasures: usize) -> usize {
+        32 * self.source_num() * num_erasures
+    }
+
+    /// Sets the capacity of the internal LRU cache of `DecodeTable`s used by [`decode`](Self::decode)
+    /// and [`decode_to_owned`](Self::decode_to_owned).
+    ///
+    /// The cache is disabled by default. With a non-zero capacity, `decode` reuses the table of a recurring
+    /// erasure pattern instead of regenerating it, see [`LruDecodeCache`].
+    /// A capacity of zero disables the cache again, and the cached tables are dropped.
+    ///
+    /// # Note
+    /// The cache is shared by all the callers of `decode` on this codec, so `decode` takes a lock on it
+    /// while looking up or generating a table, which may contend when decoding from many threads.
+    /// Codecs with the cache disabled take no lock.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let mut ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// ec.set_cache_capacity(16);
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let mut parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    /// for _ in 0..4 {
+    ///     // the decode table of [1] is generated only once
+    ///     let mut erased_data = data.clone();
+    ///     erased_data[1].fill(0);
+    ///     ec.decode(&mut erased_data, &mut parity, [1]).expect("Decoding failed");
+    ///     assert_eq!(erased_data, data);
+    /// }
+    /// assert_eq!(ec.cache_capacity(), 16);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn set_cache_capacity(&mut self, capacity: usize) {
+        self.decode_cache = NonZeroUsize::new(capacity).map(|c| Mutex::new(LruDecodeCache::new(c)));
+    }
+
+    /// Returns the capacity of the internal cache of `DecodeTable`s, zero if it is disabled.
+    ///
+    /// See [`set_cache_capacity`](Self::set_cache_capacity).
+    #[cfg(feature = "std")]
+    pub fn cache_capacity(&self) -> usize {
+        self.decode_cache.as_ref().map_or(0, |cache| {
+            cache
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .capacity()
+                .get()
+        })
+    }
+
+    /// Checks whether the blocks encoded by `self` can be decoded by `other`, and vice versa.
+    ///
+    /// Two erasure codes are compatible if and only if they have the same source number,
+    /// code number and encode matrix, which implies they produce identical code blocks.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let rs = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let cauchy = ErasureCode::with_cauchy(k, m).unwrap();
+    /// assert!(rs.compatible_with(&ErasureCode::with_reed_solomon(k, m).unwrap()));
+    /// assert!(!rs.compatible_with(&cauchy));
+    /// ```
+    pub fn compatible_with(&self, other: &ErasureCode) -> bool {
+        self.k == other.k && self.m == other.m && self.encode_matrix == other.encode_matrix
+    }
+
+    /// Computes the dot product of the source data blocks with the encoding matrix.
+    ///
+    /// # See also
+    /// [galois::dot_prod] for more details on the dot product operation.
+    pub fn dot_prod<T>(&self, sources: impl AsRef<[T]>, dest: &mut [u8]) -> Result<(), Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        galois::dot_prod(&self.encode_gf_table, sources, dest)
+    }
+
+    /// Computes the multiply and add operation of the source data blocks with the encoding matrix.
     ///
     /// # See also
     /// [galois::mul_add] for more details on the multiply and add operation.
@@ -129,6 +1117,15 @@ impl ErasureCode {
 
     /// Encodes the source data into code blocks.
     ///
+    /// The blocks can be of any length, `ec_encode_data` of isa-l computes the blocks shorter than
+    /// the minimum length of its SIMD kernels with the portable kernels, and so does [`decode`](Self::decode).
+    /// Only the low-level [`dot_prod`](Self::dot_prod) and [`mul_add`](Self::mul_add) reject short blocks with
+    /// `Error::BlockTooSmall`.
+    ///
+    /// If the only code block is the xor of the source blocks, as with a Reed-Solomon code of one code block,
+    /// the code block is computed by xor directly, and [`decode`](Self::decode) recovers a single erased block
+    /// the same way.
+    ///
     /// # Arguments
     /// * `data` - The source data blocks to be encoded.
     /// * `code` - The code blocks to be filled with the encoded data.
@@ -138,6 +1135,12 @@ impl ErasureCode {
     /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number,
     ///   or the code blocks number is not equal to the code number.
     /// * `Error::InvalidArguments` - If the input data or code blocks do not have the same length
+    /// * `Error::InvalidArguments` - If the memory of a code block overlaps a data block or another code block.
+    ///
+    /// # Note
+    /// The code blocks are written while the data blocks are read, so they must not share memory.
+    /// Safe code cannot create such buffers, but the ones assembled from raw parts by `unsafe` code can,
+    /// and they would silently corrupt the parities, so the overlap is checked and rejected.
     ///
     /// # Examples
     /// ```rust
@@ -156,43 +1159,78 @@ impl ErasureCode {
         data: impl AsRef<[T]>,
         mut code: impl AsMut<[U]>,
     ) -> Result<(), Error> {
-        self.check_encode_buffer(&data, &mut code)?;
-        self.encode_impl(data, code)
+        self.check_encode_buffer(&data, &mut code, self.code_num())?;
+        if self.is_xor_parity() {
+            // RAID5, the only code block is the xor of the source blocks
+            let blocks = data.as_ref().iter().map(AsRef::as_ref);
+            return xor_blocks(blocks, code.as_mut()[0].as_mut());
+        }
+        let len = data.as_ref()[0].as_ref().len();
+        self.encode_impl(data, 0..self.code_num(), code, len)
     }
 
-    /// Encodes the source data into code blocks and returns the code blocks as `Vec<Vec<u8>>`.
+    /// Encodes the source data into code blocks like [`encode`](Self::encode), reusing the pointer buffers of `scratch`.
     ///
-    /// This is a convenience method that allocates a new `Vec<Vec<u8>>` for the code blocks,
-    /// and encodes the data into it.
+    /// # Arguments
+    /// * `data` - The source data blocks to be encoded.
+    /// * `code` - The code blocks to be filled with the encoded data.
+    /// * `scratch` - The reusable pointer buffers, see [`EncodeScratch`].
     ///
-    /// See [`encode`](Self::encode) for more details on encoding.
-    pub fn encode_to_owned<T: AsRef<[u8]>>(
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number,
+    ///   or the code blocks number is not equal to the code number.
+    /// * `Error::InvalidArguments` - If the input data or code blocks do not have the same length.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::{ErasureCode, EncodeScratch};
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 64;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let mut scratch = EncodeScratch::new(&ec);
+    /// let mut parity: Vec<Vec<u8>> = vec![vec![0u8; BLOCK_LEN]; m.get()];
+    /// for i in 0..16 {
+    ///     let data: Vec<Vec<u8>> = (0..k.get()).map(|j| vec![(i + j) as u8; BLOCK_LEN]).collect();
+    ///     ec.encode_into(&data, &mut parity, &mut scratch).expect("Encoding failed");
+    ///     assert_eq!(parity, ec.encode_to_owned(&data).unwrap());
+    /// }
+    /// ```
+    pub fn encode_into<T: AsRef<[u8]>, U: AsMut<[u8]>>(
         &self,
         data: impl AsRef<[T]>,
-    ) -> Result<Vec<Vec<u8>>, Error> {
-        let len = data.as_ref().first().unwrap().as_ref().len();
-        let mut code = vec![vec![0_u8; len]; self.code_num()];
-        self.encode(data, &mut code)?;
-        Ok(code)
+        mut code: impl AsMut<[U]>,
+        scratch: &mut EncodeScratch,
+    ) -> Result<(), Error> {
+        self.check_encode_buffer(&data, &mut code, self.code_num())?;
+        let len = data.as_ref()[0].as_ref().len();
+        self.encode_with_scratch_impl(data, 0..self.code_num(), code, len, scratch)
     }
 
-    /// Update parities from a delta of a single source data block.
-    ///
-    /// This method is used to update the parity data from a single source data block
-    /// without re-encoding all the data blocks.
+    /// Returns a new [`EncodeContext`] to encode many stripes, reusing its pointer buffers.
+    pub fn encode_context(&self) -> EncodeContext<'_> {
+        EncodeContext {
+            ec: self,
+            scratch: EncodeScratch::new(self),
+        }
+    }
+
+    /// Encodes a single contiguous buffer, split into `source_num()` equal source data blocks, into code blocks.
     ///
-    /// This method can also be used with a part of updated source block, see in the example.
+    /// The source data block `i` is the `i`-th `data.len() / source_num()` bytes of `data`, so a memory-mapped
+    /// file or a network frame can be encoded without being reshaped into separate blocks.
     ///
     /// # Arguments
-    /// * `index` - The index of the updated source data block.
-    /// * `delta` - The delta of the updated source data block, which is the xor of the old and new data.
-    /// * `code` - The code blocks to be updated with the new parity data.
+    /// * `data` - The contiguous source data, whose length must be a multiple of `source_num()`.
+    /// * `code` - The code blocks to be filled with the encoded data.
     ///
     /// # Errors
     /// The following errors can occur:
-    /// * `Error::InvalidArguments` - If the index is out of range `0..source_num()`.
-    /// * `Error::InvalidArguments` - The code blocks number is not equal to the code number,
-    /// * `Error::InvalidArguments` - If the input data or code blocks do not have the same length.
+    /// * `Error::InvalidArguments` - If the data length is not a multiple of the source number.
+    /// * `Error::InvalidArguments` - If the code blocks number is not equal to the code number.
+    /// * `Error::InvalidArguments` - If the code blocks length is not equal to `data.len() / source_num()`.
     ///
     /// # Examples
     /// ```rust
@@ -202,77 +1240,2831 @@ impl ErasureCode {
     /// let k = NonZeroUsize::new(4).unwrap();
     /// let m = NonZeroUsize::new(2).unwrap();
     /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
-    /// let mut data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let data: Vec<u8> = (0..k.get() * BLOCK_LEN).map(|i| i as u8).collect();
     /// let mut parity: Vec<Vec<u8>> = vec![vec![0u8; BLOCK_LEN]; m.get()];
-    /// ec.encode(&data, &mut parity).expect("Encoding failed");
-    /// // Update the second quarter of the first source data block
-    /// let update_index = 0;
-    /// let range = BLOCK_LEN / 4.. BLOCK_LEN / 2;
-    /// let delta = vec![0xCC_u8; range.len()];
-    /// let parity_slice = parity.iter_mut().map(|p| &mut p[range.clone()]).collect::<Vec<_>>();
-    /// ec.update(update_index, delta.as_slice(), parity_slice).expect("Update failed");
-    /// // The parity blocks are updated correctly
-    /// data[0][range.clone()].iter_mut().for_each(|x| *x ^= 0xCC);
-    /// let mut expected_parity: Vec<Vec<u8>> = vec![vec![0u8; BLOCK_LEN]; m.get()];
-    /// ec.encode(&data, &mut expected_parity).expect("Re-encoding failed");
-    /// assert_eq!(parity, expected_parity);
+    /// ec.encode_contiguous(&data, &mut parity).expect("Encoding failed");
+    /// assert_eq!(parity, ec.encode_to_owned(data.chunks(BLOCK_LEN).collect::<Vec<_>>()).unwrap());
     /// ```
-    pub fn update<U: AsMut<[u8]>>(
+    pub fn encode_contiguous<U: AsMut<[u8]>>(
         &self,
-        index: usize,
-        delta: &[u8],
+        data: &[u8],
+        code: impl AsMut<[U]>,
+    ) -> Result<(), Error> {
+        if !data.len().is_multiple_of(self.source_num()) {
+            return Err(Error::invalid_arguments(format!(
+                "data length {} is not a multiple of source number {}",
+                data.len(),
+                self.source_num()
+            )));
+        }
+        let len = data.len() / self.source_num();
+        let data = (0..self.source_num())
+            .map(|i| &data[(i * len)..((i + 1) * len)])
+            .collect::<Vec<_>>();
+        self.encode(data, code)
+    }
+
+    /// Returns the block length the SIMD kernels of isa-l run at full speed with, for a block of `desired` bytes.
+    ///
+    /// The length is rounded up to the nearest multiple of the widest vector of isa-l, 64 bytes,
+    /// and a length of 0 is rounded up to 64 bytes as well. A block of any length can be encoded,
+    /// but the tail which does not fill a whole vector is computed by slower code.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// assert_eq!(ec.optimal_block_len(1000), 1024);
+    /// assert_eq!(ec.optimal_block_len(1024), 1024);
+    /// assert_eq!(ec.optimal_block_len(1), 64);
+    /// ```
+    pub fn optimal_block_len(&self, desired: usize) -> usize {
+        desired.next_multiple_of(MIN_VECT_LEN).max(MIN_VECT_LEN)
+    }
+
+    /// Pads the source data blocks in place with zeros to the [`optimal_block_len`](Self::optimal_block_len),
+    /// and encodes them into newly allocated code blocks of the padded length.
+    ///
+    /// # Arguments
+    /// * `data` - The source data blocks to be padded and encoded, which must have the same length.
+    ///
+    /// # Returns
+    /// The code blocks, and the real length of the source data blocks before padding.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number.
+    /// * `Error::InvalidArguments` - If the data blocks do not have the same length.
+    ///
+    /// # Note
+    /// The padding is a part of the blocks as far as the code is concerned. The surviving blocks must be
+    /// passed to decode with the same zero padding, as stored or padded again, and the recovered source data
+    /// blocks are to be truncated to the real length.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; 1000]).collect();
+    /// let mut padded = data.clone();
+    /// let (mut parity, len) = ec.encode_autopad(&mut padded).expect("Encoding failed");
+    /// assert_eq!(len, 1000);
+    /// assert_eq!(padded[0].len(), 1024);
+    /// assert_eq!(parity[0].len(), 1024);
+    /// padded[1].fill(0);
+    /// ec.decode(&mut padded, &mut parity, [1]).expect("Decoding failed");
+    /// padded.iter_mut().for_each(|block| block.truncate(len));
+    /// assert_eq!(padded, data);
+    /// ```
+    pub fn encode_autopad(
+        &self,
+        mut data: impl AsMut<[Vec<u8>]>,
+    ) -> Result<(Vec<Vec<u8>>, usize), Error> {
+        let data = data.as_mut();
+        if data.len() != self.source_num() {
+            return Err(Error::invalid_arguments(format!(
+                "data length {} is not equal to source num {}",
+                data.len(),
+                self.k,
+            )));
+        }
+        let len = data[0].len();
+        if data.iter().any(|d| d.len() != len) {
+            return Err(Error::invalid_arguments("data blocks must be equal"));
+        }
+        let padded_len = self.optimal_block_len(len);
+        data.iter_mut().for_each(|d| d.resize(padded_len, 0));
+        let code = self.encode_to_owned(&*data)?;
+        Ok((code, len))
+    }
+
+    /// Encodes source data blocks of different lengths into code blocks, as if the shorter ones were zero-padded.
+    ///
+    /// The parity is computed as if every source data block was extended with zeros to the length of the
+    /// longest one, which is the length of the code blocks, but no padded copy is allocated. The caller
+    /// is responsible for remembering the real length of each block, and the zero-padded blocks are
+    /// recovered when decoding.
+    ///
+    /// # Arguments
+    /// * `data` - The source data blocks to be encoded, which may have different lengths.
+    /// * `code` - The code blocks to be filled with the encoded data, whose length is the maximum length of the source data blocks.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number,
+    ///   or the code blocks number is not equal to the code number.
+    /// * `Error::InvalidArguments` - If any code block length is not equal to the maximum length of the source data blocks.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// let k = NonZeroUsize::new(3).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let data = [vec![1_u8; 1024], vec![2_u8; 1024], vec![3_u8; 100]];
+    /// let mut parity = vec![vec![0_u8; 1024]; m.get()];
+    /// ec.encode_padded(&data, &mut parity).expect("Encoding failed");
+    /// // The last block is encoded as if it was zero-padded
+    /// let mut padded = data.clone();
+    /// padded[2].resize(1024, 0);
+    /// assert_eq!(parity, ec.encode_to_owned(&padded).unwrap());
+    /// ```
+    pub fn encode_padded<T: AsRef<[u8]>, U: AsMut<[u8]>>(
+        &self,
+        data: impl AsRef<[T]>,
         mut code: impl AsMut<[U]>,
     ) -> Result<(), Error> {
-        self.check_update(index, delta, &mut code)?;
-        // Update the data block at the given index
-        ec::encode_data_update(
-            delta.len().try_into().unwrap(),
-            self.k_i32(),
+        let data = data.as_ref();
+        let code = code.as_mut();
+        let max_len = data
+            .iter()
+            .map(|d| d.as_ref().len())
+            .max()
+            .unwrap_or_default();
+        // only check the blocks number here, the lengths are checked below
+        self.check_len_buffer(
+            data.iter().map(|d| d.as_ref().len()),
+            code.iter_mut().map(|c| c.as_mut().len()),
+            0,
+        )?;
+        if let Some(len) = code
+            .iter_mut()
+            .map(|c| c.as_mut().len())
+            .find(|l| *l != max_len)
+        {
+            return Err(Error::invalid_arguments(format!(
+                "code block length {} is not equal to the maximum source data block length {}",
+                len, max_len
+            )));
+        }
+        // encode the common prefix of all the source data blocks
+        let min_len = data
+            .iter()
+            .map(|d| d.as_ref().len())
+            .min()
+            .unwrap_or_default();
+        let prefixes = data
+            .iter()
+            .map(|d| &d.as_ref()[..min_len])
+            .collect::<Vec<_>>();
+        self.encode_impl(prefixes, 0..self.code_num(), &mut *code, min_len)?;
+        // the rest of the parity only depends on the longer blocks, as the padding is zeros
+        let mut code_tails = code
+            .iter_mut()
+            .map(|c| {
+                let tail = &mut c.as_mut()[min_len..];
+                tail.fill(0);
+                tail
+            })
+            .collect::<Vec<_>>();
+        for (index, block) in data.iter().enumerate() {
+            let tail = &block.as_ref()[min_len..];
+            if !tail.is_empty() {
+                self.update_impl(index, tail, &mut code_tails)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Encodes the source data of a shortened code, whose absent source data blocks are zeros.
+    ///
+    /// Shortening uses a `(source_num(), code_num())` code for less than `source_num()` source data blocks:
+    /// the `data.len()` blocks take the first source positions, and the rest of the positions are
+    /// treated as permanently zero, without being allocated nor passed. The code blocks are the same as
+    /// encoding the source data followed by zero blocks with [`encode`](Self::encode).
+    ///
+    /// # Arguments
+    /// * `data` - The source data blocks, at least 1 and at most `source_num()` blocks.
+    /// * `code` - The code blocks to be filled with the encoded data.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::InvalidArguments` - If the data blocks number is 0 or larger than the source number,
+    ///   or the code blocks number is not equal to the code number.
+    /// * `Error::InvalidArguments` - If the input data or code blocks do not have the same length.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(10).unwrap();
+    /// let m = NonZeroUsize::new(4).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// // only 7 source data blocks, the last 3 positions are zeros
+    /// let data: Vec<Vec<u8>> = (0..7).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let mut parity = vec![vec![0_u8; BLOCK_LEN]; m.get()];
+    /// ec.encode_shortened(&data, &mut parity).expect("Encoding failed");
+    /// let mut padded = data.clone();
+    /// padded.resize(k.get(), vec![0_u8; BLOCK_LEN]);
+    /// assert_eq!(parity, ec.encode_to_owned(&padded).unwrap());
+    /// ```
+    pub fn encode_shortened<T: AsRef<[u8]>, U: AsMut<[u8]>>(
+        &self,
+        data: impl AsRef<[T]>,
+        mut code: impl AsMut<[U]>,
+    ) -> Result<(), Error> {
+        let (data, code) = (data.as_ref(), code.as_mut());
+        let len = self.check_shortened_buffer(
+            data.iter().map(|d| d.as_ref().len()),
+            code.iter_mut().map(|c| c.as_mut().len()),
+        )?;
+        Self::check_disjoint(data, &mut *code)?;
+        let (k, s) = (self.source_num(), data.len());
+        // the zero blocks add nothing, so only the columns of the present blocks are applied
+        let table = (0..self.code_num())
+            .flat_map(|row| &self.encode_gf_table[32 * k * row..32 * (k * row + s)])
+            .copied()
+            .collect::<Vec<_>>();
+        let data_ptrs = data.iter().map(|d| d.as_ref().as_ptr()).collect::<Vec<_>>();
+        let mut code_ptrs = code
+            .iter_mut()
+            .map(|c| c.as_mut().as_mut_ptr())
+            .collect::<Vec<_>>();
+        ec::encode_data(
+            to_i32(len, "block length")?,
+            to_i32(s, "source number")?,
+            self.m,
+            &table,
+            &data_ptrs,
+            &mut code_ptrs,
+        );
+        Ok(())
+    }
+
+    /// Decodes the erased blocks of a shortened code, see [`encode_shortened`](Self::encode_shortened).
+    ///
+    /// The absent source data blocks are known to be zeros, so they take part in the decoding as
+    /// surviving blocks, and up to `code_num()` erased blocks among the present ones are recovered.
+    ///
+    /// The erasures are indexed in the index space of the whole code, that is, the present source data
+    /// blocks take the indices `0..data.len()`, the absent ones take `data.len()..source_num()`, and the code
+    /// blocks still start at `source_num()`. So the code block `i` is at the index `source_num() + i`
+    /// however the code is shortened, and an absent block can never be erased.
+    ///
+    /// # Arguments
+    /// * `data` - The present source data blocks, at least 1 and at most `source_num()` blocks.
+    /// * `code` - The code blocks.
+    /// * `erasures` - The indices of the erased blocks.
+    ///
+    /// # Returns
+    /// The sorted and deduplicated indices of the recovered blocks.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::TooManyErasures` - If the number of erasures is larger than the code number.
+    /// * `Error::InvalidArguments` - If the erasure indices are out of range, or any of them is an absent block.
+    /// * `Error::InvalidArguments` - If the data blocks number is 0 or larger than the source number,
+    ///   or the code blocks number is not equal to the code number.
+    /// * `Error::InvalidArguments` - If the input data or code blocks do not have the same length.
+    /// * `Error::Singular` - If the sub matrix of the surviving blocks is not invertible for the erasures.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(10).unwrap();
+    /// let m = NonZeroUsize::new(4).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let data: Vec<Vec<u8>> = (0..7).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let mut parity = vec![vec![0_u8; BLOCK_LEN]; m.get()];
+    /// ec.encode_shortened(&data, &mut parity).expect("Encoding failed");
+    /// // data block 2 and code block 1 are erased, the absent blocks 7..10 are not
+    /// let (mut erased_data, mut erased_parity) = (data.clone(), parity.clone());
+    /// erased_data[2].fill(0);
+    /// erased_parity[1].fill(0);
+    /// let recovered = ec
+    ///     .decode_shortened(&mut erased_data, &mut erased_parity, [2, 11])
+    ///     .expect("Decoding failed");
+    /// assert_eq!(recovered, vec![2, 11]);
+    /// assert_eq!(erased_data, data);
+    /// assert_eq!(erased_parity, parity);
+    /// ```
+    pub fn decode_shortened<U: AsMut<[u8]>>(
+        &self,
+        mut data: impl AsMut<[U]>,
+        mut code: impl AsMut<[U]>,
+        erasures: impl AsRef<[usize]>,
+    ) -> Result<Vec<usize>, Error> {
+        let (data, code) = (data.as_mut(), code.as_mut());
+        let erasures = self.check_decode_erasure(erasures.as_ref())?;
+        let len = self.check_shortened_buffer(
+            data.iter_mut().map(|d| d.as_mut().len()),
+            code.iter_mut().map(|c| c.as_mut().len()),
+        )?;
+        let (k, s) = (self.source_num(), data.len());
+        let absent = s..k;
+        if let Some(e) = erasures.iter().find(|e| absent.contains(e)) {
+            return Err(Error::invalid_arguments(format!(
+                "erasure index {} is an absent block of the shortened code, absent blocks are {:?}",
+                e, absent
+            )));
+        }
+        if erasures.is_empty() {
+            return Ok(erasures);
+        }
+        // the absent blocks are never erased, so all of them are among the first k surviving blocks
+        let survivors = (0..self.block_num())
+            .filter(|i| erasures.binary_search(i).is_err())
+            .take(k)
+            .collect::<Vec<_>>();
+        let matrix = self.make_decode_matrix(&erasures)?;
+        // the zero blocks add nothing, so their columns are dropped
+        let present_cols = survivors
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| !absent.contains(i))
+            .map(|(col, _)| col)
+            .collect::<Vec<_>>();
+        let sub_matrix = (0..erasures.len())
+            .flat_map(|row| {
+                let row = &matrix[row * k..(row + 1) * k];
+                present_cols.iter().map(move |col| row[*col])
+            })
+            .collect::<Vec<_>>();
+        let table = galois::GaloisFiledTable::try_from_matrix(
+            &sub_matrix,
+            erasures.len(),
+            present_cols.len(),
+        )?;
+        let mut recover_src = Vec::with_capacity(present_cols.len());
+        let mut recover_output = Vec::with_capacity(erasures.len());
+        for (i, block) in data.iter_mut().chain(code.iter_mut()).enumerate() {
+            // the index of the code blocks skips the absent blocks
+            let i = if i < s { i } else { i + k - s };
+            if erasures.binary_search(&i).is_ok() {
+                recover_output.push(block.as_mut().as_mut_ptr());
+            } else if survivors.contains(&i) {
+                recover_src.push(block.as_mut().as_ptr());
+            }
+        }
+        ec::encode_data(
+            to_i32(len, "block length")?,
+            to_i32(present_cols.len(), "survivors number")?,
+            to_i32(erasures.len(), "erasures number")?,
+            &table,
+            &recover_src,
+            &mut recover_output,
+        );
+        Ok(erasures)
+    }
+
+    /// Encodes the source data into code blocks like [`encode`](Self::encode), with fixed-size arrays of blocks.
+    ///
+    /// The pointers to the blocks are gathered on the stack instead of the heap, which suits small fixed
+    /// geometries on the latency-sensitive paths.
+    ///
+    /// # Arguments
+    /// * `data` - The `K` source data blocks to be encoded.
+    /// * `code` - The `M` code blocks to be filled with the encoded data.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::InvalidArguments` - If `K` is not equal to the source number, or `M` is not equal to the code number.
+    /// * `Error::InvalidArguments` - If the input data or code blocks do not have the same length
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let ec = ErasureCode::with_reed_solomon(NonZeroUsize::new(4).unwrap(), NonZeroUsize::new(2).unwrap()).unwrap();
+    /// let data = [[0_u8; BLOCK_LEN], [1; BLOCK_LEN], [2; BLOCK_LEN], [3; BLOCK_LEN]];
+    /// let (mut p, mut q) = ([0_u8; BLOCK_LEN], [0_u8; BLOCK_LEN]);
+    /// ec.encode_arrays(&data.each_ref().map(|d| d.as_slice()), &mut [&mut p, &mut q])
+    ///     .expect("Encoding failed");
+    /// assert_eq!(vec![p.to_vec(), q.to_vec()], ec.encode_to_owned(&data).unwrap());
+    /// ```
+    pub fn encode_arrays<const K: usize, const M: usize>(
+        &self,
+        data: &[&[u8]; K],
+        code: &mut [&mut [u8]; M],
+    ) -> Result<(), Error> {
+        self.check_encode_buffer(data, &mut *code, self.code_num())?;
+        let len = data[0].len();
+        let data_ptrs: [*const u8; K] = core::array::from_fn(|i| data[i].as_ptr());
+        let mut code_ptrs = code.each_mut().map(|c| c.as_mut_ptr());
+        ec::encode_data(
+            to_i32(len, "block length")?,
+            self.k_i32(),
             self.m_i32(),
-            index.try_into().unwrap(),
             &self.encode_gf_table,
-            delta,
-            code.as_mut()
-                .iter_mut()
-                .map(AsMut::as_mut)
-                .map(<[u8]>::as_mut_ptr)
-                .collect::<Vec<_>>()
-                .as_mut_slice(),
+            &data_ptrs,
+            &mut code_ptrs,
         );
         Ok(())
     }
 
-    /// Decode the erased blocks from the surviving data and code blocks.
-    ///
-    /// The range of the blocks is `0..block_num()`.
-    /// The first `source_num()` blocks are the source data blocks, the next `code_num()` blocks are the code blocks.
+    /// Encodes the source data into the P and Q parity of RAID6, which is faster than the generic matrix path.
+    ///
+    /// A Reed-Solomon code with two code blocks, created by [`with_reed_solomon`](Self::with_reed_solomon)
+    /// or [`with_vandermonde`](Self::with_vandermonde), is RAID6: the first code block P is the xor of
+    /// the source blocks, and the second code block Q is the sum of `2^j * D_j`. This method computes
+    /// P by xor and Q by Horner's rule, multiplying by 2 instead of looking up the gf tables, and
+    /// produces the same code blocks as [`encode`](Self::encode).
+    ///
+    /// # Arguments
+    /// * `data` - The source data blocks to be encoded.
+    /// * `code` - The P and Q code blocks to be filled with the encoded data.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::InvalidArguments` - If the encode matrix is not the RAID6 matrix, such as a Cauchy matrix,
+    ///   or the code number is not 2.
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number,
+    ///   or the code blocks number is not equal to the code number.
+    /// * `Error::InvalidArguments` - If the input data or code blocks do not have the same length
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let mut parity: Vec<Vec<u8>> = vec![vec![0u8; BLOCK_LEN]; m.get()];
+    /// ec.encode_raid6(&data, &mut parity).expect("Encoding failed");
+    /// assert_eq!(parity, ec.encode_to_owned(&data).unwrap());
+    /// ```
+    pub fn encode_raid6<T: AsRef<[u8]>, U: AsMut<[u8]>>(
+        &self,
+        data: impl AsRef<[T]>,
+        mut code: impl AsMut<[U]>,
+    ) -> Result<(), Error> {
+        if !self.is_raid6() {
+            return Err(Error::invalid_arguments(
+                "the encode matrix is not the RAID6 matrix",
+            ));
+        }
+        self.check_encode_buffer(&data, &mut code, self.code_num())?;
+        let data = data.as_ref().iter().map(AsRef::as_ref).collect::<Vec<_>>();
+        let [p, q] = code.as_mut() else {
+            unreachable!("the code number is checked to be 2");
+        };
+        raid6_pq(&data, p.as_mut(), q.as_mut());
+        Ok(())
+    }
+
+    /// Encodes the source data into code blocks like [`encode`](Self::encode), with the pure Rust
+    /// [`mod@reference`] implementation instead of isa-l.
+    ///
+    /// It is much slower than `encode`, and is meant to be an oracle to cross-check the isa-l kernels.
+    ///
+    /// # Errors
+    /// The same as [`encode`](Self::encode).
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; 3]).collect();
+    /// let mut parity: Vec<Vec<u8>> = vec![vec![0u8; 3]; m.get()];
+    /// ec.encode_reference(&data, &mut parity).expect("Encoding failed");
+    /// assert_eq!(parity, ec.encode_to_owned(&data).unwrap());
+    /// ```
+    pub fn encode_reference<T: AsRef<[u8]>, U: AsMut<[u8]>>(
+        &self,
+        data: impl AsRef<[T]>,
+        mut code: impl AsMut<[U]>,
+    ) -> Result<(), Error> {
+        self.check_encode_buffer(&data, &mut code, self.code_num())?;
+        let k = self.source_num();
+        for (p, block) in code.as_mut().iter_mut().enumerate() {
+            let coefs = &self.encode_matrix[k * (k + p)..k * (k + p + 1)];
+            reference::dot_prod(coefs, data.as_ref(), block.as_mut());
+        }
+        Ok(())
+    }
+
+    /// Decode the erased blocks like [`decode`](Self::decode), with the pure Rust
+    /// [`mod@reference`] implementation instead of isa-l.
+    ///
+    /// It is much slower than `decode`, and is meant to be an oracle to cross-check the isa-l kernels.
+    /// The erased blocks are recovered from the first `source_num()` surviving blocks.
+    ///
+    /// # Returns
+    /// The sorted and deduplicated indices of the recovered blocks.
+    ///
+    /// # Errors
+    /// The same as [`decode`](Self::decode).
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; 3]).collect();
+    /// let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    /// let mut erased_data = data.clone();
+    /// erased_data[0].fill(0);
+    /// let mut erased_parity = parity.clone();
+    /// erased_parity[1].fill(0);
+    /// ec.decode_reference(&mut erased_data, &mut erased_parity, [0, 5]).expect("Decoding failed");
+    /// assert_eq!(erased_data, data);
+    /// assert_eq!(erased_parity, parity);
+    /// ```
+    pub fn decode_reference<U: AsMut<[u8]>>(
+        &self,
+        mut data: impl AsMut<[U]>,
+        mut code: impl AsMut<[U]>,
+        erasures: impl AsRef<[usize]>,
+    ) -> Result<Vec<usize>, Error> {
+        let erasures = self.check_decode_erasure(erasures.as_ref())?;
+        self.check_decode_buffer(&mut data, &mut code)?;
+        if erasures.is_empty() {
+            return Ok(erasures);
+        }
+        let k = self.source_num();
+        let survivors = (0..self.block_num())
+            .filter(|i| !erasures.contains(i))
+            .take(k)
+            .collect::<Vec<_>>();
+        let survivor_matrix = survivors
+            .iter()
+            .flat_map(|&s| &self.encode_matrix[s * k..(s + 1) * k])
+            .copied()
+            .collect::<Vec<_>>();
+        let inverse = reference::invert_matrix(&survivor_matrix, k)
+            .ok_or_else(|| Error::singular(&erasures))?;
+
+        let mut blocks = data
+            .as_mut()
+            .iter_mut()
+            .chain(code.as_mut().iter_mut())
+            .map(AsMut::as_mut)
+            .collect::<Vec<&mut [u8]>>();
+        let sources = survivors.iter().map(|&s| &*blocks[s]).collect::<Vec<_>>();
+        // the row of an erased block over the survivors is its row of the encode matrix times the inverse
+        let recovered = erasures
+            .iter()
+            .map(|&e| {
+                let row = &self.encode_matrix[e * k..(e + 1) * k];
+                let coefs = (0..k)
+                    .map(|s| {
+                        (0..k).fold(0, |acc, j| acc ^ reference::mul(row[j], inverse[j * k + s]))
+                    })
+                    .collect::<Vec<_>>();
+                let mut block = vec![0_u8; sources[0].len()];
+                reference::dot_prod(&coefs, &sources, &mut block);
+                block
+            })
+            .collect::<Vec<_>>();
+        for (&e, block) in erasures.iter().zip(recovered) {
+            blocks[e].copy_from_slice(&block);
+        }
+        Ok(erasures)
+    }
+
+    /// Encodes the source data into code blocks like [`encode`](Self::encode), using multiple threads.
+    ///
+    /// Each block is split into chunks whose boundaries are multiples of 32 bytes, except for the
+    /// last chunk which holds the remainder, and the chunks are encoded concurrently on the rayon
+    /// thread pool. It is only worthwhile for large blocks, since the encoding is memory-bound.
+    ///
+    /// This method is only available with the `rayon` feature enabled.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number,
+    ///   or the code blocks number is not equal to the code number.
+    /// * `Error::InvalidArguments` - If the input data or code blocks do not have the same length.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024 * 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let mut parity: Vec<Vec<u8>> = vec![vec![0u8; BLOCK_LEN]; m.get()];
+    /// ec.encode_parallel(&data, &mut parity).expect("Encoding failed");
+    /// assert_eq!(parity, ec.encode_to_owned(&data).unwrap());
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn encode_parallel<T: AsRef<[u8]> + Sync, U: AsMut<[u8]>>(
+        &self,
+        data: impl AsRef<[T]>,
+        mut code: impl AsMut<[U]>,
+    ) -> Result<(), Error> {
+        use rayon::prelude::*;
+
+        self.check_encode_buffer(&data, &mut code, self.code_num())?;
+        let data = data.as_ref();
+        let len = data[0].as_ref().len();
+        // the chunks are aligned to 32 bytes to keep the SIMD kernels on their fast path
+        let chunk_len = len
+            .div_ceil(rayon::current_num_threads())
+            .next_multiple_of(32)
+            .max(32);
+        // transpose the chunks of the code blocks into one group of code chunks per offset
+        let mut code_chunks = (0..len.div_ceil(chunk_len))
+            .map(|_| Vec::with_capacity(self.code_num()))
+            .collect::<Vec<_>>();
+        for block in code.as_mut().iter_mut() {
+            for (group, chunk) in code_chunks
+                .iter_mut()
+                .zip(block.as_mut().chunks_mut(chunk_len))
+            {
+                group.push(chunk);
+            }
+        }
+        code_chunks
+            .into_par_iter()
+            .enumerate()
+            .try_for_each(|(i, mut code)| {
+                let offset = i * chunk_len;
+                let chunk_len = chunk_len.min(len - offset);
+                let data = data
+                    .iter()
+                    .map(|d| &d.as_ref()[offset..offset + chunk_len])
+                    .collect::<Vec<_>>();
+                self.encode_impl(data, 0..self.code_num(), &mut code, chunk_len)
+            })
+    }
+
+    /// Encodes the source data into the code blocks of a range of parity rows.
+    ///
+    /// The `i`-th block of `code` is filled with the parity block `rows.start + i`, so
+    /// only the requested parity blocks are computed instead of all `code_num()` of them.
+    ///
+    /// # Arguments
+    /// * `data` - The source data blocks to be encoded.
+    /// * `rows` - The range of the parity rows to be encoded, which must be within `0..code_num()`.
+    /// * `code` - The code blocks to be filled with the encoded data, one for each row in `rows`.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::InvalidArguments` - If `rows` is out of range `0..code_num()`.
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number,
+    ///   or the code blocks number is not equal to the number of rows.
+    /// * `Error::InvalidArguments` - If the input data or code blocks do not have the same length
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(3).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    /// // Only encode the last two parity blocks
+    /// let mut last_two: Vec<Vec<u8>> = vec![vec![0u8; BLOCK_LEN]; 2];
+    /// ec.encode_rows(&data, 1..3, &mut last_two).expect("Encoding failed");
+    /// assert_eq!(&last_two, &parity[1..]);
+    /// ```
+    pub fn encode_rows<T: AsRef<[u8]>, U: AsMut<[u8]>>(
+        &self,
+        data: impl AsRef<[T]>,
+        rows: Range<usize>,
+        mut code: impl AsMut<[U]>,
+    ) -> Result<(), Error> {
+        if rows.start > rows.end || rows.end > self.code_num() {
+            return Err(Error::invalid_arguments(format!(
+                "rows {:?} is out of range, code number is {}",
+                rows,
+                self.code_num()
+            )));
+        }
+        self.check_encode_buffer(&data, &mut code, rows.len())?;
+        let len = data.as_ref()[0].as_ref().len();
+        self.encode_impl(data, rows, code, len)
+    }
+
+    /// Encodes the source data into a single code block.
+    ///
+    /// Only the parity row `parity_index` of the encode matrix is applied, which is a single dot product of the
+    /// source blocks, so regenerating one replaced parity block does not pay for all `code_num()` of them.
+    ///
+    /// # Arguments
+    /// * `parity_index` - The index of the parity block in range `0..code_num()`.
+    /// * `data` - The source data blocks to be encoded.
+    /// * `out` - The code block to be filled with the encoded data.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::InvalidArguments` - If `parity_index` is out of range.
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number.
+    /// * `Error::InvalidArguments` - If the input data blocks or `out` do not have the same length
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(3).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    /// // The disk of parity 1 is replaced
+    /// let mut replaced = vec![0u8; BLOCK_LEN];
+    /// ec.encode_single(1, &data, &mut replaced).expect("Encoding failed");
+    /// assert_eq!(replaced, parity[1]);
+    /// ```
+    pub fn encode_single<T: AsRef<[u8]>>(
+        &self,
+        parity_index: usize,
+        data: impl AsRef<[T]>,
+        out: &mut [u8],
+    ) -> Result<(), Error> {
+        if parity_index >= self.code_num() {
+            return Err(Error::invalid_arguments(format!(
+                "parity index {} is out of range, code number is {}",
+                parity_index,
+                self.code_num()
+            )));
+        }
+        self.encode_rows(data, parity_index..parity_index + 1, [out])
+    }
+
+    /// Combines the parity blocks of two stripes into the parity of their byte-wise XOR.
+    ///
+    /// The encoding is linear over GF(2^8), so the parity of `a ^ b` is the XOR of the parity of `a`
+    /// and the parity of `b`, and it can be computed without the source data blocks nor an encode.
+    ///
+    /// # Arguments
+    /// * `a_code` - The code blocks of the first stripe.
+    /// * `b_code` - The code blocks of the second stripe.
+    /// * `out` - The code blocks to be filled with the combined parity.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::InvalidArguments` - If any of the code blocks numbers is not equal to the code number.
+    /// * `Error::InvalidArguments` - If the code blocks do not have the same length.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let a: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let b: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![0x10 + i as u8; BLOCK_LEN]).collect();
+    /// let a_code = ec.encode_to_owned(&a).expect("Encoding failed");
+    /// let b_code = ec.encode_to_owned(&b).expect("Encoding failed");
+    /// let mut combined = vec![vec![0_u8; BLOCK_LEN]; m.get()];
+    /// ec.combine_parity(&a_code, &b_code, &mut combined).unwrap();
+    /// let xored: Vec<Vec<u8>> = a
+    ///     .iter()
+    ///     .zip(&b)
+    ///     .map(|(a, b)| a.iter().zip(b).map(|(a, b)| a ^ b).collect())
+    ///     .collect();
+    /// assert_eq!(combined, ec.encode_to_owned(&xored).unwrap());
+    /// ```
+    pub fn combine_parity<T: AsRef<[u8]>, U: AsMut<[u8]>>(
+        &self,
+        a_code: impl AsRef<[T]>,
+        b_code: impl AsRef<[T]>,
+        mut out: impl AsMut<[U]>,
+    ) -> Result<(), Error> {
+        let (a_code, b_code, out) = (a_code.as_ref(), b_code.as_ref(), out.as_mut());
+        for (name, num) in [("a", a_code.len()), ("b", b_code.len()), ("out", out.len())] {
+            if num != self.code_num() {
+                return Err(Error::invalid_arguments(format!(
+                    "{} code length {} is not equal to code number {}",
+                    name,
+                    num,
+                    self.code_num()
+                )));
+            }
+        }
+        let len = a_code[0].as_ref().len();
+        if a_code.iter().chain(b_code).any(|c| c.as_ref().len() != len)
+            || out.iter_mut().any(|c| c.as_mut().len() != len)
+        {
+            return Err(Error::invalid_arguments("code data block must be equal"));
+        }
+        a_code
+            .iter()
+            .zip(b_code)
+            .zip(out)
+            .try_for_each(|((a, b), out)| gf::xor(a.as_ref(), b.as_ref(), out.as_mut()))
+    }
+
+    /// Encodes the source data lazily, yielding the code blocks one by one in order.
+    ///
+    /// Each code block is computed on demand like [`encode_single`](Self::encode_single), with a single
+    /// dot product of its parity row, so the first code blocks can be consumed, e.g. sent over the network,
+    /// while the later ones are not computed yet. It trades some throughput of [`encode`](Self::encode),
+    /// which computes all the code blocks in one pass over the source data, for the latency.
+    ///
+    /// # Errors
+    /// Each item is an error like [`encode_single`](Self::encode_single) if the data blocks are invalid,
+    /// and the iterator ends after the first error.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    /// for (i, block) in ec.encode_iter(&data).enumerate() {
+    ///     assert_eq!(block.expect("Encoding failed"), parity[i]);
+    /// }
+    /// ```
+    pub fn encode_iter<'a, T: AsRef<[u8]>>(
+        &'a self,
+        data: &'a [T],
+    ) -> impl Iterator<Item = Result<Vec<u8>, Error>> + 'a {
+        let len = data.first().map_or(0, |d| d.as_ref().len());
+        (0..self.code_num()).scan(false, move |failed, row| {
+            if *failed {
+                return None;
+            }
+            let mut block = vec![0_u8; len];
+            let res = self.encode_single(row, data, &mut block).map(|()| block);
+            *failed = res.is_err();
+            Some(res)
+        })
+    }
+
+    /// Verifies a single parity block against the source data blocks.
+    ///
+    /// Only the requested parity block is re-encoded into a scratch buffer and compared,
+    /// which is cheaper than verifying all the parity blocks when only one of them is of interest.
+    ///
+    /// # Arguments
+    /// * `data` - The source data blocks.
+    /// * `parity_index` - The index of the parity block in range `0..code_num()`.
+    /// * `parity_block` - The parity block to be verified.
+    ///
+    /// # Returns
+    /// `true` if the parity block is consistent with the source data blocks, `false` otherwise.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::InvalidArguments` - If `parity_index` is out of range `0..code_num()`.
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number.
+    /// * `Error::InvalidArguments` - If the data blocks or the parity block do not have the same length.
+    pub fn verify_parity<T: AsRef<[u8]>>(
+        &self,
+        data: impl AsRef<[T]>,
+        parity_index: usize,
+        parity_block: &[u8],
+    ) -> Result<bool, Error> {
+        if parity_index >= self.code_num() {
+            return Err(Error::invalid_arguments(format!(
+                "parity index {} is out of range, code number is {}",
+                parity_index,
+                self.code_num()
+            )));
+        }
+        let mut scratch = [vec![0_u8; parity_block.len()]];
+        self.encode_rows(data, parity_index..parity_index + 1, &mut scratch)?;
+        Ok(scratch[0] == parity_block)
+    }
+
+    /// Verifies the code blocks against the source data blocks.
+    ///
+    /// The source data blocks are re-encoded into scratch buffers and compared with the code blocks,
+    /// which detects a code block that drifted out of sync with its data without decoding.
+    ///
+    /// # Arguments
+    /// * `data` - The source data blocks.
+    /// * `code` - The code blocks to be verified.
+    ///
+    /// # Returns
+    /// `true` if all the code blocks are consistent with the source data blocks, `false` otherwise.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number,
+    ///   or the code blocks number is not equal to the code number.
+    /// * `Error::InvalidArguments` - If the input data or code blocks do not have the same length.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let mut parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    /// assert!(ec.verify(&data, &parity).unwrap());
+    /// parity[1][0] ^= 1;
+    /// assert!(!ec.verify(&data, &parity).unwrap());
+    /// ```
+    pub fn verify<T: AsRef<[u8]>, U: AsRef<[u8]>>(
+        &self,
+        data: impl AsRef<[T]>,
+        code: impl AsRef<[U]>,
+    ) -> Result<bool, Error> {
+        let code = code.as_ref();
+        let len = data.as_ref().first().map_or(0, |d| d.as_ref().len());
+        if code.iter().any(|c| c.as_ref().len() != len) {
+            return Err(Error::invalid_arguments("code data block must be equal"));
+        }
+        let mut scratch = vec![vec![0_u8; len]; code.len()];
+        self.encode(data, &mut scratch)?;
+        Ok(scratch.iter().zip(code).all(|(s, c)| s == c.as_ref()))
+    }
+
+    /// Encodes the first `len` bytes of each source data block into the code blocks.
+    ///
+    /// This is useful when the buffers are larger than the logical block length, and it avoids
+    /// slicing every block to the same length before encoding.
+    /// The bytes beyond `len` of each code block are left untouched.
+    ///
+    /// # Arguments
+    /// * `data` - The source data blocks to be encoded.
+    /// * `code` - The code blocks to be filled with the encoded data.
+    /// * `len` - The number of bytes to be encoded of each block.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number,
+    ///   or the code blocks number is not equal to the code number.
+    /// * `Error::InvalidArguments` - If any data or code block is shorter than `len`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// const CAPACITY: usize = 4096;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; CAPACITY]).collect();
+    /// let mut parity: Vec<Vec<u8>> = vec![vec![0u8; CAPACITY]; m.get()];
+    /// ec.encode_len(&data, &mut parity, BLOCK_LEN).expect("Encoding failed");
+    /// assert!(parity.iter().all(|p| p[BLOCK_LEN..].iter().all(|b| *b == 0)));
+    /// ```
+    pub fn encode_len<T: AsRef<[u8]>, U: AsMut<[u8]>>(
+        &self,
+        data: impl AsRef<[T]>,
+        mut code: impl AsMut<[U]>,
+        len: usize,
+    ) -> Result<(), Error> {
+        self.check_len_buffer(
+            data.as_ref().iter().map(|s| s.as_ref().len()),
+            code.as_mut().iter_mut().map(|s| s.as_mut().len()),
+            len,
+        )?;
+        self.encode_impl(data, 0..self.code_num(), code, len)
+    }
+
+    /// Encodes a stripe held in a single slice of `block_num()` blocks, filling its code blocks in place.
+    ///
+    /// The first `source_num()` blocks of the stripe are the source data blocks,
+    /// and the last `code_num()` blocks are the code blocks, as the block indices of [`block_role`](Self::block_role).
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::InvalidArguments` - If the stripe blocks number is not equal to the block number.
+    /// * `Error::InvalidArguments` - If the blocks do not have the same length.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let mut stripe: Vec<Vec<u8>> = (0..ec.block_num()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// ec.encode_stripe(&mut stripe).expect("Encoding failed");
+    /// assert_eq!(&stripe[4..], ec.encode_to_owned(&stripe[..4]).unwrap().as_slice());
+    /// let orig = stripe.clone();
+    /// // Lose a data block and a code block
+    /// stripe[1].fill(0);
+    /// stripe[5].fill(0);
+    /// ec.decode_stripe(&mut stripe, [1, 5]).expect("Decoding failed");
+    /// assert_eq!(stripe, orig);
+    /// ```
+    pub fn encode_stripe<U: AsMut<[u8]>>(&self, mut stripe: impl AsMut<[U]>) -> Result<(), Error> {
+        let (data, code) = self.split_stripe(stripe.as_mut())?;
+        let data = data.iter_mut().map(|b| &*b.as_mut()).collect::<Vec<_>>();
+        self.encode(data, code)
+    }
+
+    /// Encodes source data blocks interleaved in a single buffer into interleaved code blocks.
+    ///
+    /// The interleaved layout is made of rounds, each of which holds one chunk of `stride` bytes
+    /// of every block in the order of the blocks. That is, with `k = source_num()`, the byte `j`
+    /// of the chunk `r` of the source block `i` is at `buf[(r * k + i) * stride + j]`, and a `stride`
+    /// of 1 interleaves the blocks byte by byte. The code blocks are interleaved into `code_buf` the
+    /// same way with `m = code_num()` blocks per round.
+    ///
+    /// The chunks are gathered and scattered a window of rounds at a time, so only a bounded scratch
+    /// is allocated instead of de-interleaving the whole buffer. A larger `stride` makes the copies cheaper.
+    ///
+    /// # Arguments
+    /// * `buf` - The interleaved source data blocks, of `block_len * source_num()` bytes.
+    /// * `stride` - The number of contiguous bytes of a block in each round.
+    /// * `code_buf` - The buffer to be filled with the interleaved code blocks, of `block_len * code_num()` bytes.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::InvalidArguments` - If `stride` is zero, or `stride * source_num()` overflows.
+    /// * `Error::InvalidArguments` - If the length of `buf` is not a multiple of `stride * source_num()`.
+    /// * `Error::InvalidArguments` - If the length of `code_buf` does not match the length of `buf`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// // interleave the blocks byte by byte
+    /// let buf: Vec<u8> = (0..BLOCK_LEN).flat_map(|j| data.iter().map(move |d| d[j])).collect();
+    /// let mut code_buf = vec![0_u8; BLOCK_LEN * m.get()];
+    /// ec.encode_interleaved(&buf, 1, &mut code_buf).expect("Encoding failed");
+    /// let parity = ec.encode_to_owned(&data).unwrap();
+    /// assert_eq!(code_buf[0], parity[0][0]);
+    /// assert_eq!(code_buf[1], parity[1][0]);
+    /// assert_eq!(code_buf[2], parity[0][1]);
+    /// ```
+    pub fn encode_interleaved(
+        &self,
+        buf: &[u8],
+        stride: usize,
+        code_buf: &mut [u8],
+    ) -> Result<(), Error> {
+        let (k, m) = (self.source_num(), self.code_num());
+        if stride == 0 {
+            return Err(Error::invalid_arguments("stride must be positive"));
+        }
+        let round_len = stride.checked_mul(k).ok_or_else(|| {
+            Error::invalid_arguments(format!("stride {} x source number {} overflows", stride, k))
+        })?;
+        if !buf.len().is_multiple_of(round_len) {
+            return Err(Error::invalid_arguments(format!(
+                "buffer length {} is not a multiple of stride {} x source number {}",
+                buf.len(),
+                stride,
+                k
+            )));
+        }
+        let block_len = buf.len() / k;
+        if block_len.checked_mul(m) != Some(code_buf.len()) {
+            return Err(Error::invalid_arguments(format!(
+                "code buffer length {} is not equal to block length {} x code number {}",
+                code_buf.len(),
+                block_len,
+                m
+            )));
+        }
+        let rounds = block_len / stride;
+        let window_rounds = (INTERLEAVE_WINDOW_LEN / stride).max(1);
+        // the scratch never outgrows the blocks, whose length is bounded by the buffer
+        let mut data = vec![vec![0_u8; window_rounds.min(rounds) * stride]; k];
+        let mut code = vec![vec![0_u8; window_rounds.min(rounds) * stride]; m];
+        for first in (0..rounds).step_by(window_rounds) {
+            let window = window_rounds.min(rounds - first);
+            // gather the chunks of the source blocks
+            let rows = buf[first * k * stride..(first + window) * k * stride].chunks_exact(stride);
+            for (i, chunk) in rows.enumerate() {
+                let (r, block) = (i / k, i % k);
+                data[block][r * stride..(r + 1) * stride].copy_from_slice(chunk);
+            }
+            self.encode_impl(&data, 0..m, &mut code, window * stride)?;
+            // scatter the chunks of the code blocks
+            let rows = code_buf[first * m * stride..(first + window) * m * stride]
+                .chunks_exact_mut(stride);
+            for (i, chunk) in rows.enumerate() {
+                let (r, block) = (i / m, i % m);
+                chunk.copy_from_slice(&code[block][r * stride..(r + 1) * stride]);
+            }
+        }
+        Ok(())
+    }
+
+    /// Encodes the source data into code blocks and returns the code blocks as `Vec<Vec<u8>>`.
+    ///
+    /// This is a convenience method that allocates a new `Vec<Vec<u8>>` for the code blocks,
+    /// and encodes the data into it.
+    ///
+    /// See [`encode`](Self::encode) for more details on encoding.
+    ///
+    /// # Errors
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number.
+    /// * `Error::InvalidArguments` - If the data blocks do not have the same length.
+    pub fn encode_to_owned<T: AsRef<[u8]>>(
+        &self,
+        data: impl AsRef<[T]>,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        // an empty input is rejected by the buffer check of `encode`
+        let len = data.as_ref().first().map_or(0, |d| d.as_ref().len());
+        let mut code = vec![vec![0_u8; len]; self.code_num()];
+        self.encode(data, &mut code)?;
+        Ok(code)
+    }
+
+    /// Update parities from a delta of a single source data block.
+    ///
+    /// This method is used to update the parity data from a single source data block
+    /// without re-encoding all the data blocks.
+    ///
+    /// This method can also be used with a part of updated source block, see in the example.
+    /// Deltas shorter than 64 bytes, which the SIMD kernels of isa-l do not support,
+    /// are applied by a scalar fallback.
+    ///
+    /// # Arguments
+    /// * `index` - The index of the updated source data block.
+    /// * `delta` - The delta of the updated source data block, which is the xor of the old and new data.
+    /// * `code` - The code blocks to be updated with the new parity data.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::InvalidArguments` - If the index is out of range `0..source_num()`.
+    /// * `Error::InvalidArguments` - The code blocks number is not equal to the code number,
+    /// * `Error::InvalidArguments` - If the input data or code blocks do not have the same length.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let mut data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let mut parity: Vec<Vec<u8>> = vec![vec![0u8; BLOCK_LEN]; m.get()];
+    /// ec.encode(&data, &mut parity).expect("Encoding failed");
+    /// // Update the second quarter of the first source data block
+    /// let update_index = 0;
+    /// let range = BLOCK_LEN / 4.. BLOCK_LEN / 2;
+    /// let delta = vec![0xCC_u8; range.len()];
+    /// let parity_slice = parity.iter_mut().map(|p| &mut p[range.clone()]).collect::<Vec<_>>();
+    /// ec.update(update_index, delta.as_slice(), parity_slice).expect("Update failed");
+    /// // The parity blocks are updated correctly
+    /// data[0][range.clone()].iter_mut().for_each(|x| *x ^= 0xCC);
+    /// let mut expected_parity: Vec<Vec<u8>> = vec![vec![0u8; BLOCK_LEN]; m.get()];
+    /// ec.encode(&data, &mut expected_parity).expect("Re-encoding failed");
+    /// assert_eq!(parity, expected_parity);
+    /// ```
+    pub fn update<U: AsMut<[u8]>>(
+        &self,
+        index: usize,
+        delta: &[u8],
+        mut code: impl AsMut<[U]>,
+    ) -> Result<(), Error> {
+        self.check_update(index, delta, &mut code)?;
+        let mut code = code
+            .as_mut()
+            .iter_mut()
+            .map(AsMut::as_mut)
+            .collect::<Vec<_>>();
+        self.update_impl(index, delta, &mut code)
+    }
+
+    /// Computes the delta of an updated source data block for [`update`](Self::update), that is `old ^ new`.
+    ///
+    /// # Errors
+    /// * `Error::InvalidArguments` - If `old` and `new` do not have the same length.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let mut data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let mut parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    /// // Overwrite the first source data block, and update the parity
+    /// let new = vec![0xCC_u8; BLOCK_LEN];
+    /// let delta = ErasureCode::compute_delta(&data[0], &new).unwrap();
+    /// ec.update(0, &delta, &mut parity).expect("Update failed");
+    /// data[0] = new;
+    /// assert_eq!(parity, ec.encode_to_owned(&data).unwrap());
+    /// ```
+    pub fn compute_delta(old: &[u8], new: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut delta = vec![0_u8; old.len()];
+        Self::compute_delta_into(old, new, &mut delta)?;
+        Ok(delta)
+    }
+
+    /// Computes the delta of an updated source data block like [`compute_delta`](Self::compute_delta),
+    /// writing it into `delta`.
+    ///
+    /// # Errors
+    /// * `Error::InvalidArguments` - If `old`, `new` and `delta` do not have the same length.
+    pub fn compute_delta_into(old: &[u8], new: &[u8], delta: &mut [u8]) -> Result<(), Error> {
+        gf::xor(old, new, delta)
+    }
+
+    /// Update parities from the deltas of multiple source data blocks at once.
+    ///
+    /// This works like calling [`update`](Self::update) for each delta, except that all the deltas
+    /// are validated before any parity block is touched, and the deltas are applied parity block by
+    /// parity block, so that each parity block stays hot in cache while all the deltas are applied to it.
+    ///
+    /// # Arguments
+    /// * `updates` - The updates as pairs of `(index, delta)`, where `index` is the index of the
+    ///   updated source data block, and `delta` is the xor of its old and new data.
+    /// * `code` - The code blocks to be updated with the new parity data.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::InvalidArguments` - If any index is out of range `0..source_num()`.
+    /// * `Error::InvalidArguments` - The code blocks number is not equal to the code number,
+    /// * `Error::InvalidArguments` - If any delta or the code blocks do not have the same length.
+    ///
+    /// # Note
+    /// The same index may appear more than once, and all of its deltas are applied.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let mut data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let mut parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    /// // Update the first and the third source data blocks
+    /// let delta = vec![0xCC_u8; BLOCK_LEN];
+    /// ec.update_many(&[(0, &delta), (2, &delta)], &mut parity).expect("Update failed");
+    /// data[0].iter_mut().for_each(|x| *x ^= 0xCC);
+    /// data[2].iter_mut().for_each(|x| *x ^= 0xCC);
+    /// assert_eq!(parity, ec.encode_to_owned(&data).unwrap());
+    /// ```
+    pub fn update_many<U: AsMut<[u8]>>(
+        &self,
+        updates: &[(usize, &[u8])],
+        mut code: impl AsMut<[U]>,
+    ) -> Result<(), Error> {
+        for (index, delta) in updates {
+            self.check_update(*index, delta, &mut code)?;
+        }
+        for (row, block) in code.as_mut().iter_mut().enumerate() {
+            let block = block.as_mut();
+            for (index, delta) in updates {
+                // skip the deltas of the source blocks which do not affect the parity block
+                if self.encode_matrix[self.source_num() * (self.source_num() + row) + index] == 0 {
+                    continue;
+                }
+                self.update_row_impl(row, *index, delta, block)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Updates the parities after overwriting some source data blocks, choosing the cheaper way.
+    ///
+    /// Each delta costs `code_num()` multiply-and-add passes over the block, the same as a full encode
+    /// costs for each source data block, so updating by many deltas is no cheaper than re-encoding.
+    /// If the number of distinct changed blocks is at least [`DEFAULT_REENCODE_RATIO`] of `source_num()`,
+    /// the parities are re-encoded from `data`, otherwise they are updated by the deltas
+    /// like [`update_many`](Self::update_many).
+    /// Use [`apply_writes_with_ratio`](Self::apply_writes_with_ratio) to choose another ratio.
+    ///
+    /// # Arguments
+    /// * `data` - The source data blocks after the writes.
+    /// * `changed` - The writes as pairs of `(index, delta)`, where `index` is the index of the
+    ///   overwritten source data block, and `delta` is the xor of its old and new data.
+    /// * `code` - The code blocks of the old data, to be updated with the new parity data.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number,
+    ///   or the code blocks number is not equal to the code number.
+    /// * `Error::InvalidArguments` - If any index is out of range `0..source_num()`.
+    /// * `Error::InvalidArguments` - If the data blocks, the deltas or the code blocks do not have the same length.
+    ///
+    /// # Note
+    /// The deltas must be consistent with `data`, as only one of them is used to compute the parities.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let mut data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let mut parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    /// // Overwrite the first source data block
+    /// let new = vec![0xCC_u8; BLOCK_LEN];
+    /// let delta = ErasureCode::compute_delta(&data[0], &new).unwrap();
+    /// data[0] = new;
+    /// ec.apply_writes(&data, &[(0, &delta)], &mut parity).expect("Update failed");
+    /// assert_eq!(parity, ec.encode_to_owned(&data).unwrap());
+    /// ```
+    pub fn apply_writes<T: AsRef<[u8]>, U: AsMut<[u8]>>(
+        &self,
+        data: impl AsRef<[T]>,
+        changed: &[(usize, &[u8])],
+        code: impl AsMut<[U]>,
+    ) -> Result<(), Error> {
+        self.apply_writes_with_ratio(data, changed, code, DEFAULT_REENCODE_RATIO)
+    }
+
+    /// Updates the parities after overwriting some source data blocks like [`apply_writes`](Self::apply_writes),
+    /// re-encoding if the number of distinct changed blocks is at least `ratio` of `source_num()`.
+    ///
+    /// A `ratio` of `0.0` always re-encodes, and a `ratio` larger than `1.0` never does.
+    ///
+    /// # Errors
+    /// The same as [`apply_writes`](Self::apply_writes).
+    pub fn apply_writes_with_ratio<T: AsRef<[u8]>, U: AsMut<[u8]>>(
+        &self,
+        data: impl AsRef<[T]>,
+        changed: &[(usize, &[u8])],
+        mut code: impl AsMut<[U]>,
+        ratio: f64,
+    ) -> Result<(), Error> {
+        self.check_encode_buffer(&data, &mut code, self.code_num())?;
+        for (index, delta) in changed {
+            self.check_update(*index, delta, &mut code)?;
+        }
+        let mut indices = changed.iter().map(|(index, _)| *index).collect::<Vec<_>>();
+        indices.sort_unstable();
+        indices.dedup();
+        if indices.len() as f64 >= ratio * self.source_num() as f64 {
+            self.encode(data, code)
+        } else {
+            self.update_many(changed, code)
+        }
+    }
+
+    /// Returns the indices of the parity blocks affected by updating the source block `source_index`.
+    ///
+    /// A parity block is affected if its coefficient for the source block in the encode matrix is nonzero.
+    /// For the built-in Reed-Solomon and Cauchy matrices all the parity blocks are affected, but a
+    /// structured matrix may leave some parity blocks unchanged, and [`update`](Self::update) skips them.
+    ///
+    /// The returned indices are in range `0..code_num()`, in ascending order.
+    ///
+    /// # Errors
+    /// * `Error::InvalidArguments` - If `source_index` is out of range `0..source_num()`.
+    pub fn parities_affected_by(&self, source_index: usize) -> Result<Vec<usize>, Error> {
+        let k = self.source_num();
+        if source_index >= k {
+            return Err(Error::invalid_arguments(format!(
+                "index {} is out of range, max index is source number {}",
+                source_index,
+                k - 1
+            )));
+        }
+        Ok((0..self.code_num())
+            .filter(|p| self.encode_matrix[k * (k + p) + source_index] != 0)
+            .collect())
+    }
+
+    /// Decode the erased blocks from the surviving data and code blocks.
+    ///
+    /// The range of the blocks is `0..block_num()`.
+    /// The first `source_num()` blocks are the source data blocks, the next `code_num()` blocks are the code blocks.
+    ///
+    /// The content of the erased blocks will be recovered and written back to the `data` and `code` buffers.
+    /// And the content of the non-erased blocks will not be changed.
+    ///
+    /// # Arguments
+    /// * `data` - The data blocks, such as `Vec<Vec<u8>>`, or `Vec<&mut [u8]>` of views into a larger buffer.
+    /// * `code` - The code blocks, of the same kinds as `data`.
+    /// * `erasures` - The indices of the erased blocks.
+    ///
+    /// # Returns
+    /// The sorted and deduplicated indices of the recovered blocks.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::TooManyErasure` - If the number of erasures is larger than the code number.
+    /// * `Error::InvalidArguments` - If the erasure indices are out of range.
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number,
+    ///   or the code blocks number is not equal to the code number.
+    /// * `Error::InvalidArguments` - If the input data or code blocks do not have the same length
+    /// * `Error::Singular` - If the sub matrix of the surviving blocks is not invertible for the erasures.
+    ///   
+    /// # Note
+    /// The order of the indices in `erasures` does not matter, and the duplicated indices will
+    /// be treated as one erasure of the same block. The indices are deduplicated before they are counted,
+    /// so it is possible to pass more than the code number of indices as long as the distinct ones are not,
+    /// e.g. `[2, 2, 2]` is a single erasure for a code number of 2, while `[2, 3, 4]` is rejected.
+    /// Any slice-like type can be passed, such as an array or a `&[usize]`.
+    ///
+    /// A `DecodeTable` will be generated internally to perform the decoding, which is time consuming.
+    /// If you need to decode multiple times with the same erasures, you can use [`make_decode_table`](Self::make_decode_table) to generate a
+    /// `DecodeTable` and pass it to [`decode_with_table`](Self::decode_with_table) to avoid the overhead,
+    /// or enable the internal cache by [`set_cache_capacity`](Self::set_cache_capacity).
+    ///
+    /// If there is no erasure, only the buffers are validated, and no decode table is generated.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let code = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let mut parity: Vec<Vec<u8>> = vec![vec![0u8; BLOCK_LEN]; m.get()];
+    /// ec.encode(&data, &mut parity).expect("Encoding failed");
+    /// // Simulate erasures
+    /// let erasures = [2, 5]; // Assume blocks 2 and 5 are erased
+    /// let mut erased_data: Vec<Vec<u8>> = data.clone();
+    /// erased_data[2] = vec![0; BLOCK_LEN];
+    /// let mut erased_parity: Vec<Vec<u8>> = parity.clone();
+    /// erased_parity[1] = vec![0; BLOCK_LEN];
+    /// // Decode the erased blocks
+    /// let recovered = ec.decode(&mut erased_data, &mut erased_parity, erasures).expect("Decoding failed");
+    /// assert_eq!(recovered, vec![2, 5]);
+    /// // Verify that the erased blocks are recovered
+    /// assert_eq!(&data, &erased_data);
+    /// assert_eq!(&parity, &erased_parity);
+    /// ```
+    pub fn decode<U: AsMut<[u8]>>(
+        &self,
+        mut data: impl AsMut<[U]>,
+        mut code: impl AsMut<[U]>,
+        erasures: impl AsRef<[usize]>,
+    ) -> Result<Vec<usize>, Error> {
+        let erasures = self.check_decode_erasure(erasures.as_ref())?;
+        self.check_decode_buffer(&mut data, &mut code)?;
+        if erasures.is_empty() {
+            // nothing to recover, neither the decode table nor isa-l is needed
+            return Ok(erasures);
+        }
+        if let [erased] = erasures[..]
+            && self.is_xor_parity()
+        {
+            // RAID5, the erased block is the xor of all the other blocks
+            let mut lost = None;
+            let mut survivors = Vec::with_capacity(self.source_num());
+            for (i, block) in data
+                .as_mut()
+                .iter_mut()
+                .chain(code.as_mut().iter_mut())
+                .enumerate()
+            {
+                if i == erased {
+                    lost = Some(block.as_mut());
+                } else {
+                    survivors.push(&*block.as_mut());
+                }
+            }
+            xor_blocks(survivors, lost.unwrap())?;
+            return Ok(erasures);
+        }
+        let len = data.as_mut()[0].as_mut().len();
+        #[cfg(feature = "std")]
+        if let Some(cache) = &self.decode_cache {
+            let decode_gf_table = cache
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .get_or_make(self, &erasures)?;
+            self.decode_impl(data, code, &decode_gf_table.table, erasures.as_slice(), len)?;
+            return Ok(erasures);
+        }
+        let decode_gf_table = self.make_decode_table_impl(erasures.as_slice())?;
+        self.decode_impl(data, code, &decode_gf_table.table, erasures.as_slice(), len)?;
+        Ok(erasures)
+    }
+
+    /// Decodes the erased blocks like [`decode`](Self::decode), and measures the time spent
+    /// generating the decode table and computing the erased blocks.
+    ///
+    /// The numbers tell whether precomputing the decode tables by [`make_decode_table`](Self::make_decode_table)
+    /// pays off for a distribution of the erasure patterns.
+    ///
+    /// # Arguments
+    /// * `data` - The source data blocks.
+    /// * `code` - The code blocks.
+    /// * `erasures` - The indices of the erased blocks.
+    ///
+    /// # Returns
+    /// The sorted and deduplicated indices of the recovered blocks, and the [`DecodeStats`] of the decoding.
+    ///
+    /// # Errors
+    /// The same as [`decode`](Self::decode).
+    ///
+    /// # Note
+    /// The decode table is always generated, the internal cache and the xor shortcut of
+    /// [`decode`](Self::decode) are bypassed, so that the stats measure the general path.
+    /// If there is no erasure, nothing is measured and the stats are all zero.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let mut data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let mut parity = ec.encode_to_owned(&data).unwrap();
+    /// data[1].fill(0);
+    /// let (recovered, stats) = ec.decode_with_stats(&mut data, &mut parity, [1, 4]).unwrap();
+    /// assert_eq!(recovered, vec![1, 4]);
+    /// assert_eq!(data[1], vec![1_u8; BLOCK_LEN]);
+    /// assert_eq!(stats.bytes_processed(), 2 * BLOCK_LEN as u64);
+    /// println!("table: {}ns, compute: {}ns", stats.table_gen_ns(), stats.compute_ns());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn decode_with_stats<U: AsMut<[u8]>>(
+        &self,
+        mut data: impl AsMut<[U]>,
+        mut code: impl AsMut<[U]>,
+        erasures: impl AsRef<[usize]>,
+    ) -> Result<(Vec<usize>, DecodeStats), Error> {
+        let erasures = self.check_decode_erasure(erasures.as_ref())?;
+        self.check_decode_buffer(&mut data, &mut code)?;
+        if erasures.is_empty() {
+            return Ok((erasures, DecodeStats::default()));
+        }
+        let len = data.as_mut()[0].as_mut().len();
+        let elapsed_ns = |start: Instant| start.elapsed().as_nanos().try_into().unwrap_or(u64::MAX);
+        let start = Instant::now();
+        let decode_gf_table = self.make_decode_table_impl(erasures.as_slice())?;
+        let table_gen_ns = elapsed_ns(start);
+        let start = Instant::now();
+        self.decode_impl(data, code, &decode_gf_table.table, erasures.as_slice(), len)?;
+        let compute_ns = elapsed_ns(start);
+        let stats = DecodeStats {
+            table_gen_ns,
+            compute_ns,
+            bytes_processed: (len * erasures.len()) as u64,
+        };
+        Ok((erasures, stats))
+    }
+
+    /// Decodes the erased blocks of a stripe held in a single slice of `block_num()` blocks.
+    ///
+    /// This works like [`decode`](Self::decode), with the first `source_num()` blocks of the stripe
+    /// as the data blocks and the last `code_num()` blocks as the code blocks,
+    /// see [`encode_stripe`](Self::encode_stripe).
+    ///
+    /// # Returns
+    /// The sorted and deduplicated indices of the recovered blocks.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::InvalidArguments` - If the stripe blocks number is not equal to the block number.
+    /// * Any error of [`decode`](Self::decode).
+    pub fn decode_stripe<U: AsMut<[u8]>>(
+        &self,
+        mut stripe: impl AsMut<[U]>,
+        erasures: impl AsRef<[usize]>,
+    ) -> Result<Vec<usize>, Error> {
+        let (data, code) = self.split_stripe(stripe.as_mut())?;
+        self.decode(data, code, erasures)
+    }
+
+    /// Decode the blocks missing from the `available` blocks, like [`decode`](Self::decode).
+    ///
+    /// The erasures are the complement of `available` in `0..block_num()`, so the caller can pass
+    /// the blocks it holds instead of the blocks it lost.
+    ///
+    /// # Arguments
+    /// * `data` - The data blocks.
+    /// * `code` - The code blocks.
+    /// * `available` - The indices of the available blocks, in range `0..block_num()`.
+    ///
+    /// # Returns
+    /// The sorted indices of the recovered blocks.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::TooManyErasure` - If less than `source_num()` blocks are available.
+    /// * `Error::InvalidArguments` - If the available indices are out of range.
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number,
+    ///   or the code blocks number is not equal to the code number.
+    /// * `Error::InvalidArguments` - If the input data or code blocks do not have the same length
+    /// * `Error::Singular` - If the sub matrix of the surviving blocks is not invertible for the erasures.
+    ///
+    /// # Note
+    /// The order of the indices in `available` does not matter, and the duplicated indices are treated as one.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    /// let mut erased_data = data.clone();
+    /// erased_data[1].fill(0);
+    /// let mut erased_parity = parity.clone();
+    /// erased_parity[1].fill(0);
+    /// let recovered = ec
+    ///     .decode_from_available(&mut erased_data, &mut erased_parity, [0, 2, 3, 4])
+    ///     .expect("Decoding failed");
+    /// assert_eq!(recovered, vec![1, 5]);
+    /// assert_eq!(erased_data, data);
+    /// assert_eq!(erased_parity, parity);
+    /// ```
+    pub fn decode_from_available<U: AsMut<[u8]>>(
+        &self,
+        data: impl AsMut<[U]>,
+        code: impl AsMut<[U]>,
+        available: impl AsRef<[usize]>,
+    ) -> Result<Vec<usize>, Error> {
+        let mut is_available = vec![false; self.block_num()];
+        for &i in available.as_ref() {
+            if i >= self.block_num() {
+                return Err(Error::invalid_arguments(format!(
+                    "available index {} is out of range, block number is {}",
+                    i,
+                    self.block_num()
+                )));
+            }
+            is_available[i] = true;
+        }
+        let erasures = (0..self.block_num())
+            .filter(|i| !is_available[*i])
+            .collect::<Vec<_>>();
+        self.decode(data, code, erasures)
+    }
+
+    /// Decode the erased blocks from the surviving data and code blocks using a pre-generated `DecodeTable`.
+    ///
+    /// The range of the blocks is `0..block_num()`.
+    /// The first `source_num()` blocks are the source data blocks, the next `code_num()` blocks are the code blocks.
+    ///
+    /// The content of the erased blocks will be recovered and written back to the `data` and `code` buffers.
+    /// And the content of the non-erased blocks will not be changed.
+    ///
+    /// A `DecodeTable` can be generated by [`make_decode_table`](Self::make_decode_table) method,
+    /// and it can be reused for multiple decode operations with the same erasures.
+    /// If you only need to decode once, you can use [`decode`](Self::decode) method instead,
+    /// which will generate the `DecodeTable` internally.
+    ///
+    /// # Arguments
+    /// * `data` - The data blocks.
+    /// * `code` - The code blocks.
+    /// * `decode_table` - The pre-generated `DecodeTable` for decoding.
+    /// * `erasures` - The indices of the erased blocks.
+    ///
+    /// # Returns
+    /// The sorted and deduplicated indices of the recovered blocks.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::TooManyErasure` - If the number of erasures is larger than the code number.
+    /// * `Error::InvalidArguments` - If the erasure indices are out of range.
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number,
+    ///   or the code blocks number is not equal to the code number.
+    /// * `Error::InvalidArguments` - If the input data or code blocks do not have the same length
+    /// * `Error::InvalidArguments` - If the `decode_table` is generated by a code of a different geometry,
+    ///   or of a different matrix.
+    /// * `Error::InvalidArguments` - If the `decode_table` is generated for erasures other than `erasures`.
+    /// * `Error::Singular` - If the sub matrix of the surviving blocks is not invertible for the erasures.
+    ///   
+    /// # Note
+    /// The order of the indices in `erasures` does not matter, and the duplicated indices will
+    /// be treated as one erasure of the same block. The indices are deduplicated before they are counted,
+    /// so it is possible to pass more than the code number of indices as long as the distinct ones are not,
+    /// e.g. `[2, 2, 2]` is a single erasure for a code number of 2, while `[2, 3, 4]` is rejected.
+    /// Any slice-like type can be passed, such as an array or a `&[usize]`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::{ErasureCode, DecodeTable};
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let mut parity: Vec<Vec<u8>> = vec![vec![0u8; BLOCK_LEN]; m.get()];
+    /// ec.encode(&data, &mut parity).expect("Encoding failed");
+    /// // Simulate erasures
+    /// let erasures = [2, 5]; // Assume blocks 2 and 5 are erased
+    /// let mut erased_data: Vec<Vec<u8>> = data.clone();
+    /// erased_data[2] = vec![0; BLOCK_LEN];
+    /// let mut erased_parity: Vec<Vec<u8>> = parity.clone();
+    /// erased_parity[1] = vec![0; BLOCK_LEN];
+    /// // Generate a decode table for the erasures
+    /// let decode_table = ec.make_decode_table(erasures).expect("Failed to make decode table");
+    /// // Decode the erased blocks using the decode table
+    /// ec.decode_with_table(&mut erased_data, &mut erased_parity, &decode_table, erasures).expect("Decoding failed");
+    /// // Verify that the erased blocks are recovered
+    /// assert_eq!(&data, &erased_data);
+    /// assert_eq!(&parity, &erased_parity);
+    /// ```
+    pub fn decode_with_table<U>(
+        &self,
+        mut data: impl AsMut<[U]>,
+        mut code: impl AsMut<[U]>,
+        decode_table: &DecodeTable,
+        erasures: impl AsRef<[usize]>,
+    ) -> Result<Vec<usize>, Error>
+    where
+        U: AsMut<[u8]>,
+    {
+        let erasures = self.check_decode_erasure(erasures.as_ref())?;
+        self.check_decode_buffer(&mut data, &mut code)?;
+        self.check_decode_table(decode_table, &erasures)?;
+        if erasures.is_empty() {
+            return Ok(erasures);
+        }
+        let len = data.as_mut()[0].as_mut().len();
+        self.decode_impl(data, code, &decode_table.table, erasures.as_slice(), len)?;
+        Ok(erasures)
+    }
+
+    /// Decodes many stripes sharing the same erasures, generating the decode table only once.
+    ///
+    /// This is the bulk repair of a failed disk, where every stripe loses the same blocks.
+    /// All the stripes are validated before any of them is decoded, so an invalid stripe
+    /// leaves all the stripes untouched. The stripes may have different block lengths.
+    ///
+    /// # Arguments
+    /// * `stripes` - The `(data, code)` blocks of each stripe.
+    /// * `erasures` - The indices of the erased blocks, shared by all the stripes.
+    ///
+    /// # Returns
+    /// The sorted and deduplicated indices of the recovered blocks of each stripe.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::TooManyErasure` - If the number of erasures is larger than the code number.
+    /// * `Error::InvalidArguments` - If the erasure indices are out of range.
+    /// * `Error::InvalidArguments` - If the blocks of a stripe are invalid as for [`decode`](Self::decode),
+    ///   the message tells the index of the stripe.
+    /// * `Error::Singular` - If the sub matrix of the surviving blocks is not invertible for the erasures.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let mut stripes = (0..8)
+    ///     .map(|s| {
+    ///         let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![(s + i) as u8; BLOCK_LEN]).collect();
+    ///         let parity = ec.encode_to_owned(&data).unwrap();
+    ///         (data, parity)
+    ///     })
+    ///     .collect::<Vec<_>>();
+    /// let orig = stripes.clone();
+    /// // The disk of block 1 fails
+    /// stripes.iter_mut().for_each(|(data, _)| data[1].fill(0));
+    /// ec.decode_batch(&mut stripes, [1]).expect("Decoding failed");
+    /// assert_eq!(stripes, orig);
+    /// ```
+    pub fn decode_batch<D, C, U>(
+        &self,
+        stripes: &mut [(D, C)],
+        erasures: impl AsRef<[usize]>,
+    ) -> Result<Vec<usize>, Error>
+    where
+        D: AsMut<[U]>,
+        C: AsMut<[U]>,
+        U: AsMut<[u8]>,
+    {
+        let erasures = self.check_decode_erasure(erasures.as_ref())?;
+        self.check_batch_buffer(stripes)?;
+        if erasures.is_empty() {
+            return Ok(erasures);
+        }
+        let table = self.make_decode_table_impl(&erasures)?;
+        for (data, code) in stripes.iter_mut() {
+            let len = data.as_mut()[0].as_mut().len();
+            self.decode_impl(data, code, &table.table, &erasures, len)?;
+        }
+        Ok(erasures)
+    }
+
+    /// Decodes many stripes sharing the same erasures like [`decode_batch`](Self::decode_batch),
+    /// decoding the stripes concurrently on the rayon thread pool.
+    ///
+    /// This method is only available with the `rayon` feature enabled.
+    ///
+    /// # Errors
+    /// The same as [`decode_batch`](Self::decode_batch).
+    #[cfg(feature = "rayon")]
+    pub fn decode_batch_parallel<D, C, U>(
+        &self,
+        stripes: &mut [(D, C)],
+        erasures: impl AsRef<[usize]>,
+    ) -> Result<Vec<usize>, Error>
+    where
+        D: AsMut<[U]> + Send,
+        C: AsMut<[U]> + Send,
+        U: AsMut<[u8]>,
+    {
+        use rayon::prelude::*;
+
+        let erasures = self.check_decode_erasure(erasures.as_ref())?;
+        self.check_batch_buffer(stripes)?;
+        if erasures.is_empty() {
+            return Ok(erasures);
+        }
+        let table = self.make_decode_table_impl(&erasures)?;
+        stripes.par_iter_mut().try_for_each(|(data, code)| {
+            let len = data.as_mut()[0].as_mut().len();
+            self.decode_impl(data, code, &table.table, &erasures, len)
+        })?;
+        Ok(erasures)
+    }
+
+    /// Decodes the first `len` bytes of the erased blocks from the surviving data and code blocks.
+    ///
+    /// This is the decoding counterpart of [`encode_len`](Self::encode_len), and it works like
+    /// [`decode`](Self::decode) except that only the first `len` bytes of each block are used.
+    /// The bytes beyond `len` of each block are left untouched.
+    ///
+    /// # Arguments
+    /// * `data` - The data blocks.
+    /// * `code` - The code blocks.
+    /// * `erasures` - The indices of the erased blocks.
+    /// * `len` - The number of bytes to be decoded of each block.
+    ///
+    /// # Returns
+    /// The sorted and deduplicated indices of the recovered blocks.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::TooManyErasure` - If the number of erasures is larger than the code number.
+    /// * `Error::InvalidArguments` - If the erasure indices are out of range.
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number,
+    ///   or the code blocks number is not equal to the code number.
+    /// * `Error::InvalidArguments` - If any data or code block is shorter than `len`.
+    /// * `Error::Singular` - If the sub matrix of the surviving blocks is not invertible for the erasures.
+    pub fn decode_len<U: AsMut<[u8]>>(
+        &self,
+        mut data: impl AsMut<[U]>,
+        mut code: impl AsMut<[U]>,
+        erasures: impl AsRef<[usize]>,
+        len: usize,
+    ) -> Result<Vec<usize>, Error> {
+        let erasures = self.check_decode_erasure(erasures.as_ref())?;
+        self.check_len_buffer(
+            data.as_mut().iter_mut().map(|s| s.as_mut().len()),
+            code.as_mut().iter_mut().map(|s| s.as_mut().len()),
+            len,
+        )?;
+        if erasures.is_empty() {
+            return Ok(erasures);
+        }
+        let decode_gf_table = self.make_decode_table_impl(erasures.as_slice())?;
+        self.decode_impl(data, code, &decode_gf_table.table, erasures.as_slice(), len)?;
+        Ok(erasures)
+    }
+
+    /// Decode only the `wanted` blocks among the erased blocks from the surviving data and code blocks.
+    ///
+    /// This works like [`decode`](Self::decode), except that only the decode matrix rows of the `wanted`
+    /// blocks are computed, and the other erased blocks are left untouched. It cuts the latency of
+    /// degraded reads, when only some of the erased blocks are urgently needed.
+    ///
+    /// # Arguments
+    /// * `data` - The data blocks.
+    /// * `code` - The code blocks.
+    /// * `erasures` - The indices of the erased blocks.
+    /// * `wanted` - The indices of the erased blocks to be recovered, which must be a subset of `erasures`.
+    ///
+    /// # Returns
+    /// The sorted and deduplicated indices of the recovered blocks.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::TooManyErasure` - If the number of erasures is larger than the code number.
+    /// * `Error::InvalidArguments` - If the erasure indices are out of range.
+    /// * `Error::InvalidArguments` - If any wanted index is not in `erasures`.
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number,
+    ///   or the code blocks number is not equal to the code number.
+    /// * `Error::InvalidArguments` - If the input data or code blocks do not have the same length
+    /// * `Error::Singular` - If the sub matrix of the surviving blocks is not invertible for the erasures.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let mut parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    /// // Blocks 1 and 2 are erased, but only block 2 is needed
+    /// let mut erased_data = data.clone();
+    /// erased_data[1].fill(0);
+    /// erased_data[2].fill(0);
+    /// let recovered = ec.decode_subset(&mut erased_data, &mut parity, [1, 2], [2]).expect("Decoding failed");
+    /// assert_eq!(recovered, vec![2]);
+    /// assert_eq!(erased_data[2], data[2]);
+    /// assert_eq!(erased_data[1], vec![0; BLOCK_LEN]);
+    /// ```
+    pub fn decode_subset<U: AsMut<[u8]>>(
+        &self,
+        mut data: impl AsMut<[U]>,
+        mut code: impl AsMut<[U]>,
+        erasures: impl AsRef<[usize]>,
+        wanted: impl AsRef<[usize]>,
+    ) -> Result<Vec<usize>, Error> {
+        let erasures = self.check_decode_erasure(erasures.as_ref())?;
+        let mut wanted = wanted.as_ref().to_vec();
+        wanted.sort_unstable();
+        wanted.dedup();
+        if let Some(w) = wanted.iter().find(|w| erasures.binary_search(w).is_err()) {
+            return Err(Error::invalid_arguments(format!(
+                "wanted index {} is not erased",
+                w
+            )));
+        }
+        self.check_decode_buffer(&mut data, &mut code)?;
+        if wanted.is_empty() {
+            return Ok(wanted);
+        }
+        let matrix = self.make_wanted_decode_matrix(&erasures, &wanted)?;
+        let table = galois::GaloisFiledTable::try_from_matrix(
+            &matrix[..self.source_num() * wanted.len()],
+            wanted.len(),
+            self.source_num(),
+        )?;
+        let len = data.as_mut()[0].as_mut().len();
+        self.decode_wanted_impl(data, code, &table, &erasures, &wanted, len)?;
+        Ok(wanted)
+    }
+
+    /// Decode only the erased source data blocks, leaving the erased code blocks untouched.
+    ///
+    /// This works like [`decode_subset`](Self::decode_subset) with the erased data blocks as the wanted blocks,
+    /// which suits degraded reads where the repair of the code blocks is deferred.
+    /// The erased code blocks still count as erasures, so they are never read as surviving blocks.
+    ///
+    /// # Arguments
+    /// * `data` - The data blocks.
+    /// * `code` - The code blocks.
+    /// * `erasures` - The indices of the erased blocks, both data and code blocks.
+    ///
+    /// # Returns
+    /// The sorted and deduplicated indices of the recovered data blocks.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::TooManyErasures` - If the number of erasures is larger than the code number,
+    ///   so that less than `source_num()` blocks survive.
+    /// * `Error::InvalidArguments` - If the erasure indices are out of range.
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number,
+    ///   or the code blocks number is not equal to the code number.
+    /// * `Error::InvalidArguments` - If the input data or code blocks do not have the same length
+    /// * `Error::Singular` - If the sub matrix of the surviving blocks is not invertible for the erasures.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let mut parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    /// // Block 1 and parity block 4 are erased
+    /// let mut erased_data = data.clone();
+    /// erased_data[1].fill(0);
+    /// parity[0].fill(0);
+    /// let recovered = ec.decode_data_only(&mut erased_data, &mut parity, [1, 4]).expect("Decoding failed");
+    /// assert_eq!(recovered, vec![1]);
+    /// assert_eq!(erased_data, data);
+    /// assert_eq!(parity[0], vec![0; BLOCK_LEN]);
+    /// ```
+    pub fn decode_data_only<U: AsMut<[u8]>>(
+        &self,
+        data: impl AsMut<[U]>,
+        code: impl AsMut<[U]>,
+        erasures: impl AsRef<[usize]>,
+    ) -> Result<Vec<usize>, Error> {
+        let erasures = erasures.as_ref();
+        let wanted = erasures
+            .iter()
+            .copied()
+            .filter(|e| *e < self.source_num())
+            .collect::<Vec<_>>();
+        self.decode_subset(data, code, erasures, wanted)
+    }
+
+    /// Decode the erased blocks into newly allocated buffers, and return only the recovered blocks.
+    ///
+    /// This works like [`decode`](Self::decode), except that the `data` and `code` blocks are only read,
+    /// so the surviving blocks can live in an immutable cache, and the erased blocks are recovered into
+    /// internal buffers instead of being written back. The content of the erased blocks in `data` and
+    /// `code` is ignored, but they must still have the same length as the other blocks.
+    /// The internal cache of `DecodeTable`s is used like [`decode`](Self::decode) does,
+    /// and no decode table is generated if there is no erasure.
+    ///
+    /// # Arguments
+    /// * `data` - The data blocks.
+    /// * `code` - The code blocks.
+    /// * `erasures` - The indices of the erased blocks.
+    ///
+    /// # Returns
+    /// The recovered blocks as pairs of `(index, block)`, sorted and deduplicated by the index.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::TooManyErasure` - If the number of erasures is larger than the code number.
+    /// * `Error::InvalidArguments` - If the erasure indices are out of range.
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number,
+    ///   or the code blocks number is not equal to the code number.
+    /// * `Error::InvalidArguments` - If the input data or code blocks do not have the same length
+    /// * `Error::Singular` - If the sub matrix of the surviving blocks is not invertible for the erasures.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    /// // Blocks 2 and 5 are erased, and the buffers are left untouched
+    /// let recovered = ec.decode_to_owned(&data, &parity, [5, 2]).expect("Decoding failed");
+    /// assert_eq!(recovered, vec![(2, data[2].clone()), (5, parity[1].clone())]);
+    /// ```
+    pub fn decode_to_owned<T: AsRef<[u8]>>(
+        &self,
+        data: impl AsRef<[T]>,
+        code: impl AsRef<[T]>,
+        erasures: impl AsRef<[usize]>,
+    ) -> Result<Vec<(usize, Vec<u8>)>, Error> {
+        let erasures = self.check_decode_erasure(erasures.as_ref())?;
+        let (data, code) = (data.as_ref(), code.as_ref());
+        let len = self.check_decode_source(data, code)?;
+        if erasures.is_empty() {
+            return Ok(Vec::new());
+        }
+        let recover = |decode_gf_table: &DecodeTable| {
+            let mut recovered = erasures
+                .iter()
+                .map(|&i| (i, vec![0_u8; len]))
+                .collect::<Vec<_>>();
+            let recover_src = data
+                .iter()
+                .chain(code)
+                .enumerate()
+                .filter(|(i, _)| erasures.binary_search(i).is_err())
+                .map(|(_, block)| block.as_ref().as_ptr())
+                .collect::<Vec<_>>();
+            let mut recover_output = recovered
+                .iter_mut()
+                .map(|(_, block)| block.as_mut_ptr())
+                .collect::<Vec<_>>();
+            ec::encode_data(
+                to_i32(len, "block length")?,
+                self.k_i32(),
+                to_i32(erasures.len(), "erasures number")?,
+                &decode_gf_table.table,
+                &recover_src,
+                &mut recover_output,
+            );
+            Ok(recovered)
+        };
+        #[cfg(feature = "std")]
+        if let Some(cache) = &self.decode_cache {
+            let decode_gf_table = cache
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .get_or_make(self, &erasures)?;
+            return recover(&decode_gf_table);
+        }
+        recover(&self.make_decode_table_impl(erasures.as_slice())?)
+    }
+
+    /// Decode the erased blocks from a bag of the surviving blocks into newly allocated buffers.
+    ///
+    /// This works like [`decode_to_owned`](Self::decode_to_owned), except that only the surviving blocks
+    /// are passed, by their indices, so no buffer needs to be provided for the erased blocks.
+    ///
+    /// # Arguments
+    /// * `survivors` - The surviving blocks as pairs of `(index, block)`, the index is in range `0..block_num()`.
+    /// * `erasures` - The indices of the erased blocks.
+    ///
+    /// # Returns
+    /// The recovered blocks as pairs of `(index, block)`, sorted and deduplicated by the index.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::TooManyErasures` - If the number of erasures is larger than the code number,
+    ///   or there are less than `source_num()` surviving blocks.
+    /// * `Error::InvalidArguments` - If the erasure or survivor indices are out of range,
+    ///   or the survivor indices are duplicated.
+    /// * `Error::InvalidArguments` - If an erased block is also passed as a surviving block.
+    /// * `Error::InvalidArguments` - If the surviving blocks do not have the same length.
+    /// * `Error::InvalidArguments` - If the surviving blocks do not form an invertible set.
+    ///
+    /// # Note
+    /// More than `source_num()` surviving blocks may be passed, an invertible subset of them is used.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    /// // Blocks 2 and 5 are lost
+    /// let survivors = [(0, data[0].as_slice()), (1, &data[1]), (3, &data[3]), (4, &parity[0])];
+    /// let recovered = ec.decode_alloc(&survivors, &[5, 2]).expect("Decoding failed");
+    /// assert_eq!(recovered, vec![(2, data[2].clone()), (5, parity[1].clone())]);
+    /// ```
+    pub fn decode_alloc(
+        &self,
+        survivors: &[(usize, &[u8])],
+        erasures: &[usize],
+    ) -> Result<Vec<(usize, Vec<u8>)>, Error> {
+        let erasures = self.check_decode_erasure(erasures)?;
+        if let Some((i, _)) = survivors
+            .iter()
+            .find(|(i, _)| erasures.binary_search(i).is_ok())
+        {
+            return Err(Error::invalid_arguments(format!(
+                "block {} is both erased and surviving",
+                i
+            )));
+        }
+        self.decode_target(survivors, &erasures)
+    }
+
+    /// Decode the erased blocks like [`decode`](Self::decode), resizing the erased buffers to the block length.
+    ///
+    /// The erased buffers are outputs anyway, so they need not be pre-sized: any erased buffer with
+    /// a wrong length (e.g. an empty one) is resized to the common length of the surviving blocks
+    /// before decoding, rather than being rejected.
+    ///
+    /// # Arguments
+    /// * `data` - The data blocks.
+    /// * `code` - The code blocks.
+    /// * `erasures` - The indices of the erased blocks.
+    ///
+    /// # Returns
+    /// The sorted and deduplicated indices of the recovered blocks.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::TooManyErasure` - If the number of erasures is larger than the code number.
+    /// * `Error::InvalidArguments` - If the erasure indices are out of range.
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number,
+    ///   or the code blocks number is not equal to the code number.
+    /// * `Error::InvalidArguments` - If the surviving data or code blocks do not have the same length.
+    /// * `Error::Singular` - If the sub matrix of the surviving blocks is not invertible for the erasures.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    /// // The erased buffers are left empty
+    /// let mut erased_data = data.clone();
+    /// erased_data[0] = Vec::new();
+    /// let mut erased_parity = parity.clone();
+    /// erased_parity[1] = Vec::new();
+    /// ec.decode_resizing(&mut erased_data, &mut erased_parity, vec![0, 5]).expect("Decoding failed");
+    /// assert_eq!(&data, &erased_data);
+    /// assert_eq!(&parity, &erased_parity);
+    /// ```
+    pub fn decode_resizing(
+        &self,
+        mut data: impl AsMut<[Vec<u8>]>,
+        mut code: impl AsMut<[Vec<u8>]>,
+        erasures: impl AsRef<[usize]>,
+    ) -> Result<Vec<usize>, Error> {
+        let erasures = self.check_decode_erasure(erasures.as_ref())?;
+        let (data, code) = (data.as_mut(), code.as_mut());
+        if data.len() != self.source_num() || code.len() != self.code_num() {
+            // report the mismatched number of blocks
+            self.check_decode_buffer(&mut *data, &mut *code)?;
+        }
+        let mut survivor_lens = data
+            .iter()
+            .chain(code.iter())
+            .enumerate()
+            .filter(|(i, _)| erasures.binary_search(i).is_err())
+            .map(|(_, block)| block.len());
+        let len = survivor_lens.next().unwrap_or_default();
+        if survivor_lens.any(|l| l != len) {
+            return Err(Error::invalid_arguments(
+                "surviving data and code blocks must be equal",
+            ));
+        }
+        data.iter_mut()
+            .chain(code.iter_mut())
+            .enumerate()
+            .filter(|(i, _)| erasures.binary_search(i).is_ok())
+            .for_each(|(_, block)| block.resize(len, 0));
+        self.decode(data, code, erasures)
+    }
+
+    /// Decode the erased blocks like [`decode`](Self::decode), recovering as many of them as possible
+    /// if the decode matrix is singular.
+    ///
+    /// `decode` only reads the first `source_num()` surviving blocks, whose sub matrix may be singular
+    /// with a matrix not guaranteeing every sub matrix to be invertible, such as the Vandermonde one.
+    /// In that case, all the surviving blocks are taken into account instead: if some `source_num()`
+    /// of them are independent, all the erased blocks are recovered, otherwise each erased block
+    /// spanned by the surviving blocks is recovered, and the others are reported as unrecovered.
+    ///
+    /// The content of the recovered blocks will be written back to the `data` and `code` buffers,
+    /// and the content of the unrecovered and non-erased blocks will not be changed.
+    ///
+    /// # Arguments
+    /// * `data` - The data blocks.
+    /// * `code` - The code blocks.
+    /// * `erasures` - The indices of the erased blocks.
+    ///
+    /// # Returns
+    /// The [`DecodeOutcome`] listing the recovered and the unrecovered blocks.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::TooManyErasure` - If the number of erasures is larger than the code number.
+    /// * `Error::InvalidArguments` - If the erasure indices are out of range.
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number,
+    ///   or the code blocks number is not equal to the code number.
+    /// * `Error::InvalidArguments` - If the input data or code blocks do not have the same length.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_cauchy(k, m).unwrap();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    /// let mut erased_data = data.clone();
+    /// erased_data[1].fill(0);
+    /// let mut erased_parity = parity.clone();
+    /// let outcome = ec
+    ///     .decode_best_effort(&mut erased_data, &mut erased_parity, [1])
+    ///     .expect("Decoding failed");
+    /// assert!(outcome.is_complete());
+    /// assert_eq!(outcome.recovered(), &[1]);
+    /// assert_eq!(erased_data, data);
+    /// ```
+    pub fn decode_best_effort<U: AsMut<[u8]>>(
+        &self,
+        mut data: impl AsMut<[U]>,
+        mut code: impl AsMut<[U]>,
+        erasures: impl AsRef<[usize]>,
+    ) -> Result<DecodeOutcome, Error> {
+        let erasures = self.check_decode_erasure(erasures.as_ref())?;
+        match self.decode(&mut data, &mut code, &erasures) {
+            Ok(recovered) => {
+                return Ok(DecodeOutcome {
+                    recovered,
+                    unrecovered: Vec::new(),
+                });
+            }
+            Err(Error::Singular(_)) => {}
+            Err(e) => return Err(e),
+        }
+        // the first `source_num()` survivors are singular, try all the survivors instead
+        let survivors = (0..self.block_num())
+            .filter(|i| erasures.binary_search(i).is_err())
+            .collect::<Vec<_>>();
+        let mut recovered = Vec::with_capacity(erasures.len());
+        let mut unrecovered = Vec::new();
+        let mut matrix = Vec::with_capacity(erasures.len() * survivors.len());
+        for &e in &erasures {
+            match self.span_coefficients(&survivors, e) {
+                Some(coefficients) => {
+                    matrix.extend(coefficients);
+                    recovered.push(e);
+                }
+                None => unrecovered.push(e),
+            }
+        }
+        if !recovered.is_empty() {
+            let table = galois::GaloisFiledTable::try_from_matrix(
+                &matrix,
+                recovered.len(),
+                survivors.len(),
+            )?;
+            let len = to_i32(data.as_mut()[0].as_mut().len(), "block length")?;
+            let survivors_num = to_i32(survivors.len(), "survivors number")?;
+            let erasures_num = to_i32(recovered.len(), "erasures number")?;
+            // no block is borrowed again after the pointers are collected
+            let blocks = data
+                .as_mut()
+                .iter_mut()
+                .chain(code.as_mut().iter_mut())
+                .map(|block| block.as_mut().as_mut_ptr())
+                .collect::<Vec<_>>();
+            let src_ptrs = survivors
+                .iter()
+                .map(|&i| blocks[i].cast_const())
+                .collect::<Vec<_>>();
+            let mut out_ptrs = recovered.iter().map(|&i| blocks[i]).collect::<Vec<_>>();
+            ec::encode_data(
+                len,
+                survivors_num,
+                erasures_num,
+                &table,
+                &src_ptrs,
+                &mut out_ptrs,
+            );
+        }
+        Ok(DecodeOutcome {
+            recovered,
+            unrecovered,
+        })
+    }
+
+    /// Returns whether the erased blocks can be recovered by [`decode`](Self::decode).
+    ///
+    /// This is a cheap check before committing to an expensive decode, no block is touched.
+    /// It returns `false` if the number of deduplicated erasures is larger than the code number,
+    /// or any erasure index is out of range. Otherwise, it goes further and inverts the decode
+    /// matrix like `decode` does, and returns `false` if the matrix is singular, which may happen
+    /// for some erasure patterns of a Reed-Solomon code.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_cauchy(k, m).unwrap();
+    /// assert!(ec.is_recoverable(&[1, 4]));
+    /// assert!(ec.is_recoverable(&[1, 1, 4]));
+    /// assert!(!ec.is_recoverable(&[0, 1, 4]));
+    /// assert!(!ec.is_recoverable(&[6]));
+    /// ```
+    pub fn is_recoverable(&self, erasures: &[usize]) -> bool {
+        self.check_decode_erasure(erasures)
+            .and_then(|erasures| self.make_decode_matrix(&erasures))
+            .is_ok()
+    }
+
+    /// Returns the decode matrix of the erasures, which is what the `DecodeTable` of the erasures is generated from.
+    ///
+    /// The matrix is `block_num()` x `source_num()` in row-major order, laid out as the buffer passed to isa-l:
+    /// * The row `i` holds the coefficients recovering the `i`-th erased block in the sorted erasures.
+    /// * The coefficients apply to the first `source_num()` surviving blocks in index order.
+    /// * The rows after the erasures are zero.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::TooManyErasure` - If the number of erasures is larger than the code number.
+    /// * `Error::InvalidArguments` - If the erasure indices are out of range.
+    /// * `Error::Singular` - If the sub matrix of the surviving blocks is not invertible for the erasures.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// let k = NonZeroUsize::new(2).unwrap();
+    /// let m = NonZeroUsize::new(1).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// // block 0 is recovered from block 1 and the xor parity block 2
+    /// let matrix = ec.decode_matrix(&[0]).unwrap();
+    /// assert_eq!(matrix, vec![1, 1, 0, 0, 0, 0]);
+    /// ```
+    pub fn decode_matrix(&self, erasures: &[usize]) -> Result<Vec<u8>, Error> {
+        let erasures = self.check_decode_erasure(erasures)?;
+        self.make_decode_matrix(&erasures)
+    }
+
+    /// Generates a `DecodeTable` for the given erasures.
+    ///
+    /// This allocates a new table and fills it by [`regenerate_decode_table`](Self::regenerate_decode_table).
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::TooManyErasure` - If the number of erasures is larger than the code number.
+    /// * `Error::InvalidArguments` - If the erasure indices are out of range.
+    /// * `Error::Singular` - If the sub matrix of the surviving blocks is not invertible for the erasures.
+    ///
+    /// # Note
+    /// The order of the indices in `erasures` does not matter, and the duplicated indices are treated
+    /// as one erasure of the same block. Use [`make_decode_table_strict`](Self::make_decode_table_strict)
+    /// to reject the duplicated indices instead.
+    pub fn make_decode_table(&self, erasures: impl AsRef<[usize]>) -> Result<DecodeTable, Error> {
+        let erasures = self.check_decode_erasure(erasures.as_ref())?;
+        self.make_decode_table_impl(erasures.as_slice())
+    }
+
+    /// Generates a `DecodeTable` for the given erasures like [`make_decode_table`](Self::make_decode_table),
+    /// but rejects the duplicated indices.
+    ///
+    /// A duplicated index usually tells a bug of the caller, such as a block reported lost twice,
+    /// which is silently accepted by the lenient methods.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::InvalidArguments` - If any erasure index is duplicated.
+    /// * `Error::TooManyErasure` - If the number of erasures is larger than the code number.
+    /// * `Error::InvalidArguments` - If the erasure indices are out of range.
+    /// * `Error::Singular` - If the sub matrix of the surviving blocks is not invertible for the erasures.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// assert!(ec.make_decode_table_strict([4, 1]).is_ok());
+    /// assert!(ec.make_decode_table_strict([1, 4, 1]).is_err());
+    /// ```
+    pub fn make_decode_table_strict(
+        &self,
+        erasures: impl AsRef<[usize]>,
+    ) -> Result<DecodeTable, Error> {
+        let erasures = self.check_strict_erasure(erasures.as_ref())?;
+        self.make_decode_table_impl(erasures.as_slice())
+    }
+
+    /// Rewrites `table` in place with the decode table of the given erasures.
+    ///
+    /// The buffers of `table` are reused, and they are only grown if they are too small,
+    /// so a single table, e.g. created by [`DecodeTable::with_capacity`], can be kept
+    /// across the erasure patterns of a repair worker without allocating a table for each.
+    ///
+    /// # Arguments
+    /// * `table` - The decode table to be rewritten, which must be of the same geometry as the code.
+    /// * `erasures` - The indices of the erased blocks.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::TooManyErasure` - If the number of erasures is larger than the code number.
+    /// * `Error::InvalidArguments` - If the erasure indices are out of range.
+    /// * `Error::InvalidArguments` - If the geometry of `table` does not match the code.
+    /// * `Error::Singular` - If the sub matrix of the surviving blocks is not invertible for the erasures.
+    ///
+    /// On error, `table` is left unchanged.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::{DecodeTable, ErasureCode};
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    /// let mut table = DecodeTable::with_capacity(k.get(), m.get());
+    /// for erasures in [[0, 1], [2, 4], [3, 5]] {
+    ///     ec.regenerate_decode_table(&mut table, erasures).unwrap();
+    ///     let mut erased_data = data.clone();
+    ///     let mut erased_parity = parity.clone();
+    ///     ec.decode_with_table(&mut erased_data, &mut erased_parity, &table, erasures)
+    ///         .expect("Decoding failed");
+    ///     assert_eq!(erased_data, data);
+    /// }
+    /// ```
+    pub fn regenerate_decode_table(
+        &self,
+        table: &mut DecodeTable,
+        erasures: impl AsRef<[usize]>,
+    ) -> Result<(), Error> {
+        let erasures = self.check_decode_erasure(erasures.as_ref())?;
+        if table.source_num != self.source_num() || table.code_num != self.code_num() {
+            return Err(Error::invalid_arguments(format!(
+                "decode table of ({}, {}) code does not match the ({}, {}) code",
+                table.source_num,
+                table.code_num,
+                self.source_num(),
+                self.code_num()
+            )));
+        }
+        self.regenerate_decode_table_impl(table, &erasures)
+    }
+
+    /// Plans a decode that contacts as few zones as possible.
+    ///
+    /// In a geo-distributed deployment, each block is placed in a zone, and reading blocks across
+    /// zones is expensive. This method selects `source_num()` surviving blocks which can recover the
+    /// erased blocks, while minimizing the number of distinct zones the selected blocks reside in.
+    ///
+    /// The selected survivors can be passed to the survivor-based decode methods,
+    /// such as [`decode_one_into`](Self::decode_one_into), to recover the erased blocks.
+    ///
+    /// # Arguments
+    /// * `erasures` - The indices of the erased blocks.
+    /// * `zones` - The zone of each block, indexed by the block index in range `0..block_num()`.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::TooManyErasure` - If the number of erasures is larger than the code number.
+    /// * `Error::InvalidArguments` - If the erasure indices are out of range.
+    /// * `Error::InvalidArguments` - If the length of `zones` is not equal to the block number.
+    /// * `Error::Singular` - If no invertible selection of the surviving blocks exists.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_cauchy(k, m).unwrap();
+    /// let zones = [0, 1, 1, 2, 2, 2];
+    /// let plan = ec.plan_decode_zoned(vec![0], &zones).unwrap();
+    /// // all the survivors are read from zone 1 and 2
+    /// assert!(plan.survivors().iter().all(|&i| zones[i] != 0));
+    /// ```
+    pub fn plan_decode_zoned(
+        &self,
+        erasures: impl AsRef<[usize]>,
+        zones: &[u32],
+    ) -> Result<DecodePlan, Error> {
+        let erasures = self.check_decode_erasure(erasures.as_ref())?;
+        if zones.len() != self.block_num() {
+            return Err(Error::invalid_arguments(format!(
+                "zones length {} is not equal to block number {}",
+                zones.len(),
+                self.block_num()
+            )));
+        }
+        // group the surviving blocks by zone, the largest zone first
+        let mut groups = alloc::collections::BTreeMap::<u32, Vec<usize>>::new();
+        (0..self.block_num())
+            .filter(|i| !erasures.contains(i))
+            .for_each(|i| groups.entry(zones[i]).or_default().push(i));
+        let mut groups = groups.into_values().collect::<Vec<_>>();
+        groups.sort_by_key(|g| core::cmp::Reverse(g.len()));
+
+        let k = self.source_num();
+        for zone_num in 1..=groups.len() {
+            if groups[..zone_num].iter().map(Vec::len).sum::<usize>() < k {
+                // even the largest zones cannot provide enough survivors
+                continue;
+            }
+            let mut comb = (0..zone_num).collect::<Vec<_>>();
+            loop {
+                if comb.iter().map(|&g| groups[g].len()).sum::<usize>() >= k {
+                    let mut candidates = comb
+                        .iter()
+                        .flat_map(|&g| groups[g].iter().copied())
+                        .collect::<Vec<_>>();
+                    candidates.sort_unstable();
+                    if let Some(mut survivors) = self.select_independent(candidates) {
+                        survivors.sort_unstable();
+                        return Ok(DecodePlan {
+                            erasures,
+                            survivors,
+                        });
+                    }
+                }
+                if !next_combination(&mut comb, groups.len()) {
+                    break;
+                }
+            }
+        }
+        // no selection of the surviving blocks is invertible
+        Err(Error::singular(&erasures))
+    }
+
+    /// Recovers a single block from the surviving blocks and xors it into `accumulator`.
+    ///
+    /// Instead of writing the recovered block into a separate buffer, the recovered content is
+    /// xor-folded into `accumulator`, that is, `accumulator[i] ^= recovered[i]` for each `i`.
+    /// This fuses the recovery with an aggregation, which is handy for scan workloads that
+    /// reconstruct-and-sum several blocks.
+    ///
+    /// # Arguments
+    /// * `survivors` - The surviving blocks as pairs of `(index, block)`, the index is in range `0..block_num()`.
+    /// * `erasure` - The index of the block to be recovered.
+    /// * `accumulator` - The buffer the recovered block is xor-folded into.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::TooManyErasures` - If there are less than `source_num()` surviving blocks.
+    /// * `Error::InvalidArguments` - If the survivor or erasure indices are out of range,
+    ///   or the survivor indices are duplicated.
+    /// * `Error::InvalidArguments` - If the surviving blocks and the accumulator do not have the same length.
+    /// * `Error::Singular` - If the sub matrix of the surviving blocks is not invertible for the erasures.
+    ///
+    /// # Note
+    /// Only the first `source_num()` surviving blocks (ordered by index) are read.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    /// // Block 1 is lost, fold it into an accumulator holding block 0
+    /// let survivors = [(0, data[0].as_slice()), (2, &data[2]), (3, &data[3]), (4, &parity[0])];
+    /// let mut accumulator = data[0].clone();
+    /// ec.decode_one_into(&survivors, 1, &mut accumulator).expect("Decoding failed");
+    /// let expected: Vec<u8> = data[0].iter().zip(&data[1]).map(|(a, b)| a ^ b).collect();
+    /// assert_eq!(accumulator, expected);
+    /// ```
+    pub fn decode_one_into(
+        &self,
+        survivors: &[(usize, &[u8])],
+        erasure: usize,
+        accumulator: &mut [u8],
+    ) -> Result<(), Error> {
+        let len = accumulator.len();
+        let survivors = self.select_survivors(survivors, len)?;
+        let table = self.make_recover_table(&survivors, &[erasure])?;
+        for (i, (_, block)) in survivors.iter().enumerate() {
+            // accumulate the product of each survivor into the accumulator
+            ec::encode_data_update(
+                to_i32(len, "block length")?,
+                self.k_i32(),
+                1,
+                to_i32(i, "source index")?,
+                &table,
+                block,
+                &mut [accumulator.as_mut_ptr()],
+            );
+        }
+        Ok(())
+    }
+
+    /// Recovers a single block from the surviving blocks and writes it to `out`.
+    ///
+    /// The recovered block is computed chunk by chunk into an internal buffer, and each chunk is
+    /// written to `out` as soon as it is produced, so the peak buffer size is bounded regardless of
+    /// `block_len`. This makes it easy to stream the recovered content into a downstream consumer,
+    /// such as a hashing writer for verify-on-recover pipelines.
+    ///
+    /// # Arguments
+    /// * `survivors` - The surviving blocks as pairs of `(index, block)`, the index is in range `0..block_num()`.
+    /// * `erasure` - The index of the block to be recovered.
+    /// * `block_len` - The length of each block.
+    /// * `out` - The writer the recovered block is written to.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::TooManyErasures` - If there are less than `source_num()` surviving blocks.
+    /// * `Error::InvalidArguments` - If the survivor or erasure indices are out of range,
+    ///   or the survivor indices are duplicated.
+    /// * `Error::InvalidArguments` - If the length of any surviving block is not equal to `block_len`.
+    /// * `Error::Singular` - If the sub matrix of the surviving blocks is not invertible for the erasures.
+    /// * `Error::Io` - If writing to `out` fails.
+    ///
+    /// # Note
+    /// Only the first `source_num()` surviving blocks (ordered by index) are read.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    /// // Block 1 is lost, stream it into a writer
+    /// let survivors = [(0, data[0].as_slice()), (2, &data[2]), (3, &data[3]), (4, &parity[0])];
+    /// let mut out = Vec::new();
+    /// ec.decode_one_to_writer(&survivors, 1, BLOCK_LEN, &mut out).expect("Decoding failed");
+    /// assert_eq!(out, data[1]);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn decode_one_to_writer(
+        &self,
+        survivors: &[(usize, &[u8])],
+        erasure: usize,
+        block_len: usize,
+        mut out: impl io::Write,
+    ) -> Result<(), Error> {
+        let survivors = self.select_survivors(survivors, block_len)?;
+        let table = self.make_recover_table(&survivors, &[erasure])?;
+        let mut buf = vec![0_u8; block_len.min(DECODE_CHUNK_LEN)];
+        for offset in (0..block_len).step_by(DECODE_CHUNK_LEN) {
+            let len = (block_len - offset).min(DECODE_CHUNK_LEN);
+            let src_ptrs = survivors
+                .iter()
+                .map(|(_, block)| block[offset..].as_ptr())
+                .collect::<Vec<_>>();
+            ec::encode_data(
+                to_i32(len, "block length")?,
+                self.k_i32(),
+                1,
+                &table,
+                &src_ptrs,
+                &mut [buf.as_mut_ptr()],
+            );
+            out.write_all(&buf[..len])?;
+        }
+        Ok(())
+    }
+
+    /// Computes the `produce` blocks from the surviving blocks.
+    ///
+    /// This generalizes decode to "compute these specific blocks from those survivors",
+    /// where the produced blocks may be lost data blocks as well as parity blocks that are to be
+    /// regenerated, which supports repair topologies beyond strict erasure recovery.
+    ///
+    /// # Arguments
+    /// * `survivors` - The surviving blocks as pairs of `(index, block)`, the index is in range `0..block_num()`.
+    /// * `produce` - The indices of the blocks to be computed.
+    ///
+    /// # Returns
+    /// The produced blocks as pairs of `(index, block)`, in the order of `produce`.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::TooManyErasures` - If there are less than `source_num()` surviving blocks.
+    /// * `Error::InvalidArguments` - If the survivor or produce indices are out of range,
+    ///   or the survivor or produce indices are duplicated.
+    /// * `Error::InvalidArguments` - If the surviving blocks do not have the same length.
+    /// * `Error::InvalidArguments` - If the surviving blocks do not form an invertible set,
+    ///   that is, no `source_num()` of them can recover the other blocks.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_cauchy(k, m).unwrap();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    /// // Block 1 is lost, regenerate parity block 5 instead of the lost block
+    /// let survivors = [(0, data[0].as_slice()), (2, &data[2]), (3, &data[3]), (4, &parity[0])];
+    /// let produced = ec.decode_target(&survivors, &[5]).expect("Decoding failed");
+    /// assert_eq!(produced, vec![(5, parity[1].clone())]);
+    /// ```
+    pub fn decode_target(
+        &self,
+        survivors: &[(usize, &[u8])],
+        produce: &[usize],
+    ) -> Result<Vec<(usize, Vec<u8>)>, Error> {
+        let len = survivors.first().map_or(0, |(_, block)| block.len());
+        let mut produced = produce
+            .iter()
+            .map(|&i| (i, vec![0_u8; len]))
+            .collect::<Vec<_>>();
+        let mut outputs = produced
+            .iter_mut()
+            .map(|(i, block)| (*i, block.as_mut_slice()))
+            .collect::<Vec<_>>();
+        self.produce_impl(survivors, &mut outputs)?;
+        Ok(produced)
+    }
+
+    /// Decode the erased blocks from read-only surviving blocks into the caller's output buffers.
     ///
-    /// The content of the erased blocks will be recovered and written back to the `data` and `code` buffers.
-    /// And the content of the non-erased blocks will not be changed.
+    /// This works like [`decode_target`](Self::decode_target), except that the blocks are written into
+    /// the `outputs` instead of newly allocated buffers. Only the outputs are borrowed mutably,
+    /// so the surviving blocks may be read-only memory, such as memory-mapped files, without being copied.
     ///
     /// # Arguments
-    /// * `data` - The data blocks.
-    /// * `code` - The code blocks.
-    /// * `erasures` - The indices of the erased blocks.
+    /// * `survivors` - The surviving blocks as pairs of `(index, block)`, the index is in range `0..block_num()`.
+    /// * `outputs` - The blocks to be recovered as pairs of `(index, buffer)`,
+    ///   each buffer must have the same length as the surviving blocks.
     ///
     /// # Errors
     /// The following errors can occur:
-    /// * `Error::TooManyErasure` - If the number of erasures is larger than the code number.
-    /// * `Error::InvalidArguments` - If the erasure indices are out of range.
-    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number,
-    ///   or the code blocks number is not equal to the code number.
-    /// * `Error::InvalidArguments` - If the input data or code blocks do not have the same length
-    /// * `Error::InternalError` - If the internal error occurs while decoding, typically due to a failure when
-    ///   inverting the matrix.
-    ///   
-    /// # Note
-    /// The order of the indices in `erasures` does not matter, and the duplicated indices will
-    /// be treated as one erasure of the same block. So it is possible to pass a vector with
-    /// more than the code number of erasures.
+    /// * `Error::TooManyErasures` - If there are less than `source_num()` surviving blocks.
+    /// * `Error::InvalidArguments` - If the survivor or output indices are out of range,
+    ///   or the survivor or output indices are duplicated.
+    /// * `Error::InvalidArguments` - If the surviving blocks and the output buffers do not have the same length.
+    /// * `Error::InvalidArguments` - If the surviving blocks do not form an invertible set,
+    ///   that is, no `source_num()` of them can recover the other blocks.
     ///
-    /// A `DecodeTable` will be generated internally to perform the decoding, which is time consuming.
-    /// If you need to decode multiple times with the same erasures, you can use [`make_decode_table`](Self::make_decode_table) to generate a
-    /// `DecodeTable` and pass it to [`decode_with_table`](Self::decode_with_table) to avoid the overhead.
+    /// On error, the output buffers are left unchanged.
     ///
     /// # Examples
     /// ```rust
@@ -281,157 +4073,319 @@ impl ErasureCode {
     /// const BLOCK_LEN: usize = 1024;
     /// let k = NonZeroUsize::new(4).unwrap();
     /// let m = NonZeroUsize::new(2).unwrap();
-    /// let code = ErasureCode::with_reed_solomon(k, m).unwrap();
     /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
     /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
-    /// let mut parity: Vec<Vec<u8>> = vec![vec![0u8; BLOCK_LEN]; m.get()];
-    /// ec.encode(&data, &mut parity).expect("Encoding failed");
-    /// // Simulate erasures
-    /// let mut erasures = vec![2, 5]; // Assume blocks 2 and 5 are erased
-    /// let mut erased_data: Vec<Vec<u8>> = data.clone();
-    /// erased_data[2] = vec![0; BLOCK_LEN];
-    /// let mut erased_parity: Vec<Vec<u8>> = parity.clone();
-    /// erased_parity[1] = vec![0; BLOCK_LEN];
-    /// // Decode the erased blocks
-    /// ec.decode(&mut erased_data, &mut erased_parity, erasures).expect("Decoding failed");
-    /// // Verify that the erased blocks are recovered
-    /// assert_eq!(&data, &erased_data);
-    /// assert_eq!(&parity, &erased_parity);
+    /// let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    /// // Blocks 1 and 4 are lost, the survivors are only borrowed immutably
+    /// let survivors = [(0, data[0].as_slice()), (2, &data[2]), (3, &data[3]), (5, &parity[1])];
+    /// let (mut block1, mut block4) = (vec![0_u8; BLOCK_LEN], vec![0_u8; BLOCK_LEN]);
+    /// ec.decode_readonly(&survivors, &mut [(1, &mut block1), (4, &mut block4)])
+    ///     .expect("Decoding failed");
+    /// assert_eq!(block1, data[1]);
+    /// assert_eq!(block4, parity[0]);
     /// ```
-    pub fn decode<U: AsMut<[u8]>>(
+    pub fn decode_readonly(
         &self,
-        mut data: impl AsMut<[U]>,
-        mut code: impl AsMut<[U]>,
-        mut erasures: Vec<usize>,
+        survivors: &[(usize, &[u8])],
+        outputs: &mut [(usize, &mut [u8])],
     ) -> Result<(), Error> {
-        self.check_decode_erasure(&mut erasures)?;
-        self.check_decode_buffer(&mut data, &mut code)?;
-        let decode_gf_table = self.make_decode_table_impl(erasures.as_slice())?;
-        self.decode_impl(data, code, &decode_gf_table.0, erasures.as_slice())
+        self.produce_impl(survivors, outputs)
     }
 
-    /// Decode the erased blocks from the surviving data and code blocks using a pre-generated `DecodeTable`.
-    ///
-    /// The range of the blocks is `0..block_num()`.
-    /// The first `source_num()` blocks are the source data blocks, the next `code_num()` blocks are the code blocks.
+    /// Returns how many blocks must be read to get the block `wanted`, given the `available` blocks.
     ///
-    /// The content of the erased blocks will be recovered and written back to the `data` and `code` buffers.
-    /// And the content of the non-erased blocks will not be changed.
-    ///
-    /// A `DecodeTable` can be generated by [`make_decode_table`](Self::make_decode_table) method,
-    /// and it can be reused for multiple decode operations with the same erasures.
-    /// If you only need to decode once, you can use [`decode`](Self::decode) method instead,
-    /// which will generate the `DecodeTable` internally.
+    /// A block that is available costs a single read, while a missing block has to be reconstructed
+    /// from `source_num()` surviving blocks. This guides the routing of degraded reads.
     ///
     /// # Arguments
-    /// * `data` - The data blocks.
-    /// * `code` - The code blocks.
-    /// * `decode_table` - The pre-generated `DecodeTable` for decoding.
-    /// * `erasures` - The indices of the erased blocks.
-    ///   
-    /// # Errors
-    /// The following errors can occur:
-    /// * `Error::TooManyErasure` - If the number of erasures is larger than the code number.
-    /// * `Error::InvalidArguments` - If the erasure indices are out of range.
-    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number,
-    ///   or the code blocks number is not equal to the code number.
-    /// * `Error::InvalidArguments` - If the input data or code blocks do not have the same length
-    /// * `Error::InternalError` - If the internal error occurs while decoding, typically due to a failure when
-    ///   inverting the matrix.
-    ///   
-    /// # Note
-    /// The `decode_table` must be generated with the same erasures as the `erasures` argument.
-    /// Otherwise, the decoding result is undefined.
+    /// * `wanted` - The index of the wanted block, in range `0..block_num()`.
+    /// * `available` - The indices of the available blocks, in range `0..block_num()`.
     ///
-    /// The order of the indices in `erasures` does not matter, and the duplicated indices will
-    /// be treated as one erasure of the same block. So it is possible to pass a vector with
-    /// more than the code number of erasures.
+    /// # Errors
+    /// * `Error::InvalidArguments` - If `wanted` or any of the `available` indices are out of range.
+    /// * `Error::TooManyErasures` - If `wanted` is missing and there are less than `source_num()` available blocks.
     ///
     /// # Examples
     /// ```rust
-    /// # use erasure_isa_l::erasure::{ErasureCode, DecodeTable};
+    /// # use erasure_isa_l::erasure::ErasureCode;
     /// # use std::num::NonZeroUsize;
-    /// const BLOCK_LEN: usize = 1024;
     /// let k = NonZeroUsize::new(4).unwrap();
     /// let m = NonZeroUsize::new(2).unwrap();
     /// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
-    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
-    /// let mut parity: Vec<Vec<u8>> = vec![vec![0u8; BLOCK_LEN]; m.get()];
-    /// ec.encode(&data, &mut parity).expect("Encoding failed");
-    /// // Simulate erasures
-    /// let mut erasures = vec![2, 5]; // Assume blocks 2 and 5 are erased
-    /// let mut erased_data: Vec<Vec<u8>> = data.clone();
-    /// erased_data[2] = vec![0; BLOCK_LEN];
-    /// let mut erased_parity: Vec<Vec<u8>> = parity.clone();
-    /// erased_parity[1] = vec![0; BLOCK_LEN];
-    /// // Generate a decode table for the erasures
-    /// let decode_table = ec.make_decode_table(erasures.clone()).expect("Failed to make decode table");
-    /// // Decode the erased blocks using the decode table
-    /// ec.decode_with_table(&mut erased_data, &mut erased_parity, &decode_table, erasures).expect("Decoding failed");
-    /// // Verify that the erased blocks are recovered
-    /// assert_eq!(&data, &erased_data);
-    /// assert_eq!(&parity, &erased_parity);
+    /// assert_eq!(ec.degraded_read_cost(1, &[0, 1, 2, 3]).unwrap(), 1);
+    /// assert_eq!(ec.degraded_read_cost(1, &[0, 2, 3, 4, 5]).unwrap(), 4);
     /// ```
-    pub fn decode_with_table<U>(
-        &self,
-        mut data: impl AsMut<[U]>,
-        mut code: impl AsMut<[U]>,
-        decode_table: &DecodeTable,
-        mut erasures: Vec<usize>,
-    ) -> Result<(), Error>
-    where
-        U: AsMut<[u8]>,
-    {
-        self.check_decode_erasure(&mut erasures)?;
-        self.check_decode_buffer(&mut data, &mut code)?;
-        self.decode_impl(data, code, &decode_table.0, erasures.as_mut_slice())
+    pub fn degraded_read_cost(&self, wanted: usize, available: &[usize]) -> Result<usize, Error> {
+        if let Some(i) = core::iter::once(&wanted)
+            .chain(available)
+            .find(|i| **i >= self.block_num())
+        {
+            return Err(Error::invalid_arguments(format!(
+                "index {} is out of range, max index is block number {}",
+                i,
+                self.block_num() - 1
+            )));
+        }
+        if available.contains(&wanted) {
+            return Ok(1);
+        }
+        let mut available = available.to_vec();
+        available.sort_unstable();
+        available.dedup();
+        if available.len() < self.source_num() {
+            return Err(Error::too_many_erasures(
+                self.block_num() - available.len(),
+                self.code_num(),
+            ));
+        }
+        Ok(self.source_num())
     }
 
-    /// Generates a `DecodeTable` for the given erasures.
+    /// Tries to recover the single block `target` from the surviving blocks.
+    ///
+    /// Unlike [`decode`](Self::decode), which needs at least `source_num()` surviving blocks,
+    /// a specific block may still be recoverable from fewer survivors, if its encode matrix row
+    /// is spanned by the rows of the survivors.
+    ///
+    /// # Arguments
+    /// * `survivors` - The surviving blocks as pairs of `(index, block)`, the index is in range `0..block_num()`.
+    /// * `target` - The index of the block to be recovered.
+    ///
+    /// # Returns
+    /// `Ok(Some(block))` with the recovered block, or `Ok(None)` if the `target` is unrecoverable from the survivors.
     ///
     /// # Errors
-    /// The following errors can occur:
-    /// * `Error::TooManyErasure` - If the number of erasures is larger than the code number.
-    /// * `Error::InvalidArguments` - If the erasure indices are out of range.
+    /// * `Error::InvalidArguments` - If the survivor or target indices are out of range,
+    ///   or the survivor indices are duplicated.
+    /// * `Error::InvalidArguments` - If the surviving blocks do not have the same length.
     ///
-    /// # Note
-    /// The order of the indices in `erasures` does not matter, but they must be unique.
-    /// The duplicated indices will be treated as multiple erasures of the same block,
-    /// and the result is undefined.
-    pub fn make_decode_table(&self, mut erasures: Vec<usize>) -> Result<DecodeTable, Error> {
-        self.check_decode_erasure(&mut erasures)?;
-        self.make_decode_table_impl(erasures.as_mut_slice())
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(4).unwrap();
+    /// let m = NonZeroUsize::new(2).unwrap();
+    /// let ec = ErasureCode::with_cauchy(k, m).unwrap();
+    /// let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    /// // Only 2 blocks survive, the surviving data block is trivially recoverable
+    /// let survivors = [(1, data[1].as_slice()), (4, &parity[0])];
+    /// assert_eq!(ec.try_recover_block(&survivors, 1).unwrap(), Some(data[1].clone()));
+    /// assert_eq!(ec.try_recover_block(&survivors, 0).unwrap(), None);
+    /// ```
+    pub fn try_recover_block(
+        &self,
+        survivors: &[(usize, &[u8])],
+        target: usize,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        let len = survivors.first().map_or(0, |(_, block)| block.len());
+        let survivors = self.check_survivors(survivors, len, 0)?;
+        if target >= self.block_num() {
+            return Err(Error::invalid_arguments(format!(
+                "target index {} is out of range, max index is block number {}",
+                target,
+                self.block_num() - 1
+            )));
+        }
+        let survivor_index = survivors.iter().map(|(i, _)| *i).collect::<Vec<_>>();
+        let Some(coefficients) = self.span_coefficients(&survivor_index, target) else {
+            return Ok(None);
+        };
+        let table = galois::GaloisFiledTable::try_from_matrix(&coefficients, 1, survivors.len())?;
+        let src_ptrs = survivors
+            .iter()
+            .map(|(_, block)| block.as_ptr())
+            .collect::<Vec<_>>();
+        let mut block = vec![0_u8; len];
+        ec::encode_data(
+            to_i32(len, "block length")?,
+            to_i32(survivors.len(), "survivors number")?,
+            1,
+            &table,
+            &src_ptrs,
+            &mut [block.as_mut_ptr()],
+        );
+        Ok(Some(block))
     }
 }
 
 /// private implementation of ErasureCode
 impl ErasureCode {
-    fn new(source_num: i32, code_num: i32, code_type: CodeType) -> Result<Self, Error> {
-        let k: i32 = source_num;
-        let m: i32 = code_num;
-        let n = k + m;
-
-        let mat_gen_fn = match code_type {
-            CodeType::ReedSolomon => crate::gf::gen_rs_matrix,
-            CodeType::Cauchy => crate::gf::gen_cauchy1_matrix,
+    fn new(source_num: usize, code_num: usize, matrix_kind: MatrixKind) -> Result<Self, Error> {
+        Self::check_geometry(source_num, code_num)?;
+        let encode_matrix = Self::generate_matrix(source_num, code_num, matrix_kind)?;
+        Self::from_encode_matrix(source_num, code_num, encode_matrix, Some(matrix_kind))
+    }
+
+    /// Generates the `(k + m) x k` encode matrix of `matrix_kind`, the geometry must be valid.
+    fn generate_matrix(
+        source_num: usize,
+        code_num: usize,
+        matrix_kind: MatrixKind,
+    ) -> Result<Vec<u8>, Error> {
+        let k = to_i32(source_num, "source number")?;
+        let n = to_i32(source_num + code_num, "block number")?;
+        let mut encode_matrix = match matrix_kind {
+            MatrixKind::ReedSolomon | MatrixKind::AdjustedReedSolomon | MatrixKind::Vandermonde => {
+                crate::gf::rs_matrix(n, k)
+            }
+            MatrixKind::Cauchy => crate::gf::cauchy1_matrix(n, k),
         };
-        let mut encode_matrix = vec![0; (k * n).try_into().unwrap()];
-        mat_gen_fn(&mut encode_matrix, n, k);
+        if let MatrixKind::AdjustedReedSolomon = matrix_kind {
+            Self::adjust_rs_matrix(source_num, code_num, &mut encode_matrix)?;
+        }
+        Ok(encode_matrix)
+    }
 
-        let gf_table = galois::GaloisFiledTable::try_from_matrix(
-            &encode_matrix[usize::try_from(k * k).unwrap()..],
-            m.try_into().unwrap(),
-            k.try_into().unwrap(),
-        )?;
+    /// Regenerates the parity rows of a `gf_gen_rs_matrix` matrix which make any decode matrix singular.
+    ///
+    /// The parity row `r` of `gf_gen_rs_matrix` is `[1, b, b^2, ...]` with the base `b = 2^r`,
+    /// a row breaking invertibility is replaced by the row whose base is the next unused power of 2.
+    /// The matrix is left as is for the pairs known to be invertible, and the adjustment fails
+    /// once more than [`RS_CHECK_LIMIT`] sub matrices are inverted, which is too expensive.
+    fn adjust_rs_matrix(
+        source_num: usize,
+        code_num: usize,
+        matrix: &mut [u8],
+    ) -> Result<(), Error> {
+        let (k, m) = (source_num, code_num);
+        let known_safe =
+            k <= 3 || (k == 4 && m <= 21) || (k == 5 && m <= 10) || (k == 21 && m == 4) || m <= 3;
+        if known_safe {
+            return Ok(());
+        }
+        // the powers `0..m` are used by the generated rows, and 2 is a generator of order 255
+        let mut next_exp = m;
+        let parity = &mut matrix[k * k..];
+        let mut inverted = 0;
+        for row in 0..m {
+            while !Self::is_row_independent(parity, k, row, &mut inverted)? {
+                if next_exp >= 255 {
+                    return Err(Error::internal_error(format!(
+                        "fail to find an invertible reed solomon matrix for ({}, {}) code",
+                        k, m
+                    )));
+                }
+                let base = (0..next_exp).fold(1, |acc, _| gf::mul(acc, 2));
+                next_exp += 1;
+                let mut coef = 1;
+                for c in &mut parity[row * k..(row + 1) * k] {
+                    *c = coef;
+                    coef = gf::mul(coef, base);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that every square sub matrix of the first `row + 1` rows of the `parity` matrix
+    /// containing the row `row` is invertible, assuming the rows before it are already checked.
+    ///
+    /// A systematic encode matrix makes every decode matrix invertible if and only if
+    /// every square sub matrix of its parity part is invertible.
+    /// `inverted` counts the sub matrices inverted so far, bounded by [`RS_CHECK_LIMIT`].
+    fn is_row_independent(
+        parity: &[u8],
+        k: usize,
+        row: usize,
+        inverted: &mut usize,
+    ) -> Result<bool, Error> {
+        for size in 1..=(row + 1).min(k) {
+            // the other rows are chosen from the previous rows
+            let mut rows = (0..size - 1).collect::<Vec<_>>();
+            loop {
+                let mut cols = (0..size).collect::<Vec<_>>();
+                loop {
+                    *inverted += 1;
+                    if *inverted > RS_CHECK_LIMIT {
+                        return Err(Error::internal_error(format!(
+                            "too many sub matrices to check the reed solomon matrix for ({}, {}) code",
+                            k,
+                            parity.len() / k
+                        )));
+                    }
+                    let mut sub_matrix = rows
+                        .iter()
+                        .chain(core::iter::once(&row))
+                        .flat_map(|r| cols.iter().map(move |c| parity[r * k + c]))
+                        .collect::<Vec<_>>();
+                    let mut invert_matrix = vec![0; size * size];
+                    if !gf::invert_matrix(
+                        &mut sub_matrix,
+                        &mut invert_matrix,
+                        to_i32(size, "sub matrix size")?,
+                    ) {
+                        return Ok(false);
+                    }
+                    if !next_combination(&mut cols, k) {
+                        break;
+                    }
+                }
+                if !next_combination(&mut rows, row) {
+                    break;
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    /// Creates a new `ErasureCode` from a `(k + m) x k` encode matrix, whose top is the identity matrix.
+    fn from_encode_matrix(
+        k: usize,
+        m: usize,
+        encode_matrix: Vec<u8>,
+        matrix_kind: Option<MatrixKind>,
+    ) -> Result<Self, Error> {
+        let gf_table = galois::GaloisFiledTable::try_from_matrix(&encode_matrix[k * k..], m, k)?;
 
         Ok(Self {
-            k,
-            m,
+            k: to_i32(k, "source number")?,
+            m: to_i32(m, "code number")?,
             encode_matrix,
             encode_gf_table: gf_table,
+            matrix_kind,
+            #[cfg(feature = "std")]
+            decode_cache: None,
         })
     }
 
+    /// Checks the geometry and an encode matrix supplied by the user.
+    fn check_encode_matrix(k: usize, m: usize, matrix: &[u8]) -> Result<(), Error> {
+        Self::check_geometry(k, m)?;
+        if matrix.len() != (k + m) * k {
+            return Err(Error::invalid_arguments(format!(
+                "matrix length {} is not equal to ({} + {}) x {}",
+                matrix.len(),
+                k,
+                m,
+                k
+            )));
+        }
+        if let Some((i, _)) = matrix[..k * k]
+            .iter()
+            .enumerate()
+            .find(|(i, coef)| **coef != u8::from(i / k == i % k))
+        {
+            return Err(Error::invalid_arguments(format!(
+                "the top of the matrix is not the identity matrix at row {}, column {}",
+                i / k,
+                i % k
+            )));
+        }
+        Ok(())
+    }
+
+    fn check_geometry(source_num: usize, code_num: usize) -> Result<(), Error> {
+        if !validate_geometry(source_num, code_num) {
+            return Err(Error::invalid_arguments(format!(
+                "invalid geometry: source number {} + code number {} must not exceed {}",
+                source_num, code_num, MAX_BLOCK_NUM
+            )));
+        }
+        Ok(())
+    }
+
     fn k_i32(&self) -> i32 {
         self.k
     }
@@ -445,83 +4399,286 @@ impl ErasureCode {
         self.k + self.m
     }
 
+    /// Encodes the first `len` bytes of the code blocks of the `rows` range of the parity rows.
     fn encode_impl<T: AsRef<[u8]>, U: AsMut<[u8]>>(
         &self,
         data: impl AsRef<[T]>,
+        rows: Range<usize>,
+        code: impl AsMut<[U]>,
+        len: usize,
+    ) -> Result<(), Error> {
+        self.encode_with_scratch_impl(data, rows, code, len, &mut EncodeScratch::default())
+    }
+
+    /// Encodes like `encode_impl`, gathering the block pointers into the buffers of `scratch`.
+    fn encode_with_scratch_impl<T: AsRef<[u8]>, U: AsMut<[u8]>>(
+        &self,
+        data: impl AsRef<[T]>,
+        rows: Range<usize>,
         mut code: impl AsMut<[U]>,
+        len: usize,
+        scratch: &mut EncodeScratch,
     ) -> Result<(), Error> {
-        let data_ptrs = data
-            .as_ref()
-            .iter()
-            .map(AsRef::as_ref)
-            .map(<[u8]>::as_ptr)
-            .collect::<Vec<_>>();
-        let mut code_ptrs = code
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let len = to_i32(len, "block length")?;
+        let rows_num = to_i32(rows.len(), "rows number")?;
+        let EncodeScratch {
+            data_ptrs,
+            code_ptrs,
+        } = scratch;
+        // drop the pointers left over by a previous encoding which panicked during the gathering
+        data_ptrs.clear();
+        code_ptrs.clear();
+        data_ptrs.extend(data.as_ref().iter().map(|d| d.as_ref().as_ptr()));
+        code_ptrs.extend(code.as_mut().iter_mut().map(|c| c.as_mut().as_mut_ptr()));
+        // the gf table of each parity row takes 32 * k bytes
+        let row_table_len = 32 * self.source_num();
+        ec::encode_data(
+            len,
+            self.k_i32(),
+            rows_num,
+            &self.encode_gf_table[(rows.start * row_table_len)..(rows.end * row_table_len)],
+            data_ptrs,
+            code_ptrs,
+        );
+        // no pointer is kept beyond the call
+        data_ptrs.clear();
+        code_ptrs.clear();
+        Ok(())
+    }
+
+    /// Decodes the first `len` bytes of the erased blocks.
+    ///
+    /// When both data and code blocks are erased, only the erased data blocks are recovered with
+    /// the decode table, and the erased code blocks are then re-encoded from the recovered data,
+    /// applying the rows of the encode table instead of the rows derived from the inverted matrix.
+    fn decode_impl<U: AsMut<[u8]>>(
+        &self,
+        mut data: impl AsMut<[U]>,
+        mut code: impl AsMut<[U]>,
+        decode_table: &[u8],
+        erasures: &[usize],
+        len: usize,
+    ) -> Result<(), Error> {
+        let k = self.source_num();
+        let lost_data = erasures.partition_point(|e| *e < k);
+        if lost_data == 0 || lost_data == erasures.len() {
+            return self.decode_wanted_impl(data, code, decode_table, erasures, erasures, len);
+        }
+        // the rows of the table follow the sorted erasures, so the rows of the data blocks come first
+        let (data_erasures, code_erasures) = erasures.split_at(lost_data);
+        self.decode_wanted_impl(
+            &mut data,
+            &mut code,
+            &decode_table[..32 * k * lost_data],
+            erasures,
+            data_erasures,
+            len,
+        )?;
+        let data = data
             .as_mut()
             .iter_mut()
-            .map(AsMut::as_mut)
-            .map(<[u8]>::as_mut_ptr)
+            .map(|b| &*b.as_mut())
             .collect::<Vec<_>>();
-        let blk_len = data
-            .as_ref()
-            .first()
-            .unwrap()
-            .as_ref()
-            .len()
-            .try_into()
-            .unwrap();
+        let code = code.as_mut();
+        for row in code_erasures.iter().map(|e| e - k) {
+            self.encode_impl(&data, row..row + 1, [code[row].as_mut()], len)?;
+        }
+        Ok(())
+    }
+
+    /// Decodes the first `len` bytes of the `wanted` blocks among the erased blocks,
+    /// with a decode table holding the rows of the `wanted` blocks in order.
+    fn decode_wanted_impl<U: AsMut<[u8]>>(
+        &self,
+        mut data: impl AsMut<[U]>,
+        mut code: impl AsMut<[U]>,
+        decode_table: &[u8],
+        erasures: &[usize],
+        wanted: &[usize],
+        len: usize,
+    ) -> Result<(), Error> {
+        if wanted.is_empty() {
+            return Ok(());
+        }
+        let mut recover_src = Vec::with_capacity(self.block_num() - erasures.len());
+        let mut recover_output = Vec::with_capacity(wanted.len());
+        data.as_mut()
+            .iter_mut()
+            .chain(code.as_mut().iter_mut())
+            .enumerate()
+            .for_each(|(i, ptr)| {
+                if wanted.contains(&i) {
+                    // if the block is wanted, we will recover it
+                    recover_output.push(ptr.as_mut().as_mut_ptr());
+                } else if !erasures.contains(&i) {
+                    // if the block is not erased, we will use it to recover
+                    recover_src.push(ptr.as_mut().as_ptr());
+                }
+            });
         ec::encode_data(
-            blk_len,
-            self.k_i32(),
-            self.m_i32(),
-            &self.encode_gf_table,
-            &data_ptrs,
-            &mut code_ptrs,
+            to_i32(len, "block length")?,
+            self.k,
+            to_i32(wanted.len(), "erasures number")?,
+            decode_table,
+            &recover_src,
+            &mut recover_output,
         );
         Ok(())
     }
 
-    fn decode_impl<U: AsMut<[u8]>>(
+    fn make_decode_table_impl(&self, erasures: &[usize]) -> Result<DecodeTable, Error> {
+        let mut table = DecodeTable::with_capacity(self.source_num(), self.code_num());
+        self.regenerate_decode_table_impl(&mut table, erasures)?;
+        Ok(table)
+    }
+
+    /// Rewrites `table` of the same geometry with the decode table of the sorted and deduplicated `erasures`.
+    fn regenerate_decode_table_impl(
+        &self,
+        table: &mut DecodeTable,
+        erasures: &[usize],
+    ) -> Result<(), Error> {
+        let matrix = self.make_decode_matrix(erasures)?;
+        let cols = self.source_num();
+        let rows = erasures.len();
+        table
+            .table
+            .regenerate(&matrix[0..(cols * rows)], rows, cols);
+        table.erasures.clear();
+        table.erasures.extend_from_slice(erasures);
+        table.matrix = Some(self.matrix_stamp());
+        Ok(())
+    }
+
+    /// Returns the matrix kind, and the digest of the encode matrix if it is user supplied,
+    /// which identify the matrix of the code.
+    fn matrix_stamp(&self) -> (Option<MatrixKind>, Option<u64>) {
+        let digest = self
+            .matrix_kind
+            .is_none()
+            .then(|| matrix_digest(&self.encode_matrix));
+        (self.matrix_kind, digest)
+    }
+
+    /// Checks the decode table is generated by a code of the same geometry and matrix
+    /// for the sorted and deduplicated `erasures`.
+    fn check_decode_table(
+        &self,
+        decode_table: &DecodeTable,
+        erasures: &[usize],
+    ) -> Result<(), Error> {
+        if decode_table.source_num != self.source_num() || decode_table.code_num != self.code_num()
+        {
+            return Err(Error::invalid_arguments(format!(
+                "decode table of ({}, {}) code does not match the ({}, {}) code",
+                decode_table.source_num,
+                decode_table.code_num,
+                self.source_num(),
+                self.code_num()
+            )));
+        }
+        if let Some(matrix) = decode_table.matrix
+            && matrix != self.matrix_stamp()
+        {
+            return Err(Error::invalid_arguments(format!(
+                "decode table of {:?} matrix does not match the {:?} matrix",
+                matrix.0, self.matrix_kind
+            )));
+        }
+        if decode_table.erasures != erasures {
+            return Err(Error::invalid_arguments(format!(
+                "decode table of erasures {:?} does not match the erasures {:?}",
+                decode_table.erasures, erasures
+            )));
+        }
+        Ok(())
+    }
+
+    /// Applies the delta of the source block `index` to the parity blocks of `code`,
+    /// each of which must hold at least `delta.len()` bytes.
+    fn update_impl(&self, index: usize, delta: &[u8], code: &mut [&mut [u8]]) -> Result<(), Error> {
+        debug_assert!(code.iter().all(|c| c.len() >= delta.len()));
+        let affected = self.parities_affected_by(index)?;
+        if affected.len() == self.code_num() && delta.len() >= MIN_VECT_LEN {
+            let mut code_ptrs = code.iter_mut().map(|c| c.as_mut_ptr()).collect::<Vec<_>>();
+            // Update the data block at the given index
+            ec::encode_data_update(
+                to_i32(delta.len(), "block length")?,
+                self.k_i32(),
+                self.m_i32(),
+                to_i32(index, "source index")?,
+                &self.encode_gf_table,
+                delta,
+                &mut code_ptrs,
+            );
+        } else {
+            // skip the parity blocks which are not affected by the source block
+            for row in affected {
+                self.update_row_impl(row, index, delta, code[row])?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies the delta of the source block `index` to the parity block of `row`,
+    /// which must hold at least `delta.len()` bytes.
+    ///
+    /// The SIMD kernels of isa-l require at least [`MIN_VECT_LEN`] bytes,
+    /// so shorter deltas are accumulated byte by byte instead.
+    fn update_row_impl(
         &self,
-        mut data: impl AsMut<[U]>,
-        mut code: impl AsMut<[U]>,
-        decode_table: &[u8],
-        erasures: &[usize],
+        row: usize,
+        index: usize,
+        delta: &[u8],
+        block: &mut [u8],
     ) -> Result<(), Error> {
-        let mut recover_src = Vec::with_capacity(self.block_num() - erasures.len());
-        let mut recover_output = Vec::with_capacity(erasures.len());
-        data.as_mut()
-            .iter_mut()
-            .chain(code.as_mut().iter_mut())
-            .enumerate()
-            .for_each(|(i, ptr)| {
-                if erasures.contains(&i) {
-                    // if the block is erased, we will recover it
-                    recover_output.push(ptr.as_mut().as_mut_ptr());
-                } else {
-                    // if the block is not erased, we will use it to recover
-                    recover_src.push(ptr.as_mut().as_ptr());
-                }
-            });
-        let blk_len = data.as_mut().first_mut().unwrap().as_mut().len();
-        ec::encode_data(
-            blk_len.try_into().unwrap(),
-            self.k,
-            erasures.len().try_into().unwrap(),
-            decode_table,
-            &recover_src,
-            &mut recover_output,
+        debug_assert!(block.len() >= delta.len());
+        if delta.len() < MIN_VECT_LEN {
+            let coef = self.encode_matrix[self.source_num() * (self.source_num() + row) + index];
+            block
+                .iter_mut()
+                .zip(delta)
+                .for_each(|(p, d)| *p ^= gf::mul(coef, *d));
+            return Ok(());
+        }
+        let row_table_len = 32 * self.source_num();
+        ec::encode_data_update(
+            to_i32(delta.len(), "block length")?,
+            self.k_i32(),
+            1,
+            to_i32(index, "source index")?,
+            &self.encode_gf_table[(row * row_table_len)..((row + 1) * row_table_len)],
+            delta,
+            &mut [block.as_mut_ptr()],
         );
         Ok(())
     }
 
-    fn make_decode_table_impl(&self, erasures: &[usize]) -> Result<DecodeTable, Error> {
-        let matrix = self.make_decode_matrix(erasures)?;
-        let cols = self.k as usize;
-        let rows = erasures.len();
-        let decode_matrix = &matrix[0..(cols * rows)];
-        let table = galois::GaloisFiledTable::try_from_matrix(decode_matrix, rows, cols)?;
-        Ok(DecodeTable(table))
+    /// Returns `true` if the only parity row of the encode matrix is all ones, that is RAID5.
+    fn is_xor_parity(&self) -> bool {
+        self.code_num() == 1
+            && self.encode_matrix[self.source_num().pow(2)..]
+                .iter()
+                .all(|c| *c == 1)
+    }
+
+    /// Returns `true` if the parity part of the encode matrix is the P and Q rows of RAID6,
+    /// that is `[1, 1, 1, ...]` and `[1, 2, 4, ...]`.
+    fn is_raid6(&self) -> bool {
+        if self.code_num() != 2 {
+            return false;
+        }
+        let k = self.source_num();
+        let parity = &self.encode_matrix[k * k..];
+        let mut coef = 1;
+        (0..k).all(|j| {
+            let matched = parity[j] == 1 && parity[k + j] == coef;
+            coef = gf::mul(coef, 2);
+            matched
+        })
     }
 
     fn check_update<U: AsMut<[u8]>>(
@@ -559,10 +4716,12 @@ impl ErasureCode {
         Ok(())
     }
 
+    /// Checks the buffers to encode `code_num` code blocks from the source data blocks.
     fn check_encode_buffer<T: AsRef<[u8]>, U: AsMut<[u8]>>(
         &self,
         data: impl AsRef<[T]>,
         mut code: impl AsMut<[U]>,
+        code_num: usize,
     ) -> Result<(), Error> {
         let data = data.as_ref();
         let code = code.as_mut();
@@ -573,11 +4732,11 @@ impl ErasureCode {
                 self.k,
             )));
         }
-        if code.len() != self.code_num() {
+        if code.len() != code_num {
             return Err(Error::invalid_arguments(format!(
                 "code length {} is not equal to code number {}",
                 code.len(),
-                self.m,
+                code_num,
             )));
         }
         let len = data.first().unwrap().as_ref().len();
@@ -591,6 +4750,136 @@ impl ErasureCode {
                 return Err(Error::invalid_arguments("code data block must be equal"));
             }
         }
+        Self::check_disjoint(data, code)
+    }
+
+    /// Checks that no code block shares memory with a data block or another code block,
+    /// which can only happen with the buffers assembled from raw parts by `unsafe` code.
+    fn check_disjoint<T: AsRef<[u8]>, U: AsMut<[u8]>>(
+        data: &[T],
+        code: &mut [U],
+    ) -> Result<(), Error> {
+        let data = data
+            .iter()
+            .map(|d| d.as_ref().as_ptr_range())
+            .collect::<Vec<_>>();
+        let code = code
+            .iter_mut()
+            .map(|c| {
+                let c: &[u8] = c.as_mut();
+                c.as_ptr_range()
+            })
+            .collect::<Vec<_>>();
+        check_disjoint_ranges(&data, &code)
+    }
+
+    /// Checks the buffers of a shortened code, and returns the length of the blocks.
+    fn check_shortened_buffer(
+        &self,
+        data_lens: impl ExactSizeIterator<Item = usize>,
+        code_lens: impl ExactSizeIterator<Item = usize>,
+    ) -> Result<usize, Error> {
+        if data_lens.len() == 0 || data_lens.len() > self.source_num() {
+            return Err(Error::invalid_arguments(format!(
+                "data length {} of the shortened code is not in range 1..={}",
+                data_lens.len(),
+                self.source_num(),
+            )));
+        }
+        if code_lens.len() != self.code_num() {
+            return Err(Error::invalid_arguments(format!(
+                "code length {} is not equal to code number {}",
+                code_lens.len(),
+                self.code_num(),
+            )));
+        }
+        let mut lens = data_lens.chain(code_lens);
+        let len = lens.next().unwrap();
+        if lens.any(|l| l != len) {
+            return Err(Error::invalid_arguments(
+                "data and code blocks must be equal",
+            ));
+        }
+        Ok(len)
+    }
+
+    /// Checks the buffers to code the first `len` bytes of each block.
+    fn check_len_buffer(
+        &self,
+        data_lens: impl ExactSizeIterator<Item = usize>,
+        code_lens: impl ExactSizeIterator<Item = usize>,
+        len: usize,
+    ) -> Result<(), Error> {
+        if data_lens.len() != self.source_num() {
+            return Err(Error::invalid_arguments(format!(
+                "data length {} is not equal to source num {}",
+                data_lens.len(),
+                self.k,
+            )));
+        }
+        if code_lens.len() != self.code_num() {
+            return Err(Error::invalid_arguments(format!(
+                "code length {} is not equal to code number {}",
+                code_lens.len(),
+                self.m,
+            )));
+        }
+        if let Some(blk_len) = data_lens.chain(code_lens).find(|l| *l < len) {
+            return Err(Error::invalid_arguments(format!(
+                "block length {} is shorter than the coding length {}",
+                blk_len, len
+            )));
+        }
+        Ok(())
+    }
+
+    /// Checks the read-only data and code blocks to decode from, and returns the block length.
+    fn check_decode_source<T: AsRef<[u8]>>(&self, data: &[T], code: &[T]) -> Result<usize, Error> {
+        self.check_len_buffer(
+            data.iter().map(|s| s.as_ref().len()),
+            code.iter().map(|s| s.as_ref().len()),
+            0,
+        )?;
+        let len = data[0].as_ref().len();
+        if data.iter().any(|s| s.as_ref().len() != len) {
+            return Err(Error::invalid_arguments("source data block must be equal"));
+        }
+        if code.iter().any(|s| s.as_ref().len() != len) {
+            return Err(Error::invalid_arguments("code data block must be equal"));
+        }
+        Ok(len)
+    }
+
+    /// Splits a stripe of `block_num()` blocks into the data blocks and the code blocks.
+    fn split_stripe<'a, U>(
+        &self,
+        stripe: &'a mut [U],
+    ) -> Result<(&'a mut [U], &'a mut [U]), Error> {
+        if stripe.len() != self.block_num() {
+            return Err(Error::invalid_arguments(format!(
+                "stripe length {} is not equal to block number {}",
+                stripe.len(),
+                self.block_num()
+            )));
+        }
+        Ok(stripe.split_at_mut(self.source_num()))
+    }
+
+    /// Checks the blocks of each stripe like `check_decode_buffer`, telling the index of an invalid stripe.
+    fn check_batch_buffer<D, C, U>(&self, stripes: &mut [(D, C)]) -> Result<(), Error>
+    where
+        D: AsMut<[U]>,
+        C: AsMut<[U]>,
+        U: AsMut<[u8]>,
+    {
+        for (i, (data, code)) in stripes.iter_mut().enumerate() {
+            self.check_decode_buffer(data, code).map_err(|e| match e {
+                Error::InvalidArguments(msg) => {
+                    Error::invalid_arguments(format!("stripe {}: {}", i, msg))
+                }
+                e => e,
+            })?;
+        }
         Ok(())
     }
 
@@ -629,7 +4918,12 @@ impl ErasureCode {
         Ok(())
     }
 
-    fn check_decode_erasure(&self, erasures: &mut Vec<usize>) -> Result<(), Error> {
+    /// Checks the erasures, and returns a sorted and deduplicated copy of them.
+    ///
+    /// The erasures are deduplicated before they are counted against the code number,
+    /// every entry point taking the erasures goes through this to share the semantics.
+    fn check_decode_erasure(&self, erasures: &[usize]) -> Result<Vec<usize>, Error> {
+        let mut erasures = erasures.to_vec();
         erasures.sort_unstable();
         erasures.dedup();
         if erasures.len() > self.code_num() {
@@ -637,18 +4931,42 @@ impl ErasureCode {
         }
         if erasures.iter().any(|e| *e >= self.block_num()) {
             return Err(Error::invalid_arguments(format!(
-                "erasure index out of range: {}",
+                "erasure index out of range: {}, max index is block number {}",
                 erasures
                     .iter()
+                    .filter(|e| **e >= self.block_num())
                     .map(ToString::to_string)
                     .collect::<Vec<_>>()
-                    .join(", ")
+                    .join(", "),
+                self.block_num() - 1
             )));
         }
-        Ok(())
+        Ok(erasures)
+    }
+
+    /// Checks the erasures like `check_decode_erasure`, but rejects the duplicated indices.
+    fn check_strict_erasure(&self, erasures: &[usize]) -> Result<Vec<usize>, Error> {
+        let mut sorted = erasures.to_vec();
+        sorted.sort_unstable();
+        if let Some(w) = sorted.windows(2).find(|w| w[0] == w[1]) {
+            return Err(Error::invalid_arguments(format!(
+                "erasure index {} is duplicated",
+                w[0]
+            )));
+        }
+        self.check_decode_erasure(&sorted)
     }
 
     fn make_decode_matrix(&self, erasures: &[usize]) -> Result<Vec<u8>, Error> {
+        self.make_wanted_decode_matrix(erasures, erasures)
+    }
+
+    /// Makes the decode matrix like `make_decode_matrix`, but only with the rows of the `wanted` blocks in order.
+    fn make_wanted_decode_matrix(
+        &self,
+        erasures: &[usize],
+        wanted: &[usize],
+    ) -> Result<Vec<u8>, Error> {
         let k = self.source_num();
         // record the erasure status for each block,
         // if the block is erased, set it to true
@@ -680,13 +4998,13 @@ impl ErasureCode {
             let mut invert_matrix = vec![0; k * k];
             let ret = gf::invert_matrix(&mut surviver_row, &mut invert_matrix, self.k_i32());
             if !ret {
-                return Err(Error::internal_error("fail to invert matrix"));
+                return Err(Error::singular(erasures));
             }
             invert_matrix
         };
 
         let mut decode_matrix = vec![0_u8; k * self.block_num()];
-        for (i, erasure) in erasures.iter().enumerate() {
+        for (i, erasure) in wanted.iter().enumerate() {
             let mat_row = &mut decode_matrix[(k * i)..(k * (i + 1))];
             match *erasure {
                 erasure if erasure < k => {
@@ -712,6 +5030,478 @@ impl ErasureCode {
 
         Ok(decode_matrix)
     }
+
+    /// Greedily selects `source_num()` blocks from `candidates` in order, whose encode matrix rows are
+    /// linearly independent, so that the selected blocks can recover any other block.
+    ///
+    /// Returns `None` if the candidates do not span the whole space.
+    fn select_independent(
+        &self,
+        candidates: impl IntoIterator<Item = usize>,
+    ) -> Option<Vec<usize>> {
+        let k = self.source_num();
+        // the selected rows reduced to echelon form, along with their pivot columns
+        let mut basis: Vec<(usize, Vec<u8>)> = Vec::with_capacity(k);
+        let mut selected = Vec::with_capacity(k);
+        for i in candidates {
+            let mut row = self.encode_matrix[(k * i)..(k * i + k)].to_vec();
+            for (pivot, basis_row) in basis.iter() {
+                let coef = row[*pivot];
+                if coef != 0 {
+                    row.iter_mut()
+                        .zip(basis_row)
+                        .for_each(|(r, b)| *r ^= gf::mul(coef, *b));
+                }
+            }
+            let Some(pivot) = row.iter().position(|r| *r != 0) else {
+                // linearly dependent on the selected rows
+                continue;
+            };
+            let inv = gf::inv(row[pivot]);
+            row.iter_mut().for_each(|r| *r = gf::mul(*r, inv));
+            basis.push((pivot, row));
+            selected.push(i);
+            if selected.len() == k {
+                return Some(selected);
+            }
+        }
+        None
+    }
+
+    /// Expresses the encode matrix row of `target` as a linear combination of the rows of `survivors`.
+    ///
+    /// Returns the coefficient of each survivor, or `None` if the row is not spanned by the survivors.
+    fn span_coefficients(&self, survivors: &[usize], target: usize) -> Option<Vec<u8>> {
+        let k = self.source_num();
+        let s = survivors.len();
+        let row_of = |i: usize| self.encode_matrix[(k * i)..(k * i + k)].to_vec();
+        // eliminates `row` by the basis, tracking the combination of survivors in `comb`
+        let eliminate = |basis: &[(usize, Vec<u8>, Vec<u8>)], row: &mut [u8], comb: &mut [u8]| {
+            for (pivot, basis_row, basis_comb) in basis {
+                let coef = row[*pivot];
+                if coef != 0 {
+                    row.iter_mut()
+                        .zip(basis_row)
+                        .for_each(|(r, b)| *r ^= gf::mul(coef, *b));
+                    comb.iter_mut()
+                        .zip(basis_comb)
+                        .for_each(|(c, b)| *c ^= gf::mul(coef, *b));
+                }
+            }
+        };
+        // the survivor rows reduced to echelon form, along with their pivot columns and combinations
+        let mut basis: Vec<(usize, Vec<u8>, Vec<u8>)> = Vec::with_capacity(s);
+        for (n, &i) in survivors.iter().enumerate() {
+            let mut row = row_of(i);
+            let mut comb = vec![0_u8; s];
+            comb[n] = 1;
+            eliminate(&basis, &mut row, &mut comb);
+            let Some(pivot) = row.iter().position(|r| *r != 0) else {
+                continue;
+            };
+            let inv = gf::inv(row[pivot]);
+            row.iter_mut().for_each(|r| *r = gf::mul(*r, inv));
+            comb.iter_mut().for_each(|c| *c = gf::mul(*c, inv));
+            basis.push((pivot, row, comb));
+        }
+        let mut row = row_of(target);
+        let mut comb = vec![0_u8; s];
+        eliminate(&basis, &mut row, &mut comb);
+        row.iter().all(|r| *r == 0).then_some(comb)
+    }
+
+    /// Sorts the survivors by index, checks them, and takes the first `source_num()` of them.
+    fn select_survivors<'a>(
+        &self,
+        survivors: &[(usize, &'a [u8])],
+        len: usize,
+    ) -> Result<Vec<(usize, &'a [u8])>, Error> {
+        let mut survivors = self.check_survivors(survivors, len, self.source_num())?;
+        survivors.truncate(self.source_num());
+        Ok(survivors)
+    }
+
+    /// Sorts the survivors by index, and checks their indices and lengths,
+    /// and that there are at least `min_num` of them.
+    fn check_survivors<'a>(
+        &self,
+        survivors: &[(usize, &'a [u8])],
+        len: usize,
+        min_num: usize,
+    ) -> Result<Vec<(usize, &'a [u8])>, Error> {
+        let mut survivors = survivors.to_vec();
+        survivors.sort_unstable_by_key(|(i, _)| *i);
+        if let Some((i, _)) = survivors.iter().find(|(i, _)| *i >= self.block_num()) {
+            return Err(Error::invalid_arguments(format!(
+                "survivor index {} is out of range, max index is block number {}",
+                i,
+                self.block_num() - 1
+            )));
+        }
+        if let Some(w) = survivors.windows(2).find(|w| w[0].0 == w[1].0) {
+            return Err(Error::invalid_arguments(format!(
+                "survivor index {} is duplicated",
+                w[0].0
+            )));
+        }
+        if survivors.len() < min_num {
+            return Err(Error::too_many_erasures(
+                self.block_num() - survivors.len(),
+                self.code_num(),
+            ));
+        }
+        if let Some((i, s)) = survivors.iter().find(|(_, s)| s.len() != len) {
+            return Err(Error::invalid_arguments(format!(
+                "survivor block {} length {} is not equal to block length {}",
+                i,
+                s.len(),
+                len
+            )));
+        }
+        Ok(survivors)
+    }
+
+    /// Computes the `outputs` blocks from the surviving blocks, see `decode_target`.
+    fn produce_impl(
+        &self,
+        survivors: &[(usize, &[u8])],
+        outputs: &mut [(usize, &mut [u8])],
+    ) -> Result<(), Error> {
+        let len = survivors.first().map_or(0, |(_, block)| block.len());
+        let survivors = self.check_survivors(survivors, len, self.source_num())?;
+        let produce = outputs.iter().map(|(i, _)| *i).collect::<Vec<_>>();
+        let mut sorted_produce = produce.clone();
+        sorted_produce.sort_unstable();
+        if let Some(w) = sorted_produce.windows(2).find(|w| w[0] == w[1]) {
+            return Err(Error::invalid_arguments(format!(
+                "produce index {} is duplicated",
+                w[0]
+            )));
+        }
+        if let Some((i, block)) = outputs.iter().find(|(_, block)| block.len() != len) {
+            return Err(Error::invalid_arguments(format!(
+                "output block {} length {} is not equal to block length {}",
+                i,
+                block.len(),
+                len
+            )));
+        }
+        let selected = self
+            .select_independent(survivors.iter().map(|(i, _)| *i))
+            .ok_or_else(|| {
+                Error::invalid_arguments("surviving blocks do not form an invertible set")
+            })?;
+        let survivors = survivors
+            .into_iter()
+            .filter(|(i, _)| selected.contains(i))
+            .collect::<Vec<_>>();
+        let table = self.make_recover_table(&survivors, &produce)?;
+        if produce.is_empty() {
+            return Ok(());
+        }
+        let src_ptrs = survivors
+            .iter()
+            .map(|(_, block)| block.as_ptr())
+            .collect::<Vec<_>>();
+        let mut out_ptrs = outputs
+            .iter_mut()
+            .map(|(_, block)| block.as_mut_ptr())
+            .collect::<Vec<_>>();
+        ec::encode_data(
+            to_i32(len, "block length")?,
+            self.k_i32(),
+            to_i32(produce.len(), "produce number")?,
+            &table,
+            &src_ptrs,
+            &mut out_ptrs,
+        );
+        Ok(())
+    }
+
+    /// Makes the gf table which computes the `targets` blocks from the selected `survivors` blocks.
+    fn make_recover_table(
+        &self,
+        survivors: &[(usize, &[u8])],
+        targets: &[usize],
+    ) -> Result<galois::GaloisFiledTable, Error> {
+        if let Some(target) = targets.iter().find(|t| **t >= self.block_num()) {
+            return Err(Error::invalid_arguments(format!(
+                "erasure index {} is out of range, max index is block number {}",
+                target,
+                self.block_num() - 1
+            )));
+        }
+        let survivor_index = survivors.iter().map(|(i, _)| *i).collect::<Vec<_>>();
+        let recover_matrix = self.make_recover_matrix(&survivor_index, targets)?;
+        galois::GaloisFiledTable::try_from_matrix(&recover_matrix, targets.len(), self.source_num())
+    }
+
+    /// Makes the `targets.len() x k` matrix which computes the `targets` blocks from the `survivors` blocks.
+    ///
+    /// The `survivors` must be exactly `k` distinct block indices, and the row for each target
+    /// is its encode matrix row multiplied by the inverse of the survivors' sub matrix.
+    fn make_recover_matrix(
+        &self,
+        survivors: &[usize],
+        targets: &[usize],
+    ) -> Result<Vec<u8>, Error> {
+        let k = self.source_num();
+        debug_assert_eq!(survivors.len(), k);
+        let mut surviver_row = survivors
+            .iter()
+            .flat_map(|&i| &self.encode_matrix[(k * i)..(k * i + k)])
+            .copied()
+            .collect::<Vec<_>>();
+        let mut invert_matrix = vec![0; k * k];
+        if !gf::invert_matrix(&mut surviver_row, &mut invert_matrix, self.k_i32()) {
+            // the blocks out of the survivors are the erasures
+            let erasures = (0..self.block_num())
+                .filter(|i| !survivors.contains(i))
+                .collect::<Vec<_>>();
+            return Err(Error::singular(&erasures));
+        }
+
+        let mut recover_matrix = vec![0_u8; k * targets.len()];
+        for (row, &target) in recover_matrix.chunks_exact_mut(k).zip(targets) {
+            let target_row = &self.encode_matrix[(k * target)..(k * target + k)];
+            row.iter_mut().enumerate().for_each(|(i, s)| {
+                for (j, &coef) in target_row.iter().enumerate() {
+                    *s ^= gf::mul(invert_matrix[j * k + i], coef);
+                }
+            });
+        }
+        Ok(recover_matrix)
+    }
+}
+
+/// Fills `dest` with the xor of the `blocks`, which all have the same length as `dest`.
+fn xor_blocks<'a>(
+    blocks: impl IntoIterator<Item = &'a [u8]>,
+    dest: &mut [u8],
+) -> Result<(), Error> {
+    let mut blocks = blocks.into_iter();
+    dest.copy_from_slice(blocks.next().unwrap());
+    blocks.try_for_each(|block| gf::xor_into(dest, block))
+}
+
+/// Computes the RAID6 parity of the `data` blocks, P as the xor and Q as the sum of `2^j * D_j`.
+///
+/// Q is computed by Horner's rule from the last block, multiplying by 2 on eight bytes at a time.
+fn raid6_pq(data: &[&[u8]], p: &mut [u8], q: &mut [u8]) {
+    /// Multiplies each byte of `x` by 2 in GF(2^8) with the polynomial 0x11D.
+    fn mul2(x: u64) -> u64 {
+        let hi = x & 0x8080_8080_8080_8080;
+        ((x & 0x7F7F_7F7F_7F7F_7F7F) << 1) ^ ((hi >> 7) * 0x1D)
+    }
+
+    let (last, rest) = data.split_last().unwrap();
+    p.copy_from_slice(last);
+    q.copy_from_slice(last);
+    for block in rest.iter().rev() {
+        let mut p_chunks = p.chunks_exact_mut(8);
+        let mut q_chunks = q.chunks_exact_mut(8);
+        let mut d_chunks = block.chunks_exact(8);
+        for ((p, q), d) in (&mut p_chunks).zip(&mut q_chunks).zip(&mut d_chunks) {
+            let d = u64::from_ne_bytes(d.try_into().unwrap());
+            let p_word = u64::from_ne_bytes((*p).try_into().unwrap()) ^ d;
+            let q_word = mul2(u64::from_ne_bytes((*q).try_into().unwrap())) ^ d;
+            p.copy_from_slice(&p_word.to_ne_bytes());
+            q.copy_from_slice(&q_word.to_ne_bytes());
+        }
+        for ((p, q), d) in p_chunks
+            .into_remainder()
+            .iter_mut()
+            .zip(q_chunks.into_remainder())
+            .zip(d_chunks.remainder())
+        {
+            *p ^= d;
+            *q = gf::mul(*q, 2) ^ d;
+        }
+    }
+}
+
+/// Converts a size or an index to the `i32` taken by isa-l, rejecting the value out of its range.
+fn to_i32(value: usize, name: &str) -> Result<i32, Error> {
+    i32::try_from(value).map_err(|_| {
+        Error::invalid_arguments(format!(
+            "{} {} exceeds the maximum {} supported by isa-l",
+            name,
+            value,
+            i32::MAX
+        ))
+    })
+}
+
+/// Checks that no code range overlaps a data range or another code range,
+/// the empty ranges overlapping nothing.
+fn check_disjoint_ranges(
+    data: &[Range<*const u8>],
+    code: &[Range<*const u8>],
+) -> Result<(), Error> {
+    let overlaps = |a: &Range<*const u8>, b: &Range<*const u8>| {
+        !a.is_empty() && !b.is_empty() && a.start < b.end && b.start < a.end
+    };
+    for (i, c) in code.iter().enumerate() {
+        if let Some(j) = data.iter().position(|d| overlaps(c, d)) {
+            return Err(Error::invalid_arguments(format!(
+                "code block {} overlaps data block {}",
+                i, j
+            )));
+        }
+        if let Some(j) = code[i + 1..].iter().position(|o| overlaps(c, o)) {
+            return Err(Error::invalid_arguments(format!(
+                "code block {} overlaps code block {}",
+                i,
+                i + 1 + j
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Returns the 64-bit FNV-1a digest of an encode matrix.
+fn matrix_digest(matrix: &[u8]) -> u64 {
+    matrix.iter().fold(0xcbf2_9ce4_8422_2325, |h, &b| {
+        (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Advances `comb` to the next combination of `comb.len()` elements from `0..n` in lexicographic order.
+///
+/// Returns `false` if `comb` is already the last combination.
+fn next_combination(comb: &mut [usize], n: usize) -> bool {
+    let r = comb.len();
+    let Some(i) = (0..r).rev().find(|&i| comb[i] < n - r + i) else {
+        return false;
+    };
+    comb[i] += 1;
+    for j in (i + 1)..r {
+        comb[j] = comb[j - 1] + 1;
+    }
+    true
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use alloc::format;
+    use alloc::vec::Vec;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{DecodeTable, MatrixKind};
+
+    /// The magic of the serialized `DecodeTable`.
+    const MAGIC: [u8; 4] = *b"ECDT";
+    /// The version of the serialized format of `DecodeTable`.
+    ///
+    /// Version 2 records the erasures the table is generated for.
+    /// Version 3 records the matrix of the code which generated the table.
+    const VERSION: u32 = 3;
+
+    #[derive(Serialize)]
+    struct DecodeTableRef<'a> {
+        magic: [u8; 4],
+        version: u32,
+        source_num: usize,
+        code_num: usize,
+        /// The code of the matrix kind, see [`kind_code`], `None` if the code is unknown.
+        matrix_kind: Option<u8>,
+        matrix_digest: Option<u64>,
+        erasures: &'a [usize],
+        table: &'a [u8],
+    }
+
+    #[derive(Deserialize)]
+    struct DecodeTableOwned {
+        magic: [u8; 4],
+        version: u32,
+        source_num: usize,
+        code_num: usize,
+        matrix_kind: Option<u8>,
+        matrix_digest: Option<u64>,
+        erasures: Vec<usize>,
+        table: Vec<u8>,
+    }
+
+    /// Returns the stable code of a matrix kind in the serialized form, `0` for a user supplied matrix.
+    fn kind_code(kind: Option<MatrixKind>) -> u8 {
+        match kind {
+            None => 0,
+            Some(MatrixKind::ReedSolomon) => 1,
+            Some(MatrixKind::AdjustedReedSolomon) => 2,
+            Some(MatrixKind::Vandermonde) => 3,
+            Some(MatrixKind::Cauchy) => 4,
+        }
+    }
+
+    /// Returns the matrix kind of a code returned by [`kind_code`].
+    fn code_kind(code: u8) -> Option<Option<MatrixKind>> {
+        match code {
+            0 => Some(None),
+            1 => Some(Some(MatrixKind::ReedSolomon)),
+            2 => Some(Some(MatrixKind::AdjustedReedSolomon)),
+            3 => Some(Some(MatrixKind::Vandermonde)),
+            4 => Some(Some(MatrixKind::Cauchy)),
+            _ => None,
+        }
+    }
+
+    impl Serialize for DecodeTable {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            DecodeTableRef {
+                magic: MAGIC,
+                version: VERSION,
+                source_num: self.source_num,
+                code_num: self.code_num,
+                matrix_kind: self.matrix.map(|(kind, _)| kind_code(kind)),
+                matrix_digest: self.matrix.and_then(|(_, digest)| digest),
+                erasures: &self.erasures,
+                table: &self.table,
+            }
+            .serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for DecodeTable {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            use serde::de::Error;
+            let owned = DecodeTableOwned::deserialize(deserializer)?;
+            if owned.magic != MAGIC {
+                return Err(D::Error::custom("invalid decode table magic"));
+            }
+            if owned.version != VERSION {
+                return Err(D::Error::custom(format!(
+                    "unsupported decode table version {}, expected {}",
+                    owned.version, VERSION
+                )));
+            }
+            let matrix = match owned.matrix_kind {
+                None if owned.matrix_digest.is_some() => {
+                    return Err(D::Error::custom("invalid decode table matrix digest"));
+                }
+                None => None,
+                Some(code) => {
+                    let kind = code_kind(code).ok_or_else(|| {
+                        D::Error::custom(format!("invalid decode table matrix kind {}", code))
+                    })?;
+                    // only a user supplied matrix is identified by its digest
+                    if kind.is_none() != owned.matrix_digest.is_some() {
+                        return Err(D::Error::custom("invalid decode table matrix digest"));
+                    }
+                    Some((kind, owned.matrix_digest))
+                }
+            };
+            let mut table = DecodeTable::from_bytes(
+                owned.table,
+                owned.source_num,
+                owned.code_num,
+                owned.erasures,
+            )
+            .map_err(D::Error::custom)?;
+            table.matrix = matrix;
+            Ok(table)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -768,4 +5558,73 @@ mod test {
         ];
         assert_eq!(decode_matrix, expected_decode_matrix);
     }
+
+    #[test]
+    fn to_i32() {
+        use crate::Error;
+        assert_eq!(super::to_i32(0, "block length"), Ok(0));
+        assert_eq!(
+            super::to_i32(i32::MAX as usize, "block length"),
+            Ok(i32::MAX)
+        );
+        assert!(matches!(
+            super::to_i32(i32::MAX as usize + 1, "block length"),
+            Err(Error::InvalidArguments(_))
+        ));
+    }
+
+    #[test]
+    fn codec_key_matrix() {
+        let ec = super::ErasureCode::with_cauchy(
+            NonZeroUsize::new(K).unwrap(),
+            NonZeroUsize::new(M).unwrap(),
+        )
+        .unwrap();
+        let custom = super::ErasureCode::with_matrix(
+            NonZeroUsize::new(K).unwrap(),
+            NonZeroUsize::new(M).unwrap(),
+            ec.encode_matrix(),
+        )
+        .unwrap();
+        let key = custom.key();
+        assert_eq!(key, custom.key());
+        // the keys whose digests collide still tell the matrices apart
+        let mut collided = key.clone();
+        let mut matrix = ec.encode_matrix().to_vec();
+        matrix[K * K] ^= 1;
+        collided.matrix = Some(matrix.into());
+        assert_eq!(collided.matrix_digest, key.matrix_digest);
+        assert_ne!(collided, key);
+    }
+
+    #[test]
+    fn check_disjoint_ranges() {
+        use crate::Error;
+        let buf = [0_u8; 64];
+        let range = |start: usize, end: usize| buf[start..end].as_ptr_range();
+        let data = [range(0, 16), range(16, 32)];
+        // adjacent and empty ranges do not overlap
+        assert_eq!(
+            super::check_disjoint_ranges(&data, &[range(32, 48), range(48, 64)]),
+            Ok(())
+        );
+        assert_eq!(
+            super::check_disjoint_ranges(&data, &[range(8, 8), range(32, 32), range(32, 48)]),
+            Ok(())
+        );
+        // a code range overlapping the tail of a data range
+        assert!(matches!(
+            super::check_disjoint_ranges(&data, &[range(24, 40), range(48, 64)]),
+            Err(Error::InvalidArguments(_))
+        ));
+        // a code range overlapping another code range, or containing it
+        assert!(matches!(
+            super::check_disjoint_ranges(&data, &[range(32, 48), range(40, 56)]),
+            Err(Error::InvalidArguments(_))
+        ));
+        assert!(matches!(
+            super::check_disjoint_ranges(&data, &[range(32, 64), range(40, 48)]),
+            Err(Error::InvalidArguments(_))
+        ));
+    }
 }
@@ -0,0 +1,73 @@
+//! This module reports the instruction sets that isa-l selects its kernels for.
+//!
+//! isa-l selects the kernels of functions such as `ec_encode_data` at runtime, according to the
+//! instruction sets supported by the CPU. The probe here mirrors that selection, so that the
+//! kernel in use can be reported, e.g. to annotate benchmark results across machines.
+use std::sync::OnceLock;
+
+/// The instruction set that the isa-l kernels of `ec_encode_data` are selected for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SimdLevel {
+    /// The portable kernels without SIMD.
+    Base,
+    /// The SSE kernels, which require SSE4.1.
+    Sse,
+    /// The AVX kernels.
+    Avx,
+    /// The AVX2 kernels.
+    Avx2,
+    /// The AVX-512 kernels, which require AVX-512F, AVX-512BW and AVX-512VL.
+    Avx512,
+    /// The Neon kernels on aarch64.
+    Neon,
+}
+
+/// Returns the instruction set that isa-l uses for `ec_encode_data` in this process.
+///
+/// The CPU is probed once, and the result is cached for the later calls.
+///
+/// # Examples
+/// ```rust
+/// # use erasure_isa_l::features::{cpu_features, SimdLevel};
+/// let level = cpu_features();
+/// println!("isa-l uses the {:?} kernels", level);
+/// # assert_eq!(level, cpu_features());
+/// ```
+pub fn cpu_features() -> SimdLevel {
+    static LEVEL: OnceLock<SimdLevel> = OnceLock::new();
+    *LEVEL.get_or_init(probe)
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn probe() -> SimdLevel {
+    // the detection macros also check that the OS saves the extended registers
+    if is_x86_feature_detected!("avx512f")
+        && is_x86_feature_detected!("avx512bw")
+        && is_x86_feature_detected!("avx512vl")
+    {
+        SimdLevel::Avx512
+    } else if is_x86_feature_detected!("avx2") {
+        SimdLevel::Avx2
+    } else if is_x86_feature_detected!("avx") {
+        SimdLevel::Avx
+    } else if is_x86_feature_detected!("sse4.1") {
+        SimdLevel::Sse
+    } else {
+        SimdLevel::Base
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+fn probe() -> SimdLevel {
+    if std::arch::is_aarch64_feature_detected!("neon") {
+        SimdLevel::Neon
+    } else {
+        SimdLevel::Base
+    }
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+fn probe() -> SimdLevel {
+    SimdLevel::Base
+}
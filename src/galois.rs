@@ -1,7 +1,15 @@
-use std::ops::Deref;
+use alloc::format;
+use alloc::vec::Vec;
+use core::ops::Deref;
 
 use crate::{Error, gf};
 
+/// The minimum buffer length in bytes of [`dot_prod`], required by the `gf_vect_dot_prod` kernels.
+pub const DOT_PROD_MIN_LEN: usize = 32;
+
+/// The minimum buffer length in bytes of [`mul_add`], required by the `gf_vect_mad` kernels.
+pub const MUL_ADD_MIN_LEN: usize = 64;
+
 pub struct GaloisFiledTable(Vec<u8>);
 
 impl GaloisFiledTable {
@@ -24,14 +32,22 @@ impl GaloisFiledTable {
                 rows * cols
             )));
         }
-        let mut gf_table = vec![0_u8; cols * rows * 32];
+        let mut gf_table = Self(Vec::with_capacity(cols * rows * 32));
+        gf_table.regenerate(matrix, rows, cols);
+        Ok(gf_table)
+    }
+
+    /// Rewrites the table in place from a `rows x cols` matrix, reusing the capacity of the buffer.
+    pub(crate) fn regenerate(&mut self, matrix: &[u8], rows: usize, cols: usize) {
+        debug_assert_eq!(matrix.len(), rows * cols);
+        self.0.clear();
+        self.0.resize(cols * rows * 32, 0);
         crate::ec::init_tables(
             cols.try_into().unwrap(),
             rows.try_into().unwrap(),
             matrix,
-            &mut gf_table[..],
+            &mut self.0[..],
         );
-        Ok(Self(gf_table))
     }
 
     pub fn as_slice(&self) -> &[u8] {
@@ -78,7 +94,7 @@ impl AsRef<[u8]> for GaloisFiledTable {
 ///
 /// # Errors
 /// The following errors can occur:
-/// * `Error::invalid_arguments` - If the destination buffer is smaller than 32 bytes.
+/// * `Error::BlockTooSmall` - If the destination buffer is smaller than [`DOT_PROD_MIN_LEN`] bytes.
 /// * `Error::invalid_arguments` - If the source slices are not all equal in length to the destination buffer.
 /// * `Error::invalid_arguments` - If the length of the Galois field table does not match `32 * source.len()`.
 ///
@@ -110,11 +126,8 @@ where
     T: AsRef<[u8]>,
 {
     let len = dest.len();
-    if len < 32 {
-        return Err(Error::invalid_arguments(format!(
-            "Destination buffer too small: length {}, expected at least 32",
-            len
-        )));
+    if len < DOT_PROD_MIN_LEN {
+        return Err(Error::block_too_small(len, DOT_PROD_MIN_LEN));
     }
     let source = source.as_ref();
     if source.iter().any(|s| s.as_ref().len() != len) {
@@ -165,7 +178,7 @@ where
 ///
 /// # Errors
 /// The following errors can occur:
-/// * `Error::invalid_arguments` - If the destination buffer is smaller than 64 bytes
+/// * `Error::BlockTooSmall` - If the destination buffer is smaller than [`MUL_ADD_MIN_LEN`] bytes.
 /// * `Error::invalid_arguments` - If the source slice length does not match the destination buffer length.
 /// * `Error::invalid_arguments` - If the length of the Galois field table does not match `32 * source_num`.
 /// * `Error::invalid_arguments` - If the `index` is out of bounds for the number of source slices.
@@ -177,11 +190,8 @@ pub fn mul_add(
     dest: &mut [u8],
 ) -> Result<(), Error> {
     let len = dest.len();
-    if len < 64 {
-        return Err(Error::invalid_arguments(format!(
-            "Destination buffer too small: length {}, expected at least 64",
-            len
-        )));
+    if len < MUL_ADD_MIN_LEN {
+        return Err(Error::block_too_small(len, MUL_ADD_MIN_LEN));
     }
     if source_i.len() != len {
         return Err(Error::invalid_arguments(format!(
@@ -0,0 +1,464 @@
+//! This module provides a pure Rust erasure code over GF(2^16), for stripes wider than 255 blocks.
+//!
+//! isa-l only works over GF(2^8), which limits a stripe to [`MAX_BLOCK_NUM`](crate::erasure::MAX_BLOCK_NUM)
+//! blocks. Over GF(2^16) a stripe can have up to [`MAX_BLOCK_NUM`] blocks, at the cost of a much slower
+//! codec computed symbol by symbol with log and exp tables, without SIMD and without calling isa-l.
+//!
+//! # Field
+//! The field is GF(2^16) with the primitive polynomial `x^16 + x^12 + x^3 + x + 1` ([`PRIMITIVE_POLY`])
+//! and the generator 2 ([`GENERATOR`]). A block is a sequence of 16-bit symbols in little-endian,
+//! so its length must be even.
+use alloc::format;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::num::NonZeroUsize;
+
+use crate::Error;
+
+/// The number of elements of the field, 2^16.
+pub const FIELD_ORDER: usize = 1 << 16;
+
+/// The primitive polynomial of the field, `x^16 + x^12 + x^3 + x + 1`.
+pub const PRIMITIVE_POLY: u32 = 0x1100B;
+
+/// The generator of the multiplicative group of the field.
+pub const GENERATOR: u16 = 2;
+
+/// The maximum number of blocks (source + code) supported by an [`ErasureCode16`].
+pub const MAX_BLOCK_NUM: usize = FIELD_ORDER - 1;
+
+/// The order of the multiplicative group.
+const GROUP_ORDER: usize = FIELD_ORDER - 1;
+
+struct Tables {
+    /// `exp[n] = GENERATOR^n`, repeated twice so that the sum of two logarithms needs no modulo.
+    exp: [u16; 2 * GROUP_ORDER],
+    /// `log[a]` is the logarithm of `a` to the base `GENERATOR`, `log[0]` is unused.
+    log: [u16; FIELD_ORDER],
+}
+
+static TABLES: Tables = make_tables();
+
+const fn make_tables() -> Tables {
+    let mut tables = Tables {
+        exp: [0; 2 * GROUP_ORDER],
+        log: [0; FIELD_ORDER],
+    };
+    let mut x: u32 = 1;
+    let mut n = 0;
+    while n < GROUP_ORDER {
+        tables.exp[n] = x as u16;
+        tables.exp[n + GROUP_ORDER] = x as u16;
+        tables.log[x as usize] = n as u16;
+        x <<= 1;
+        if x & 0x10000 != 0 {
+            x ^= PRIMITIVE_POLY;
+        }
+        n += 1;
+    }
+    tables
+}
+
+/// Single element GF(2^16) multiply.
+///
+/// # Examples
+/// ```rust
+/// # use erasure_isa_l::gf16;
+/// assert_eq!(gf16::mul(2, 0x8000), 0x100B);
+/// assert_eq!(gf16::mul(7, 1), 7);
+/// ```
+pub fn mul(a: u16, b: u16) -> u16 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    TABLES.exp[TABLES.log[a as usize] as usize + TABLES.log[b as usize] as usize]
+}
+
+/// Single element GF(2^16) inverse.
+///
+/// The inverse of 0 is 0.
+pub fn inv(a: u16) -> u16 {
+    if a == 0 {
+        return 0;
+    }
+    TABLES.exp[GROUP_ORDER - TABLES.log[a as usize] as usize]
+}
+
+/// Returns `GENERATOR^n`.
+pub fn exp(n: u16) -> u16 {
+    TABLES.exp[n as usize]
+}
+
+/// Returns the logarithm of `a` to the base [`GENERATOR`], or `None` if `a` is 0.
+pub fn log(a: u16) -> Option<u16> {
+    (a != 0).then(|| TABLES.log[a as usize])
+}
+
+/// Multiplies the `a_rows x a_cols` matrix `a` by the `a_cols x b_cols` matrix `b` in GF(2^16).
+///
+/// # Returns
+/// The `a_rows x b_cols` product in row-major order.
+///
+/// # Panics
+/// Panics if the length of `a` is not `a_rows * a_cols`, or the length of `b` is not `a_cols * b_cols`.
+pub fn mat_mul(a: &[u16], a_rows: usize, a_cols: usize, b: &[u16], b_cols: usize) -> Vec<u16> {
+    assert_eq!(a.len(), a_rows * a_cols, "matrix a must be a_rows x a_cols");
+    assert_eq!(b.len(), a_cols * b_cols, "matrix b must be a_cols x b_cols");
+    let mut out = vec![0_u16; a_rows * b_cols];
+    for r in 0..a_rows {
+        for i in 0..a_cols {
+            let coef = a[r * a_cols + i];
+            if coef == 0 {
+                continue;
+            }
+            for c in 0..b_cols {
+                out[r * b_cols + c] ^= mul(coef, b[i * b_cols + c]);
+            }
+        }
+    }
+    out
+}
+
+/// Inverts an `n x n` matrix in GF(2^16) by Gauss-Jordan elimination.
+///
+/// # Returns
+/// The inverse matrix in row-major order, or `None` if the matrix is singular.
+///
+/// # Panics
+/// Panics if the length of `input` is not `n * n`.
+pub fn invert_matrix(input: &[u16], n: usize) -> Option<Vec<u16>> {
+    assert_eq!(input.len(), n * n, "input matrix must be n x n");
+    let mut input = input.to_vec();
+    let mut output = vec![0_u16; n * n];
+    (0..n).for_each(|i| output[i * n + i] = 1);
+    for i in 0..n {
+        let pivot = (i..n).find(|&r| input[r * n + i] != 0)?;
+        if pivot != i {
+            for c in 0..n {
+                input.swap(i * n + c, pivot * n + c);
+                output.swap(i * n + c, pivot * n + c);
+            }
+        }
+        let scale = inv(input[i * n + i]);
+        for c in 0..n {
+            input[i * n + c] = mul(input[i * n + c], scale);
+            output[i * n + c] = mul(output[i * n + c], scale);
+        }
+        for r in (0..n).filter(|&r| r != i) {
+            let factor = input[r * n + i];
+            if factor == 0 {
+                continue;
+            }
+            for c in 0..n {
+                input[r * n + c] ^= mul(factor, input[i * n + c]);
+                output[r * n + c] ^= mul(factor, output[i * n + c]);
+            }
+        }
+    }
+    Some(output)
+}
+
+/// Generates a `rows x k` systematic Cauchy matrix in GF(2^16), in the same form as
+/// [`gf::cauchy1_matrix`](crate::gf::cauchy1_matrix).
+///
+/// The first `k` rows are the identity matrix, and the element of the row `i >= k` and the column `j`
+/// is `1 / (i ^ j)`. Any `k` rows of it are invertible.
+///
+/// # Panics
+/// Panics if `rows` is less than `k`, or larger than [`MAX_BLOCK_NUM`].
+pub fn cauchy1_matrix(rows: usize, k: usize) -> Vec<u16> {
+    assert!(k <= rows, "rows must be at least k");
+    assert!(
+        rows <= MAX_BLOCK_NUM,
+        "rows must not exceed {MAX_BLOCK_NUM}"
+    );
+    let mut matrix = vec![0_u16; rows * k];
+    (0..k).for_each(|i| matrix[i * k + i] = 1);
+    for i in k..rows {
+        for j in 0..k {
+            matrix[i * k + j] = inv((i ^ j) as u16);
+        }
+    }
+    matrix
+}
+
+/// Computes `dest = sum(coefs[i] * sources[i])` symbol by symbol.
+fn dot_prod(coefs: &[u16], sources: &[&[u8]], dest: &mut [u8]) {
+    dest.fill(0);
+    for (&coef, source) in coefs.iter().zip(sources) {
+        let dest = dest.chunks_exact_mut(2).zip(source.chunks_exact(2));
+        match coef {
+            0 => continue,
+            1 => dest.for_each(|(d, s)| {
+                d[0] ^= s[0];
+                d[1] ^= s[1];
+            }),
+            _ => {
+                let log_coef = TABLES.log[coef as usize] as usize;
+                for (d, s) in dest {
+                    let s = u16::from_le_bytes([s[0], s[1]]);
+                    if s == 0 {
+                        continue;
+                    }
+                    let product = TABLES.exp[log_coef + TABLES.log[s as usize] as usize];
+                    let d = <&mut [u8; 2]>::try_from(d).unwrap();
+                    *d = (u16::from_le_bytes(*d) ^ product).to_le_bytes();
+                }
+            }
+        }
+    }
+}
+
+/// ErasureCode16 is an erasure code over GF(2^16), which supports up to [`MAX_BLOCK_NUM`] blocks.
+///
+/// It works like [`ErasureCode`](crate::erasure::ErasureCode) with a systematic Cauchy matrix,
+/// except that it is computed in pure Rust, and the length of the blocks must be even.
+/// Prefer `ErasureCode` whenever the stripe fits in 255 blocks, as it is much faster.
+///
+/// # Examples
+/// ```rust
+/// # use erasure_isa_l::gf16::ErasureCode16;
+/// # use std::num::NonZeroUsize;
+/// const BLOCK_LEN: usize = 64;
+/// let k = NonZeroUsize::new(300).unwrap();
+/// let m = NonZeroUsize::new(4).unwrap();
+/// let ec = ErasureCode16::new(k, m).unwrap();
+/// let mut data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+/// let mut parity = ec.encode_to_owned(&data).expect("Encoding failed");
+/// let orig = data.clone();
+/// data[7].fill(0);
+/// data[299].fill(0);
+/// parity[1].fill(0);
+/// let recovered = ec.decode(&mut data, &mut parity, [7, 299, 301]).expect("Decoding failed");
+/// assert_eq!(recovered, vec![7, 299, 301]);
+/// assert_eq!(data, orig);
+/// ```
+pub struct ErasureCode16 {
+    k: usize,
+    m: usize,
+    /// The `m x k` parity rows of the encode matrix, the identity rows on top are implicit.
+    parity_matrix: Vec<u16>,
+}
+
+impl ErasureCode16 {
+    /// Creates a new `ErasureCode16` with a systematic Cauchy matrix.
+    ///
+    /// # Arguments
+    /// * `source_num` - The number of source data blocks.
+    /// * `code_num` - The number of code blocks.
+    ///
+    /// # Errors
+    /// * `Error::InvalidArguments` - If `source_num + code_num` exceeds [`MAX_BLOCK_NUM`].
+    pub fn new(source_num: NonZeroUsize, code_num: NonZeroUsize) -> Result<Self, Error> {
+        let (k, m) = (source_num.get(), code_num.get());
+        if k > MAX_BLOCK_NUM || m > MAX_BLOCK_NUM - k {
+            return Err(Error::invalid_arguments(format!(
+                "block number {} + {} exceeds {}",
+                k, m, MAX_BLOCK_NUM
+            )));
+        }
+        Ok(Self {
+            k,
+            m,
+            parity_matrix: cauchy1_matrix(k + m, k).split_off(k * k),
+        })
+    }
+
+    /// Returns the number of source data blocks.
+    pub fn source_num(&self) -> usize {
+        self.k
+    }
+
+    /// Returns the number of code blocks.
+    pub fn code_num(&self) -> usize {
+        self.m
+    }
+
+    /// Returns the total number of blocks.
+    pub fn block_num(&self) -> usize {
+        self.k + self.m
+    }
+
+    /// Returns the `code_num() x source_num()` parity rows of the encode matrix in row-major order.
+    ///
+    /// The encode matrix is systematic, its first `source_num()` rows are the identity matrix.
+    pub fn parity_matrix(&self) -> &[u16] {
+        &self.parity_matrix
+    }
+
+    /// Encodes the source data into code blocks.
+    ///
+    /// # Arguments
+    /// * `data` - The source data blocks to be encoded.
+    /// * `code` - The code blocks to be filled with the encoded data.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number,
+    ///   or the code blocks number is not equal to the code number.
+    /// * `Error::InvalidArguments` - If the input data or code blocks do not have the same even length.
+    pub fn encode<T: AsRef<[u8]>, U: AsMut<[u8]>>(
+        &self,
+        data: impl AsRef<[T]>,
+        mut code: impl AsMut<[U]>,
+    ) -> Result<(), Error> {
+        let data = data.as_ref().iter().map(AsRef::as_ref).collect::<Vec<_>>();
+        let code = code
+            .as_mut()
+            .iter_mut()
+            .map(AsMut::as_mut)
+            .collect::<Vec<_>>();
+        self.check_blocks(&data, &code)?;
+        for (row, block) in self.parity_matrix.chunks_exact(self.k).zip(code) {
+            dot_prod(row, &data, block);
+        }
+        Ok(())
+    }
+
+    /// Encodes the source data into newly allocated code blocks.
+    ///
+    /// # Errors
+    /// The same as [`encode`](Self::encode).
+    pub fn encode_to_owned<T: AsRef<[u8]>>(
+        &self,
+        data: impl AsRef<[T]>,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        let len = data.as_ref().first().map_or(0, |d| d.as_ref().len());
+        let mut code = vec![vec![0_u8; len]; self.m];
+        self.encode(data, &mut code)?;
+        Ok(code)
+    }
+
+    /// Decode the erased blocks from the surviving data and code blocks.
+    ///
+    /// The content of the erased blocks will be recovered and written back to the `data` and `code` buffers.
+    /// And the content of the non-erased blocks will not be changed.
+    ///
+    /// # Arguments
+    /// * `data` - The data blocks.
+    /// * `code` - The code blocks.
+    /// * `erasures` - The indices of the erased blocks.
+    ///
+    /// # Returns
+    /// The sorted and deduplicated indices of the recovered blocks.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::TooManyErasure` - If the number of erasures is larger than the code number.
+    /// * `Error::InvalidArguments` - If the erasure indices are out of range.
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number,
+    ///   or the code blocks number is not equal to the code number.
+    /// * `Error::InvalidArguments` - If the input data or code blocks do not have the same even length.
+    pub fn decode<U: AsMut<[u8]>>(
+        &self,
+        mut data: impl AsMut<[U]>,
+        mut code: impl AsMut<[U]>,
+        erasures: impl AsRef<[usize]>,
+    ) -> Result<Vec<usize>, Error> {
+        let mut erasures = erasures.as_ref().to_vec();
+        erasures.sort_unstable();
+        erasures.dedup();
+        if erasures.len() > self.m {
+            return Err(Error::too_many_erasures(erasures.len(), self.m));
+        }
+        if let Some(e) = erasures.iter().find(|e| **e >= self.block_num()) {
+            return Err(Error::invalid_arguments(format!(
+                "erasure index {} is out of range, max index is block number {}",
+                e,
+                self.block_num() - 1
+            )));
+        }
+        let mut data = data
+            .as_mut()
+            .iter_mut()
+            .map(AsMut::as_mut)
+            .collect::<Vec<_>>();
+        let mut code = code
+            .as_mut()
+            .iter_mut()
+            .map(AsMut::as_mut)
+            .collect::<Vec<_>>();
+        self.check_blocks(&data, &code)?;
+        if erasures.is_empty() {
+            return Ok(erasures);
+        }
+
+        let k = self.k;
+        let mut survivors = Vec::with_capacity(k);
+        let mut lost = Vec::with_capacity(erasures.len());
+        for (i, block) in data.iter_mut().chain(code.iter_mut()).enumerate() {
+            if erasures.binary_search(&i).is_ok() {
+                lost.push(&mut **block);
+            } else if survivors.len() < k {
+                survivors.push((i, &**block));
+            }
+        }
+        // the rows of the surviving blocks, whose inverse maps them back to the source data
+        let rows = survivors
+            .iter()
+            .flat_map(|(i, _)| self.row(*i))
+            .collect::<Vec<_>>();
+        let inverse = invert_matrix(&rows, k).ok_or_else(|| Error::Singular(erasures.clone()))?;
+        let wanted = erasures
+            .iter()
+            .flat_map(|i| self.row(*i))
+            .collect::<Vec<_>>();
+        let decode_matrix = mat_mul(&wanted, erasures.len(), k, &inverse, k);
+        let sources = survivors.iter().map(|(_, b)| *b).collect::<Vec<_>>();
+        for (r, block) in lost.into_iter().enumerate() {
+            dot_prod(&decode_matrix[r * k..(r + 1) * k], &sources, block);
+        }
+        Ok(erasures)
+    }
+}
+
+/// private implementation of ErasureCode16
+impl ErasureCode16 {
+    /// Returns the row of the encode matrix for the block `index`.
+    fn row(&self, index: usize) -> impl Iterator<Item = u16> + '_ {
+        let k = self.k;
+        (0..k).map(move |j| match index.checked_sub(k) {
+            None => u16::from(index == j),
+            Some(p) => self.parity_matrix[p * k + j],
+        })
+    }
+
+    fn check_blocks(
+        &self,
+        data: &[impl AsRef<[u8]>],
+        code: &[impl AsRef<[u8]>],
+    ) -> Result<(), Error> {
+        if data.len() != self.k {
+            return Err(Error::invalid_arguments(format!(
+                "data length {} is not equal to source number {}",
+                data.len(),
+                self.k
+            )));
+        }
+        if code.len() != self.m {
+            return Err(Error::invalid_arguments(format!(
+                "code length {} is not equal to code number {}",
+                code.len(),
+                self.m
+            )));
+        }
+        let len = data[0].as_ref().len();
+        if data
+            .iter()
+            .map(AsRef::as_ref)
+            .chain(code.iter().map(AsRef::as_ref))
+            .any(|b| b.len() != len)
+        {
+            return Err(Error::invalid_arguments(
+                "data and code blocks must have the same length",
+            ));
+        }
+        if !len.is_multiple_of(2) {
+            return Err(Error::invalid_arguments(format!(
+                "block length {} is not a multiple of the symbol size 2",
+                len
+            )));
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,38 @@
+//! Bindings to the isa-l library for erasure coding operations.
+//!
+//! # Features
+//! * `std` (default) - Enables the modules depending on the standard library, [`cache`], [`features`]
+//!   and [`stream`], and the I/O related methods. Without it the crate is `no_std` and requires `alloc`.
+//! * `rayon` - Enables the parallel methods, implies `std`.
+//! * `gf16` - Enables the [`gf16`] module, a pure Rust erasure code over GF(2^16) for stripes wider than 255 blocks.
+//! * `serde` - Enables the serialization of [`DecodeTable`](erasure::DecodeTable).
+//! * `testkit` - Enables the [`testkit`] module of conformance checks for the downstream tests.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
 mod bind;
+pub mod buffer;
+#[cfg(feature = "std")]
+pub mod cache;
+pub mod checksum;
 pub mod erasure;
+#[cfg(feature = "std")]
+pub mod features;
 pub mod galois;
+#[cfg(feature = "gf16")]
+pub mod gf16;
+pub mod lrc;
+pub mod product;
+pub mod reference;
+#[cfg(feature = "std")]
+pub mod stream;
+pub mod stripe;
+#[cfg(feature = "testkit")]
+pub mod testkit;
 
 pub use bind::ec;
 pub use bind::gf;
@@ -12,27 +44,74 @@ pub enum Error {
     /// and the lost data cannot be recovered.
     #[error("Too Many Erased Blocks: {0} erased, up to {1} allowed")]
     TooManyErasures(usize, usize),
+    /// BlockTooSmall: The block is shorter than the minimum length required by a SIMD kernel of isa-l,
+    /// with the length of the block and the minimum length.
+    #[error("Block Too Small: {0} bytes, at least {1} required")]
+    BlockTooSmall(usize, usize),
     /// InvalidArguments: The the input is invalid.
     #[error("Invalid Arguments: {0}")]
     InvalidArguments(String),
+    /// Singular: The sub matrix of the surviving blocks is not invertible, with the sorted erased blocks.
+    ///
+    /// The erasures are unrecoverable by the encode matrix of the codec, rather than by a failure
+    /// of the library. It only happens with a matrix not guaranteeing every sub matrix to be invertible,
+    /// such as the Vandermonde one, and a Cauchy matrix avoids it.
+    #[error("Singular Matrix: the erasures {0:?} are unrecoverable by the encode matrix")]
+    Singular(Vec<usize>),
     /// InternalError: An internal error caused by libisa-l.
     #[error("Internal Error: {0}")]
     InternalError(String),
+    /// Io: An I/O error from the underlying reader or writer.
+    #[cfg(feature = "std")]
+    #[error("IO Error: {0}")]
+    Io(#[from] std::io::Error),
     /// Other: Other errors that are not covered by the above.
     #[error("Error: {0}")]
     Other(String),
 }
 
+/// Compares the variants of the errors, the numbers of `TooManyErasures` and `BlockTooSmall`,
+/// and the erasures of `Singular`.
+///
+/// The messages of the string variants are diagnostics which may change between versions,
+/// so they are not compared, and `Io` errors are compared by their [`kind`](std::io::Error::kind).
+impl PartialEq for Error {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::TooManyErasures(a, b), Self::TooManyErasures(c, d)) => a == c && b == d,
+            (Self::BlockTooSmall(a, b), Self::BlockTooSmall(c, d)) => a == c && b == d,
+            (Self::Singular(a), Self::Singular(b)) => a == b,
+            #[cfg(feature = "std")]
+            (Self::Io(a), Self::Io(b)) => a.kind() == b.kind(),
+            (Self::InvalidArguments(_), Self::InvalidArguments(_))
+            | (Self::InternalError(_), Self::InternalError(_))
+            | (Self::Other(_), Self::Other(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+/// The result type of this crate, with [`Error`] as the default error type.
+pub type Result<T, E = Error> = core::result::Result<T, E>;
+
 #[allow(dead_code)]
 impl Error {
     fn too_many_erasures(erasures: usize, max_erasures: usize) -> Self {
         Self::TooManyErasures(erasures, max_erasures)
     }
 
+    fn block_too_small(len: usize, min_len: usize) -> Self {
+        Self::BlockTooSmall(len, min_len)
+    }
+
     fn invalid_arguments(msg: impl Into<String>) -> Self {
         Self::InvalidArguments(msg.into())
     }
 
+    fn singular(erasures: &[usize]) -> Self {
+        Self::Singular(erasures.to_vec())
+    }
+
     fn internal_error(msg: impl Into<String>) -> Self {
         Self::InternalError(msg.into())
     }
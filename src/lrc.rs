@@ -0,0 +1,316 @@
+//! This module provides Local Reconstruction Codes (LRC) built on top of [`ErasureCode`].
+//!
+//! A local reconstruction code splits the source data blocks into groups, and adds a local parity
+//! to each group besides the global parities over all the source data blocks.
+//! A single failure in a group is repaired by reading only the rest of the group,
+//! instead of `source_num` blocks as with a Reed-Solomon code.
+use alloc::format;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::num::NonZeroUsize;
+
+use crate::Error;
+use crate::erasure::ErasureCode;
+use crate::gf;
+
+/// LrcCode is a local reconstruction code with local parities over groups of source data blocks,
+/// and global parities over all the source data blocks.
+///
+/// The blocks are indexed as follows, with `k = source_num()`, `l = local_num()` and `r = global_num()`:
+/// * `0..k` - The source data blocks, the block `i` belongs to the group `i / group_len()`.
+/// * `k..k + l` - The local parity blocks, the block `k + g` is the local parity of the group `g`.
+/// * `k + l..k + l + r` - The global parity blocks.
+///
+/// The generator matrix is a pyramid code: a Cauchy matrix with `r + 1` parity rows is generated,
+/// its first parity row is split into the `l` local parity rows by the groups,
+/// and the rest `r` rows are the global parity rows.
+/// Since the local parities sum up to the split row, the code is at least as strong as the
+/// `(k, r + 1)` MDS code it is derived from.
+///
+/// # Parameters
+/// * `local_num` must divide `source_num`, so that the groups have the same length.
+/// * `source_num + local_num + global_num` must not exceed 255.
+///
+/// # Recoverable Patterns
+/// * A single erasure in a group, among its source data blocks and its local parity,
+///   is repaired locally from the other `group_len()` blocks of the group.
+/// * Any pattern of up to `global_num() + 1` erasures is recoverable.
+/// * Many larger patterns of up to `local_num() + global_num()` erasures are recoverable as well,
+///   such as one erasure in each group plus `global_num()` more erasures.
+pub struct LrcCode {
+    ec: ErasureCode,
+    local_num: usize,
+    global_num: usize,
+}
+
+impl LrcCode {
+    /// Creates a new `LrcCode`.
+    ///
+    /// # Arguments
+    /// * `source_num` - The number of source data blocks.
+    /// * `local_num` - The number of local parities, that is, the number of groups.
+    /// * `global_num` - The number of global parities.
+    ///
+    /// # Errors
+    /// * `Error::InvalidArguments` - If `local_num` does not divide `source_num`.
+    /// * `Error::InvalidArguments` - If `source_num + local_num + global_num` exceeds 255.
+    pub fn new(
+        source_num: NonZeroUsize,
+        local_num: NonZeroUsize,
+        global_num: NonZeroUsize,
+    ) -> Result<Self, Error> {
+        let (k, l, r) = (source_num.get(), local_num.get(), global_num.get());
+        if !k.is_multiple_of(l) {
+            return Err(Error::invalid_arguments(format!(
+                "local number {} does not divide source number {}",
+                l, k
+            )));
+        }
+        if k + l + r > 255 {
+            return Err(Error::invalid_arguments(format!(
+                "block number {} + {} + {} exceeds 255",
+                k, l, r
+            )));
+        }
+        let group_len = k / l;
+        // the MDS code with `r + 1` parity rows, whose first parity row is split into the local rows
+        let cauchy = gf::cauchy1_matrix((k + r + 1).try_into().unwrap(), k.try_into().unwrap());
+        let (identity, parity) = cauchy.split_at(k * k);
+        let (split_row, global_rows) = parity.split_at(k);
+        let mut matrix = Vec::with_capacity((k + l + r) * k);
+        matrix.extend_from_slice(identity);
+        for g in 0..l {
+            matrix.extend(
+                split_row
+                    .iter()
+                    .enumerate()
+                    .map(|(j, coef)| if j / group_len == g { *coef } else { 0 }),
+            );
+        }
+        matrix.extend_from_slice(global_rows);
+        let ec = ErasureCode::with_matrix(source_num, NonZeroUsize::new(l + r).unwrap(), &matrix)?;
+        Ok(Self {
+            ec,
+            local_num: l,
+            global_num: r,
+        })
+    }
+
+    /// Returns the underlying erasure code, whose code blocks are the local parities followed by
+    /// the global parities.
+    pub fn codec(&self) -> &ErasureCode {
+        &self.ec
+    }
+
+    /// Returns the number of source data blocks.
+    pub fn source_num(&self) -> usize {
+        self.ec.source_num()
+    }
+
+    /// Returns the number of local parities, that is, the number of groups.
+    pub fn local_num(&self) -> usize {
+        self.local_num
+    }
+
+    /// Returns the number of global parities.
+    pub fn global_num(&self) -> usize {
+        self.global_num
+    }
+
+    /// Returns the number of source data blocks in each group.
+    pub fn group_len(&self) -> usize {
+        self.source_num() / self.local_num
+    }
+
+    /// Returns the total number of blocks.
+    pub fn block_num(&self) -> usize {
+        self.ec.block_num()
+    }
+
+    /// Returns the group of the block at `index`, or `None` if it is a global parity or out of range.
+    pub fn group_of(&self, index: usize) -> Option<usize> {
+        let k = self.source_num();
+        if index < k {
+            Some(index / self.group_len())
+        } else if index < k + self.local_num {
+            Some(index - k)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the indices of the blocks of the group `group`, that is, its source data blocks
+    /// followed by its local parity.
+    ///
+    /// # Panics
+    /// Panics if `group` is not less than `local_num()`.
+    pub fn group_members(&self, group: usize) -> Vec<usize> {
+        assert!(group < self.local_num, "group {} is out of range", group);
+        let g = self.group_len();
+        (group * g..(group + 1) * g)
+            .chain(core::iter::once(self.source_num() + group))
+            .collect()
+    }
+
+    /// Encodes the source data blocks into the local and global parity blocks.
+    ///
+    /// # Arguments
+    /// * `data` - The `source_num()` source data blocks.
+    /// * `code` - The `local_num() + global_num()` parity blocks, the local parities followed by the global ones.
+    ///
+    /// # Errors
+    /// The same as [`ErasureCode::encode`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::lrc::LrcCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let k = NonZeroUsize::new(6).unwrap();
+    /// let l = NonZeroUsize::new(2).unwrap();
+    /// let r = NonZeroUsize::new(2).unwrap();
+    /// let code = LrcCode::new(k, l, r).unwrap();
+    /// let mut data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// let mut parity = vec![vec![0_u8; BLOCK_LEN]; l.get() + r.get()];
+    /// code.encode(&data, &mut parity).expect("Encoding failed");
+    /// let orig = data.clone();
+    /// // Lose a block of each group, both are repaired locally
+    /// data[1].fill(0);
+    /// data[4].fill(0);
+    /// code.decode(&mut data, &mut parity, [1, 4]).expect("Decoding failed");
+    /// assert_eq!(data, orig);
+    /// ```
+    pub fn encode<T: AsRef<[u8]>, U: AsMut<[u8]>>(
+        &self,
+        data: impl AsRef<[T]>,
+        code: impl AsMut<[U]>,
+    ) -> Result<(), Error> {
+        self.ec.encode(data, code)
+    }
+
+    /// Decodes the erased blocks, repairing them locally whenever possible.
+    ///
+    /// Each group with a single erasure is repaired from the rest of the group,
+    /// then the remaining erasures, if any, are recovered from the global parities
+    /// and the surviving blocks.
+    ///
+    /// The content of the erased blocks will be recovered and written back,
+    /// and the content of the non-erased blocks will not be changed.
+    ///
+    /// # Arguments
+    /// * `data` - The source data blocks.
+    /// * `code` - The local parity blocks followed by the global parity blocks.
+    /// * `erasures` - The indices of the erased blocks.
+    ///
+    /// # Returns
+    /// The sorted and deduplicated indices of the recovered blocks.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::TooManyErasures` - If the erasure pattern cannot be recovered, the number of erasures
+    ///   and the number of erasures that is always recoverable are reported.
+    /// * `Error::InvalidArguments` - If the erasure indices are out of range.
+    /// * `Error::InvalidArguments` - If the data blocks number is not equal to the source number,
+    ///   or the code blocks number is not equal to `local_num() + global_num()`.
+    /// * `Error::InvalidArguments` - If the input data or code blocks do not have the same length.
+    pub fn decode<U: AsMut<[u8]>>(
+        &self,
+        mut data: impl AsMut<[U]>,
+        mut code: impl AsMut<[U]>,
+        erasures: impl AsRef<[usize]>,
+    ) -> Result<Vec<usize>, Error> {
+        let (data, code) = (data.as_mut(), code.as_mut());
+        self.check_blocks(data, code)?;
+        let mut erasures = erasures.as_ref().to_vec();
+        erasures.sort_unstable();
+        erasures.dedup();
+        if let Some(i) = erasures.iter().find(|i| **i >= self.block_num()) {
+            return Err(Error::invalid_arguments(format!(
+                "erasure index {} is out of range, max index is block number {}",
+                i,
+                self.block_num() - 1
+            )));
+        }
+        let mut blocks = data
+            .iter_mut()
+            .chain(code.iter_mut())
+            .map(AsMut::as_mut)
+            .collect::<Vec<_>>();
+        let mut erased = vec![false; self.block_num()];
+        erasures.iter().for_each(|i| erased[*i] = true);
+
+        // repair the groups with a single erasure locally
+        for group in 0..self.local_num {
+            let members = self.group_members(group);
+            let mut lost = members.iter().filter(|i| erased[**i]);
+            let (Some(&target), None) = (lost.next(), lost.next()) else {
+                continue;
+            };
+            let survivors = members
+                .iter()
+                .filter(|i| **i != target)
+                .map(|i| (*i, &*blocks[*i]))
+                .collect::<Vec<_>>();
+            if let Some(block) = self.ec.try_recover_block(&survivors, target)? {
+                blocks[target].copy_from_slice(&block);
+                erased[target] = false;
+            }
+        }
+
+        // recover the rest from all the surviving blocks
+        let remaining = (0..self.block_num())
+            .filter(|i| erased[*i])
+            .collect::<Vec<_>>();
+        if !remaining.is_empty() {
+            let survivors = (0..self.block_num())
+                .filter(|i| !erased[*i])
+                .map(|i| (i, &*blocks[i]))
+                .collect::<Vec<_>>();
+            let produced = match self.ec.decode_target(&survivors, &remaining) {
+                Ok(produced) => produced,
+                Err(Error::InvalidArguments(_)) | Err(Error::TooManyErasures(_, _)) => {
+                    return Err(Error::too_many_erasures(
+                        erasures.len(),
+                        self.global_num + 1,
+                    ));
+                }
+                Err(e) => return Err(e),
+            };
+            for (i, block) in produced {
+                blocks[i].copy_from_slice(&block);
+            }
+        }
+        Ok(erasures)
+    }
+}
+
+/// private implementation of LrcCode
+impl LrcCode {
+    fn check_blocks<U: AsMut<[u8]>>(&self, data: &mut [U], code: &mut [U]) -> Result<(), Error> {
+        if data.len() != self.source_num() {
+            return Err(Error::invalid_arguments(format!(
+                "data length {} is not equal to source number {}",
+                data.len(),
+                self.source_num()
+            )));
+        }
+        if code.len() != self.ec.code_num() {
+            return Err(Error::invalid_arguments(format!(
+                "code length {} is not equal to code number {}",
+                code.len(),
+                self.ec.code_num()
+            )));
+        }
+        let len = data[0].as_mut().len();
+        if data
+            .iter_mut()
+            .chain(code.iter_mut())
+            .any(|b| b.as_mut().len() != len)
+        {
+            return Err(Error::invalid_arguments(
+                "data and code blocks must be equal",
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,248 @@
+//! This module provides two-dimensional product codes built on top of [`ErasureCode`].
+//!
+//! A product code arranges the source data blocks in a grid, encodes each row with a row code,
+//! and then encodes each column (including the columns of row parity) with a column code.
+//! It tolerates more failure patterns than a single code with the same overhead,
+//! by decoding rows and columns iteratively.
+use alloc::format;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::Error;
+use crate::erasure::ErasureCode;
+
+/// ProductCode is a two-dimensional erasure code composed of a row code and a column code.
+///
+/// The blocks are arranged in a grid of `rows()` x `cols()` blocks in row-major order,
+/// that is, the block at `(row, col)` is at index `row * cols() + col`.
+/// The top-left `col_code.source_num()` x `row_code.source_num()` sub-grid holds the source data blocks,
+/// and the rest of the grid holds the parity blocks:
+/// * The right `row_code.code_num()` columns of the top rows hold the row parity.
+/// * The bottom `col_code.code_num()` rows hold the column parity, including the parity of the row parity.
+///
+/// # Recoverable Patterns
+/// The erased blocks are recovered iteratively: each row with no more than `row_code.code_num()` erasures
+/// is decoded by the row code, and each column with no more than `col_code.code_num()` erasures is decoded
+/// by the column code, until all the erasures are recovered or no progress can be made.
+///
+/// With `m1 = row_code.code_num()` and `m2 = col_code.code_num()`:
+/// * Any pattern of up to `(m1 + 1) * (m2 + 1) - 1` erasures is recoverable.
+/// * Many larger patterns are recoverable as well, such as a whole row or a whole column being erased.
+/// * A pattern is unrecoverable if it contains `m2 + 1` rows sharing `m1 + 1` erased columns,
+///   e.g. a `(m2 + 1) x (m1 + 1)` rectangle of erasures, since no row or column in it can make progress.
+pub struct ProductCode {
+    row_code: ErasureCode,
+    col_code: ErasureCode,
+}
+
+impl ProductCode {
+    /// Creates a new `ProductCode` from a row code and a column code.
+    ///
+    /// # Arguments
+    /// * `row_code` - The erasure code applied to each row of the grid.
+    /// * `col_code` - The erasure code applied to each column of the grid.
+    pub fn new(row_code: ErasureCode, col_code: ErasureCode) -> Self {
+        Self { row_code, col_code }
+    }
+
+    /// Returns the erasure code applied to each row of the grid.
+    pub fn row_code(&self) -> &ErasureCode {
+        &self.row_code
+    }
+
+    /// Returns the erasure code applied to each column of the grid.
+    pub fn col_code(&self) -> &ErasureCode {
+        &self.col_code
+    }
+
+    /// Returns the number of rows of the grid.
+    pub fn rows(&self) -> usize {
+        self.col_code.block_num()
+    }
+
+    /// Returns the number of columns of the grid.
+    pub fn cols(&self) -> usize {
+        self.row_code.block_num()
+    }
+
+    /// Returns the number of source data blocks in the grid.
+    pub fn source_num(&self) -> usize {
+        self.row_code.source_num() * self.col_code.source_num()
+    }
+
+    /// Returns the total number of blocks in the grid.
+    pub fn block_num(&self) -> usize {
+        self.rows() * self.cols()
+    }
+
+    /// Encodes the source data blocks of the grid into the parity blocks.
+    ///
+    /// # Arguments
+    /// * `grid` - The `rows()` x `cols()` blocks in row-major order. The source data blocks are read,
+    ///   and the parity blocks are filled with the encoded data.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::InvalidArguments` - If the grid blocks number is not equal to the block number.
+    /// * `Error::InvalidArguments` - If the blocks do not have the same length.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use erasure_isa_l::erasure::ErasureCode;
+    /// # use erasure_isa_l::product::ProductCode;
+    /// # use std::num::NonZeroUsize;
+    /// const BLOCK_LEN: usize = 1024;
+    /// let three = NonZeroUsize::new(3).unwrap();
+    /// let one = NonZeroUsize::new(1).unwrap();
+    /// let code = ProductCode::new(
+    ///     ErasureCode::with_cauchy(three, one).unwrap(),
+    ///     ErasureCode::with_cauchy(three, one).unwrap(),
+    /// );
+    /// let mut grid: Vec<Vec<u8>> = (0..code.block_num()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+    /// code.encode(&mut grid).expect("Encoding failed");
+    /// let orig = grid.clone();
+    /// // Erase the whole first row
+    /// grid[..4].iter_mut().for_each(|b| b.fill(0));
+    /// let erasures = (0..4).map(|col| (0, col)).collect::<Vec<_>>();
+    /// code.decode(&mut grid, &erasures).expect("Decoding failed");
+    /// assert_eq!(grid, orig);
+    /// ```
+    pub fn encode<U: AsMut<[u8]>>(&self, mut grid: impl AsMut<[U]>) -> Result<(), Error> {
+        let grid = grid.as_mut();
+        self.check_grid(grid)?;
+        let row_k = self.row_code.source_num();
+        let col_k = self.col_code.source_num();
+        // encode the row parity of the source rows
+        for mut row in self.split_rows(grid).into_iter().take(col_k) {
+            let (data, code) = row.split_at_mut(row_k);
+            self.row_code.encode(&*data, code)?;
+        }
+        // encode the column parity of all the columns
+        for mut col in self.split_cols(grid) {
+            let (data, code) = col.split_at_mut(col_k);
+            self.col_code.encode(&*data, code)?;
+        }
+        Ok(())
+    }
+
+    /// Decodes the erased blocks of the grid iteratively by rows and columns.
+    ///
+    /// The content of the erased blocks will be recovered and written back to the `grid`,
+    /// and the content of the non-erased blocks will not be changed.
+    ///
+    /// # Arguments
+    /// * `grid` - The `rows()` x `cols()` blocks in row-major order.
+    /// * `erasures` - The `(row, col)` positions of the erased blocks.
+    ///
+    /// # Errors
+    /// The following errors can occur:
+    /// * `Error::TooManyErasures` - If the erasure pattern cannot be recovered, the number of unrecovered
+    ///   erasures and the number of erasures that is always recoverable are reported.
+    /// * `Error::InvalidArguments` - If the erasure positions are out of range.
+    /// * `Error::InvalidArguments` - If the grid blocks number is not equal to the block number.
+    /// * `Error::InvalidArguments` - If the blocks do not have the same length.
+    /// * `Error::InternalError` - If the internal error occurs while decoding a row or a column.
+    ///
+    /// # Note
+    /// Even when an error is returned, some erased blocks may have been recovered already.
+    pub fn decode<U: AsMut<[u8]>>(
+        &self,
+        mut grid: impl AsMut<[U]>,
+        erasures: &[(usize, usize)],
+    ) -> Result<(), Error> {
+        let grid = grid.as_mut();
+        self.check_grid(grid)?;
+        let (rows, cols) = (self.rows(), self.cols());
+        let mut erased = vec![false; self.block_num()];
+        for &(row, col) in erasures {
+            if row >= rows || col >= cols {
+                return Err(Error::invalid_arguments(format!(
+                    "erasure position ({}, {}) is out of range of the {} x {} grid",
+                    row, col, rows, cols
+                )));
+            }
+            erased[row * cols + col] = true;
+        }
+
+        loop {
+            let mut progress = false;
+            // decode the rows
+            for (r, mut row) in self.split_rows(grid).into_iter().enumerate() {
+                let row_erasures = (0..cols)
+                    .filter(|c| erased[r * cols + c])
+                    .collect::<Vec<_>>();
+                if row_erasures.is_empty() || row_erasures.len() > self.row_code.code_num() {
+                    continue;
+                }
+                let (data, code) = row.split_at_mut(self.row_code.source_num());
+                self.row_code.decode(data, code, &row_erasures)?;
+                row_erasures
+                    .iter()
+                    .for_each(|c| erased[r * cols + c] = false);
+                progress = true;
+            }
+            // decode the columns
+            for (c, mut col) in self.split_cols(grid).into_iter().enumerate() {
+                let col_erasures = (0..rows)
+                    .filter(|r| erased[r * cols + c])
+                    .collect::<Vec<_>>();
+                if col_erasures.is_empty() || col_erasures.len() > self.col_code.code_num() {
+                    continue;
+                }
+                let (data, code) = col.split_at_mut(self.col_code.source_num());
+                self.col_code.decode(data, code, &col_erasures)?;
+                col_erasures
+                    .iter()
+                    .for_each(|r| erased[r * cols + c] = false);
+                progress = true;
+            }
+
+            let remaining = erased.iter().filter(|e| **e).count();
+            if remaining == 0 {
+                return Ok(());
+            }
+            if !progress {
+                let recoverable =
+                    (self.row_code.code_num() + 1) * (self.col_code.code_num() + 1) - 1;
+                return Err(Error::too_many_erasures(remaining, recoverable));
+            }
+        }
+    }
+}
+
+/// private implementation of ProductCode
+impl ProductCode {
+    fn check_grid<U: AsMut<[u8]>>(&self, grid: &mut [U]) -> Result<(), Error> {
+        if grid.len() != self.block_num() {
+            return Err(Error::invalid_arguments(format!(
+                "grid length {} is not equal to block number {} x {}",
+                grid.len(),
+                self.rows(),
+                self.cols()
+            )));
+        }
+        let len = grid[0].as_mut().len();
+        if grid.iter_mut().any(|b| b.as_mut().len() != len) {
+            return Err(Error::invalid_arguments("grid blocks must be equal"));
+        }
+        Ok(())
+    }
+
+    /// Splits the grid into rows of mutable blocks.
+    fn split_rows<'a, U: AsMut<[u8]>>(&self, grid: &'a mut [U]) -> Vec<Vec<&'a mut [u8]>> {
+        grid.chunks_mut(self.cols())
+            .map(|row| row.iter_mut().map(AsMut::as_mut).collect())
+            .collect()
+    }
+
+    /// Splits the grid into columns of mutable blocks.
+    fn split_cols<'a, U: AsMut<[u8]>>(&self, grid: &'a mut [U]) -> Vec<Vec<&'a mut [u8]>> {
+        let mut cols = (0..self.cols())
+            .map(|_| Vec::with_capacity(self.rows()))
+            .collect::<Vec<_>>();
+        for (i, block) in grid.iter_mut().enumerate() {
+            cols[i % self.cols()].push(block.as_mut());
+        }
+        cols
+    }
+}
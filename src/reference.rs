@@ -0,0 +1,105 @@
+//! This module provides a pure Rust reference implementation of the arithmetic of the erasure codes.
+//!
+//! It computes byte by byte without SIMD and without calling isa-l, so it is correct for blocks of any
+//! length but much slower. It serves as an oracle to cross-check the isa-l kernels, e.g. in the tests and
+//! fuzzing, see [`ErasureCode::encode_reference`](crate::erasure::ErasureCode::encode_reference) and
+//! [`ErasureCode::decode_reference`](crate::erasure::ErasureCode::decode_reference).
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::gf::PRIMITIVE_POLY;
+
+/// Single element GF(2^8) multiply, which gives the same result as [`gf::mul`](crate::gf::mul).
+///
+/// # Examples
+/// ```rust
+/// # use erasure_isa_l::reference;
+/// assert_eq!(reference::mul(2, 0x80), 0x1D);
+/// assert_eq!(reference::mul(7, 1), 7);
+/// ```
+pub const fn mul(a: u8, b: u8) -> u8 {
+    let (mut a, mut b) = (a as u16, b);
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        a <<= 1;
+        if a & 0x100 != 0 {
+            a ^= PRIMITIVE_POLY;
+        }
+        b >>= 1;
+    }
+    product as u8
+}
+
+/// Single element GF(2^8) inverse, which gives the same result as [`gf::inv`](crate::gf::inv).
+///
+/// The inverse of 0 is 0.
+pub const fn inv(a: u8) -> u8 {
+    // a^254 = a^-1, as the multiplicative group has the order 255
+    let mut result = 1;
+    let mut exp = 0;
+    while exp < 254 {
+        result = mul(result, a);
+        exp += 1;
+    }
+    result
+}
+
+/// Inverts an `n x n` matrix in GF(2^8) by Gauss-Jordan elimination.
+///
+/// # Returns
+/// The inverse matrix in row-major order, or `None` if the matrix is singular.
+///
+/// # Panics
+/// Panics if the length of `input` is not `n * n`.
+pub fn invert_matrix(input: &[u8], n: usize) -> Option<Vec<u8>> {
+    assert_eq!(input.len(), n * n, "input matrix must be n x n");
+    let mut input = input.to_vec();
+    let mut output = vec![0_u8; n * n];
+    (0..n).for_each(|i| output[i * n + i] = 1);
+    for i in 0..n {
+        let pivot = (i..n).find(|&r| input[r * n + i] != 0)?;
+        if pivot != i {
+            for c in 0..n {
+                input.swap(i * n + c, pivot * n + c);
+                output.swap(i * n + c, pivot * n + c);
+            }
+        }
+        let scale = inv(input[i * n + i]);
+        for c in 0..n {
+            input[i * n + c] = mul(input[i * n + c], scale);
+            output[i * n + c] = mul(output[i * n + c], scale);
+        }
+        for r in (0..n).filter(|&r| r != i) {
+            let factor = input[r * n + i];
+            if factor == 0 {
+                continue;
+            }
+            for c in 0..n {
+                input[r * n + c] ^= mul(factor, input[i * n + c]);
+                output[r * n + c] ^= mul(factor, output[i * n + c]);
+            }
+        }
+    }
+    Some(output)
+}
+
+/// Computes `dest[b] = sum(coefs[i] * sources[i][b])` for each byte `b` of `dest`.
+///
+/// # Panics
+/// Panics if the numbers of `coefs` and `sources` differ, or any source is shorter than `dest`.
+pub fn dot_prod<T: AsRef<[u8]>>(coefs: &[u8], sources: &[T], dest: &mut [u8]) {
+    assert_eq!(
+        coefs.len(),
+        sources.len(),
+        "one coefficient for each source"
+    );
+    dest.fill(0);
+    for (&coef, source) in coefs.iter().zip(sources) {
+        dest.iter_mut()
+            .zip(source.as_ref())
+            .for_each(|(d, s)| *d ^= mul(coef, *s));
+    }
+}
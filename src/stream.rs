@@ -0,0 +1,763 @@
+//! This module provides streaming adapters on top of [`ErasureCode`].
+//!
+//! The adapters implement [`std::io::Write`] and [`std::io::Read`], so that an erasure coded
+//! object can be produced from and consumed as a plain byte stream.
+use std::io::{self, Read, Write};
+
+use crate::Error;
+use crate::erasure::ErasureCode;
+
+/// The length of the header of a shard record: index, length, crc and header crc,
+/// each as a little-endian `u32`.
+const RECORD_HEADER_LEN: usize = 16;
+
+/// The default maximum length of a shard accepted by a [`ShardReader`], see [`ShardReader::max_shard_len`].
+pub const DEFAULT_MAX_SHARD_LEN: usize = 64 * 1024 * 1024;
+
+/// The index of the trailer record, which holds the original length of the stream.
+const TRAILER_INDEX: u32 = u32::MAX;
+
+/// Converts an [`Error`] into an [`io::Error`], keeping the underlying I/O error if there is one.
+fn into_io_error(err: Error) -> io::Error {
+    match err {
+        Error::Io(err) => err,
+        err => io::Error::new(io::ErrorKind::InvalidData, err),
+    }
+}
+
+/// ShardWriter is a writer that erasure codes the input stream into length-prefixed shard records.
+///
+/// The input is split into stripes of `source_num() * shard_len` bytes, and each stripe is split into
+/// `source_num()` data shards and encoded into `code_num()` parity shards.
+/// The `block_num()` shards of each stripe are written to the sink in index order, each as a record:
+///
+/// | field   | size        | description                                 |
+/// |---------|-------------|---------------------------------------------|
+/// | index   | 4 bytes     | the index of the shard in the stripe        |
+/// | len     | 4 bytes     | the length of the shard, that is `shard_len`|
+/// | crc     | 4 bytes     | the CRC-32 of the shard                     |
+/// | hcrc    | 4 bytes     | the CRC-32 of the 12 bytes above            |
+/// | payload | `len` bytes | the content of the shard                    |
+///
+/// All the integers are little-endian. The final partial stripe is padded with zeros, and the stream is
+/// terminated by a trailer record with index `u32::MAX`, whose 8-byte payload is the original length of
+/// the input stream.
+///
+/// The trailer is only written by [`finish`](Self::finish), which must be called once all the input is written.
+///
+/// The records can be read back by a [`ShardReader`].
+pub struct ShardWriter<'a, W: Write> {
+    ec: &'a ErasureCode,
+    shard_len: usize,
+    sink: W,
+    buf: Vec<u8>,
+    total_len: u64,
+}
+
+impl<'a, W: Write> ShardWriter<'a, W> {
+    /// Creates a new `ShardWriter`.
+    ///
+    /// # Arguments
+    /// * `ec` - The erasure code to encode the stripes.
+    /// * `shard_len` - The length of each shard.
+    /// * `sink` - The writer the shard records are written to.
+    ///
+    /// # Errors
+    /// * `Error::InvalidArguments` - If `shard_len` is zero or larger than `u32::MAX`.
+    pub fn new(ec: &'a ErasureCode, shard_len: usize, sink: W) -> Result<Self, Error> {
+        if shard_len == 0 || u32::try_from(shard_len).is_err() {
+            return Err(Error::invalid_arguments(format!(
+                "shard length {} is out of range 1..={}",
+                shard_len,
+                u32::MAX
+            )));
+        }
+        Ok(Self {
+            ec,
+            shard_len,
+            sink,
+            buf: Vec::with_capacity(ec.source_num() * shard_len),
+            total_len: 0,
+        })
+    }
+
+    /// Writes the final partial stripe and the trailer, and returns the underlying sink.
+    pub fn finish(mut self) -> io::Result<W> {
+        if !self.buf.is_empty() {
+            self.buf.resize(self.stripe_len(), 0);
+            self.write_stripe()?;
+        }
+        write_record(&mut self.sink, TRAILER_INDEX, &self.total_len.to_le_bytes())?;
+        self.sink.flush()?;
+        Ok(self.sink)
+    }
+
+    fn stripe_len(&self) -> usize {
+        self.ec.source_num() * self.shard_len
+    }
+
+    /// Encodes the buffered stripe and writes its shards to the sink.
+    fn write_stripe(&mut self) -> io::Result<()> {
+        debug_assert_eq!(self.buf.len(), self.stripe_len());
+        let data = self.buf.chunks(self.shard_len).collect::<Vec<_>>();
+        let parity = self.ec.encode_to_owned(&data).map_err(into_io_error)?;
+        for (i, shard) in data
+            .into_iter()
+            .chain(parity.iter().map(Vec::as_slice))
+            .enumerate()
+        {
+            write_record(&mut self.sink, i.try_into().unwrap(), shard)?;
+        }
+        self.buf.clear();
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for ShardWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.stripe_len() - self.buf.len());
+        self.buf.extend_from_slice(&buf[..len]);
+        self.total_len += len as u64;
+        if self.buf.len() == self.stripe_len() {
+            self.write_stripe()?;
+        }
+        Ok(len)
+    }
+
+    /// Flushes the underlying sink.
+    ///
+    /// The bytes of the current partial stripe stay buffered until the stripe is full or the writer is finished.
+    fn flush(&mut self) -> io::Result<()> {
+        self.sink.flush()
+    }
+}
+
+/// EncodeWriter is a writer that erasure codes the input stream stripe by stripe, and forwards the shards to a sink.
+///
+/// The input is buffered until a full stripe of `source_num() * block_len` bytes accumulates, then the stripe
+/// is split into `source_num()` data shards and encoded into `code_num()` parity shards, and the `block_num()`
+/// shards are passed to the sink closure in index order.
+///
+/// Unlike [`ShardWriter`], no framing is added, and the sink decides where the shards go.
+/// On [`flush`](Write::flush), [`finish`](Self::finish) or drop, the partial stripe buffered so far is padded
+/// with zeros and emitted, so the original length, see [`total_len`](Self::total_len), must be kept
+/// to strip the padding after reconstruction.
+///
+/// # Examples
+/// ```rust
+/// # use erasure_isa_l::erasure::ErasureCode;
+/// # use erasure_isa_l::stream::EncodeWriter;
+/// # use std::num::NonZeroUsize;
+/// let k = NonZeroUsize::new(4).unwrap();
+/// let m = NonZeroUsize::new(2).unwrap();
+/// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+/// let mut stripes = Vec::new();
+/// let mut writer = EncodeWriter::new(&ec, 64, |shards: &[&[u8]]| {
+///     stripes.push(shards.iter().map(|s| s.to_vec()).collect::<Vec<_>>());
+///     Ok(())
+/// })
+/// .unwrap();
+/// std::io::copy(&mut &[7_u8; 1000][..], &mut writer).unwrap();
+/// assert_eq!(writer.finish().unwrap(), 1000);
+/// // 1000 bytes make 3 full stripes of 256 bytes and a padded one
+/// assert_eq!(stripes.len(), 4);
+/// ```
+pub struct EncodeWriter<'a, F>
+where
+    F: FnMut(&[&[u8]]) -> io::Result<()>,
+{
+    ec: &'a ErasureCode,
+    block_len: usize,
+    sink: F,
+    buf: Vec<u8>,
+    parity: Vec<Vec<u8>>,
+    total_len: u64,
+}
+
+impl<'a, F> EncodeWriter<'a, F>
+where
+    F: FnMut(&[&[u8]]) -> io::Result<()>,
+{
+    /// Creates a new `EncodeWriter`.
+    ///
+    /// # Arguments
+    /// * `ec` - The erasure code to encode the stripes.
+    /// * `block_len` - The length of each shard.
+    /// * `sink` - The closure the `block_num()` shards of each stripe are passed to, in index order.
+    ///
+    /// # Errors
+    /// * `Error::InvalidArguments` - If `block_len` is zero.
+    pub fn new(ec: &'a ErasureCode, block_len: usize, sink: F) -> Result<Self, Error> {
+        if block_len == 0 {
+            return Err(Error::invalid_arguments("block length must be positive"));
+        }
+        Ok(Self {
+            ec,
+            block_len,
+            sink,
+            buf: Vec::with_capacity(ec.source_num() * block_len),
+            parity: vec![vec![0_u8; block_len]; ec.code_num()],
+            total_len: 0,
+        })
+    }
+
+    /// Returns the number of bytes written so far, which is the original length of the stream without padding.
+    pub fn total_len(&self) -> u64 {
+        self.total_len
+    }
+
+    /// Emits the final partial stripe, and returns the original length of the stream.
+    ///
+    /// Unlike dropping the writer, the error of emitting the final stripe is reported.
+    pub fn finish(mut self) -> io::Result<u64> {
+        self.flush()?;
+        Ok(self.total_len)
+    }
+
+    fn stripe_len(&self) -> usize {
+        self.ec.source_num() * self.block_len
+    }
+
+    /// Pads the buffered stripe, encodes it and passes its shards to the sink.
+    fn write_stripe(&mut self) -> io::Result<()> {
+        self.buf.resize(self.stripe_len(), 0);
+        let data = self.buf.chunks(self.block_len).collect::<Vec<_>>();
+        self.ec
+            .encode(&data, &mut self.parity)
+            .map_err(into_io_error)?;
+        let shards = data
+            .into_iter()
+            .chain(self.parity.iter().map(Vec::as_slice))
+            .collect::<Vec<_>>();
+        let res = (self.sink)(&shards);
+        self.buf.clear();
+        res
+    }
+}
+
+impl<F> Write for EncodeWriter<'_, F>
+where
+    F: FnMut(&[&[u8]]) -> io::Result<()>,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.stripe_len() - self.buf.len());
+        self.buf.extend_from_slice(&buf[..len]);
+        self.total_len += len as u64;
+        if self.buf.len() == self.stripe_len() {
+            self.write_stripe()?;
+        }
+        Ok(len)
+    }
+
+    /// Pads and emits the partial stripe buffered so far, if any.
+    ///
+    /// The following writes start a new stripe.
+    fn flush(&mut self) -> io::Result<()> {
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+        self.write_stripe()
+    }
+}
+
+impl<F> Drop for EncodeWriter<'_, F>
+where
+    F: FnMut(&[&[u8]]) -> io::Result<()>,
+{
+    /// Emits the final partial stripe, ignoring any error, use [`finish`](EncodeWriter::finish) to handle it.
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+/// FileStripeEncoder erasure codes a source stream stripe by stripe into `block_num()` shard files.
+///
+/// Each stripe of `source_num() * block_len` bytes read from the source is encoded like [`EncodeWriter`]
+/// does, and the shard of each index is appended to the shard writer of that index, so the stream never has
+/// to fit in memory. The final partial stripe is padded with zeros, and the original length of the stream
+/// is recorded in a sidecar as an 8-byte little-endian integer, to be read back by
+/// [`read_sidecar`].
+///
+/// The shard files can be read back by a [`DecodeReader`], with some of them missing.
+///
+/// # Examples
+/// ```rust
+/// # use erasure_isa_l::erasure::ErasureCode;
+/// # use erasure_isa_l::stream::{self, DecodeReader, FileStripeEncoder};
+/// # use std::io::Read;
+/// # use std::num::NonZeroUsize;
+/// let k = NonZeroUsize::new(4).unwrap();
+/// let m = NonZeroUsize::new(2).unwrap();
+/// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+/// let input = vec![7_u8; 1000];
+/// let mut shards = vec![Vec::new(); ec.block_num()];
+/// let mut sidecar = Vec::new();
+/// let encoder = FileStripeEncoder::new(&ec, 64, shards.iter_mut()).unwrap();
+/// encoder.encode(input.as_slice(), &mut sidecar).unwrap();
+/// let orig_len = stream::read_sidecar(sidecar.as_slice()).unwrap();
+/// // The shard 2 is lost
+/// let available = [0, 1, 3, 4, 5].map(|i| (i, shards[i].as_slice()));
+/// let mut reader = DecodeReader::new(&ec, 64, available, orig_len).unwrap();
+/// let mut output = Vec::new();
+/// reader.read_to_end(&mut output).unwrap();
+/// assert_eq!(output, input);
+/// ```
+pub struct FileStripeEncoder<'a, W: Write> {
+    ec: &'a ErasureCode,
+    block_len: usize,
+    shards: Vec<W>,
+}
+
+impl<'a, W: Write> FileStripeEncoder<'a, W> {
+    /// Creates a new `FileStripeEncoder` writing to the shard writers.
+    ///
+    /// # Arguments
+    /// * `ec` - The erasure code to encode the stripes.
+    /// * `block_len` - The length of each shard of a stripe.
+    /// * `shards` - The `block_num()` shard writers, in index order.
+    ///
+    /// # Errors
+    /// * `Error::InvalidArguments` - If `block_len` is zero.
+    /// * `Error::InvalidArguments` - If the number of shard writers is not equal to the block number.
+    pub fn new(
+        ec: &'a ErasureCode,
+        block_len: usize,
+        shards: impl IntoIterator<Item = W>,
+    ) -> Result<Self, Error> {
+        if block_len == 0 {
+            return Err(Error::invalid_arguments("block length must be positive"));
+        }
+        let shards = shards.into_iter().collect::<Vec<_>>();
+        if shards.len() != ec.block_num() {
+            return Err(Error::invalid_arguments(format!(
+                "shard writers number {} is not equal to block number {}",
+                shards.len(),
+                ec.block_num()
+            )));
+        }
+        Ok(Self {
+            ec,
+            block_len,
+            shards,
+        })
+    }
+
+    /// Reads the source to the end, encodes it into the shard writers, and records its length in the sidecar.
+    ///
+    /// # Returns
+    /// The original length of the source stream, the same as recorded in the sidecar.
+    ///
+    /// # Errors
+    /// Any error of reading the source, or writing the shards or the sidecar.
+    pub fn encode(mut self, mut source: impl Read, mut sidecar: impl Write) -> io::Result<u64> {
+        let shards = &mut self.shards;
+        let mut writer = EncodeWriter::new(self.ec, self.block_len, |stripe: &[&[u8]]| {
+            shards
+                .iter_mut()
+                .zip(stripe)
+                .try_for_each(|(w, shard)| w.write_all(shard))
+        })
+        .map_err(into_io_error)?;
+        io::copy(&mut source, &mut writer)?;
+        let total_len = writer.finish()?;
+        self.shards.iter_mut().try_for_each(Write::flush)?;
+        sidecar.write_all(&total_len.to_le_bytes())?;
+        sidecar.flush()?;
+        Ok(total_len)
+    }
+}
+
+impl<'a> FileStripeEncoder<'a, io::BufWriter<std::fs::File>> {
+    /// Creates a new `FileStripeEncoder` writing to the shard files at `paths`, which are created or truncated.
+    ///
+    /// # Arguments
+    /// * `ec` - The erasure code to encode the stripes.
+    /// * `block_len` - The length of each shard of a stripe.
+    /// * `paths` - The `block_num()` paths of the shard files, in index order.
+    ///
+    /// # Errors
+    /// * `Error::InvalidArguments` - If `block_len` is zero.
+    /// * `Error::InvalidArguments` - If the number of paths is not equal to the block number.
+    /// * `Error::Io` - If any shard file cannot be created.
+    pub fn create(
+        ec: &'a ErasureCode,
+        block_len: usize,
+        paths: impl IntoIterator<Item = impl AsRef<std::path::Path>>,
+    ) -> Result<Self, Error> {
+        let shards = paths
+            .into_iter()
+            .map(|path| std::fs::File::create(path).map(io::BufWriter::new))
+            .collect::<io::Result<Vec<_>>>()?;
+        Self::new(ec, block_len, shards)
+    }
+}
+
+/// Reads the original length of the stream recorded in a sidecar by [`FileStripeEncoder::encode`].
+pub fn read_sidecar(mut sidecar: impl Read) -> io::Result<u64> {
+    let mut len = [0_u8; 8];
+    sidecar.read_exact(&mut len)?;
+    Ok(u64::from_le_bytes(len))
+}
+
+/// DecodeReader is a reader that reconstructs the original stream from the shard streams of an [`EncodeWriter`].
+///
+/// Each shard stream holds the shards of one index of all the stripes back to back, and some of them may be
+/// unavailable. For each stripe, a shard of `block_len` bytes is read from each available stream, the missing
+/// source data shards are reconstructed by [`decode`](ErasureCode::decode), and the source data is exposed
+/// through [`Read`], truncated to the original length.
+///
+/// The parity streams are only read when a source data stream is unavailable.
+/// Reading fails with [`io::ErrorKind::InvalidData`] if a stripe cannot be decoded, with the [`Error`] as
+/// the source, and with [`io::ErrorKind::UnexpectedEof`] if a shard stream ends early.
+///
+/// # Examples
+/// ```rust
+/// # use erasure_isa_l::erasure::ErasureCode;
+/// # use erasure_isa_l::stream::{DecodeReader, EncodeWriter};
+/// # use std::io::{Read, Write};
+/// # use std::num::NonZeroUsize;
+/// let k = NonZeroUsize::new(4).unwrap();
+/// let m = NonZeroUsize::new(2).unwrap();
+/// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+/// let input = vec![7_u8; 1000];
+/// let mut streams = vec![Vec::new(); ec.block_num()];
+/// let mut writer = EncodeWriter::new(&ec, 64, |shards: &[&[u8]]| {
+///     streams.iter_mut().zip(shards).for_each(|(s, shard)| s.extend_from_slice(shard));
+///     Ok(())
+/// })
+/// .unwrap();
+/// writer.write_all(&input).unwrap();
+/// let orig_len = writer.finish().unwrap();
+/// // The streams of the shard 0 and 5 are lost
+/// let available = [1, 2, 3, 4].map(|i| (i, streams[i].as_slice()));
+/// let mut reader = DecodeReader::new(&ec, 64, available, orig_len).unwrap();
+/// let mut output = Vec::new();
+/// reader.read_to_end(&mut output).unwrap();
+/// assert_eq!(output, input);
+/// ```
+pub struct DecodeReader<'a, R: Read> {
+    ec: &'a ErasureCode,
+    block_len: usize,
+    /// The shard streams by index, `None` if unavailable.
+    sources: Vec<Option<R>>,
+    /// The sorted indices of the unavailable shard streams.
+    erasures: Vec<usize>,
+    shards: Vec<Vec<u8>>,
+    out: Vec<u8>,
+    pos: usize,
+    remaining: u64,
+}
+
+impl<'a, R: Read> DecodeReader<'a, R> {
+    /// Creates a new `DecodeReader`.
+    ///
+    /// # Arguments
+    /// * `ec` - The erasure code which the shards are encoded with.
+    /// * `block_len` - The length of each shard.
+    /// * `sources` - The available shard streams as pairs of `(index, reader)`, the index is in range `0..block_num()`.
+    /// * `orig_len` - The original length of the stream, see [`EncodeWriter::total_len`].
+    ///
+    /// # Errors
+    /// * `Error::InvalidArguments` - If `block_len` is zero.
+    /// * `Error::InvalidArguments` - If any index is out of range or duplicated.
+    /// * `Error::TooManyErasures` - If less than `source_num()` shard streams are available.
+    pub fn new(
+        ec: &'a ErasureCode,
+        block_len: usize,
+        sources: impl IntoIterator<Item = (usize, R)>,
+        orig_len: u64,
+    ) -> Result<Self, Error> {
+        if block_len == 0 {
+            return Err(Error::invalid_arguments("block length must be positive"));
+        }
+        let mut slots = (0..ec.block_num()).map(|_| None).collect::<Vec<_>>();
+        for (index, source) in sources {
+            match slots.get_mut(index) {
+                None => {
+                    return Err(Error::invalid_arguments(format!(
+                        "shard index {} is out of range, max index is block number {}",
+                        index,
+                        ec.block_num()
+                    )));
+                }
+                Some(Some(_)) => {
+                    return Err(Error::invalid_arguments(format!(
+                        "shard index {} is duplicated",
+                        index
+                    )));
+                }
+                Some(slot) => *slot = Some(source),
+            }
+        }
+        let erasures = (0..ec.block_num())
+            .filter(|i| slots[*i].is_none())
+            .collect::<Vec<_>>();
+        if erasures.len() > ec.code_num() {
+            return Err(Error::too_many_erasures(erasures.len(), ec.code_num()));
+        }
+        Ok(Self {
+            ec,
+            block_len,
+            sources: slots,
+            erasures,
+            shards: vec![vec![0_u8; block_len]; ec.block_num()],
+            out: Vec::new(),
+            pos: 0,
+            remaining: orig_len,
+        })
+    }
+
+    /// Reads the shards of the next stripe, and makes its source data available in `out`.
+    fn fill(&mut self) -> io::Result<()> {
+        let k = self.ec.source_num();
+        // the parity is only needed to reconstruct the missing source data
+        let need_decode = self.erasures.first().is_some_and(|e| *e < k);
+        let read_num = if need_decode { self.ec.block_num() } else { k };
+        for (source, shard) in self.sources.iter_mut().zip(&mut self.shards).take(read_num) {
+            if let Some(source) = source {
+                source.read_exact(shard)?;
+            }
+        }
+        if need_decode {
+            let (data, code) = self.shards.split_at_mut(k);
+            self.ec
+                .decode(data, code, &self.erasures)
+                .map_err(into_io_error)?;
+        }
+        let stripe_len =
+            (k * self.block_len).min(usize::try_from(self.remaining).unwrap_or(usize::MAX));
+        self.out.clear();
+        for shard in &self.shards[..k] {
+            let len = shard.len().min(stripe_len - self.out.len());
+            self.out.extend_from_slice(&shard[..len]);
+        }
+        self.remaining -= self.out.len() as u64;
+        self.pos = 0;
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for DecodeReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos == self.out.len() {
+            if self.remaining == 0 {
+                return Ok(0);
+            }
+            self.fill()?;
+        }
+        let len = buf.len().min(self.out.len() - self.pos);
+        buf[..len].copy_from_slice(&self.out[self.pos..self.pos + len]);
+        self.pos += len;
+        Ok(len)
+    }
+}
+
+fn write_record(sink: &mut impl Write, index: u32, payload: &[u8]) -> io::Result<()> {
+    let mut header = [0_u8; RECORD_HEADER_LEN];
+    header[0..4].copy_from_slice(&index.to_le_bytes());
+    header[4..8].copy_from_slice(&u32::try_from(payload.len()).unwrap().to_le_bytes());
+    header[8..12].copy_from_slice(&crc32fast::hash(payload).to_le_bytes());
+    let header_crc = crc32fast::hash(&header[..12]);
+    header[12..16].copy_from_slice(&header_crc.to_le_bytes());
+    sink.write_all(&header)?;
+    sink.write_all(payload)
+}
+
+/// The header of a shard record.
+struct RecordHeader {
+    index: u32,
+    len: usize,
+    crc: u32,
+}
+
+/// Reads the header of a shard record, `None` if the header itself is corrupted.
+fn read_record_header(source: &mut impl Read) -> io::Result<Option<RecordHeader>> {
+    let mut header = [0_u8; RECORD_HEADER_LEN];
+    source.read_exact(&mut header)?;
+    let field = |i: usize| u32::from_le_bytes(header[i..i + 4].try_into().unwrap());
+    if crc32fast::hash(&header[..12]) != field(12) {
+        return Ok(None);
+    }
+    Ok(Some(RecordHeader {
+        index: field(0),
+        len: field(4) as usize,
+        crc: field(8),
+    }))
+}
+
+/// ShardReader is a reader that reconstructs the original stream from the shard records
+/// written by a [`ShardWriter`].
+///
+/// The CRCs of the header and the payload of each shard record are verified, and the shards that fail
+/// the verification are treated as erasures and reconstructed from the other shards of the stripe.
+/// Up to `code_num()` corrupted shards per stripe can be tolerated. A shard with a corrupted header is
+/// assumed to have the length of the other shards, so the shard length must have been learned from a
+/// valid header before it, and a corrupted trailer header fails the stream.
+///
+/// Reading fails with [`io::ErrorKind::InvalidData`] if a stripe cannot be reconstructed, the records are
+/// malformed or a shard is longer than [`max_shard_len`](Self::max_shard_len), and with
+/// [`io::ErrorKind::UnexpectedEof`] if the stream ends before the trailer.
+pub struct ShardReader<'a, R: Read> {
+    ec: &'a ErasureCode,
+    source: R,
+    max_shard_len: usize,
+    /// The length of the shards, learned from the first valid shard header.
+    shard_len: Option<usize>,
+    /// The decoded data of the latest stripe, which is held back until it is known whether it is the last one.
+    pending: Option<Vec<u8>>,
+    out: Vec<u8>,
+    pos: usize,
+    emitted: u64,
+    finished: bool,
+}
+
+impl<'a, R: Read> ShardReader<'a, R> {
+    /// Creates a new `ShardReader`.
+    ///
+    /// # Arguments
+    /// * `ec` - The erasure code which the shard records are encoded with.
+    /// * `source` - The reader the shard records are read from.
+    pub fn new(ec: &'a ErasureCode, source: R) -> Self {
+        Self {
+            ec,
+            source,
+            max_shard_len: DEFAULT_MAX_SHARD_LEN,
+            shard_len: None,
+            pending: None,
+            out: Vec::new(),
+            pos: 0,
+            emitted: 0,
+            finished: false,
+        }
+    }
+
+    /// Sets the maximum length of a shard, [`DEFAULT_MAX_SHARD_LEN`] by default.
+    ///
+    /// The shards are allocated by the length in their headers, so a longer shard fails the stream
+    /// instead of being allocated.
+    pub fn max_shard_len(mut self, max_shard_len: usize) -> Self {
+        self.max_shard_len = max_shard_len;
+        self
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.source
+    }
+
+    /// Reads the next stripe or the trailer, and makes the bytes ready to be read available in `out`.
+    fn fill(&mut self) -> io::Result<()> {
+        let header = read_record_header(&mut self.source)?;
+        if let Some(header) = header.as_ref()
+            && header.index == TRAILER_INDEX
+        {
+            return self.read_trailer(header);
+        }
+        let data = self.read_stripe(header)?;
+        if let Some(prev) = self.pending.replace(data) {
+            self.emitted += prev.len() as u64;
+            self.out = prev;
+            self.pos = 0;
+        }
+        Ok(())
+    }
+
+    fn read_trailer(&mut self, header: &RecordHeader) -> io::Result<()> {
+        let mut payload = [0_u8; 8];
+        if header.len != payload.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "malformed trailer record",
+            ));
+        }
+        self.source.read_exact(&mut payload)?;
+        if crc32fast::hash(&payload) != header.crc {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "corrupted trailer record",
+            ));
+        }
+        let total_len = u64::from_le_bytes(payload);
+        let mut last = self.pending.take().unwrap_or_default();
+        let last_len = total_len
+            .checked_sub(self.emitted)
+            .and_then(|len| usize::try_from(len).ok())
+            .filter(|len| *len <= last.len())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "original length {} is inconsistent with the stripes",
+                        total_len
+                    ),
+                )
+            })?;
+        last.truncate(last_len);
+        self.emitted += last_len as u64;
+        self.out = last;
+        self.pos = 0;
+        self.finished = true;
+        Ok(())
+    }
+
+    /// Reads the shards of a stripe whose first record header is `first`, and decodes the source data.
+    fn read_stripe(&mut self, first: Option<RecordHeader>) -> io::Result<Vec<u8>> {
+        let mut shards = Vec::with_capacity(self.ec.block_num());
+        let mut erasures = Vec::new();
+        let mut first = Some(first);
+        for i in 0..self.ec.block_num() {
+            let header = match first.take() {
+                Some(header) => header,
+                None => read_record_header(&mut self.source)?,
+            };
+            let shard_len = match &header {
+                Some(header)
+                    if header.index == TRAILER_INDEX
+                        || header.len > self.max_shard_len
+                        || self.shard_len.is_some_and(|len| len != header.len) =>
+                {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("malformed shard record {} in the stripe", i),
+                    ));
+                }
+                Some(header) => header.len,
+                None => self.shard_len.ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("corrupted header of shard record {} of unknown length", i),
+                    )
+                })?,
+            };
+            self.shard_len = Some(shard_len);
+            let mut shard = vec![0_u8; shard_len];
+            self.source.read_exact(&mut shard)?;
+            if header.is_none_or(|h| h.index as usize != i || crc32fast::hash(&shard) != h.crc) {
+                // treat the corrupted shard as an erasure
+                shard.fill(0);
+                erasures.push(i);
+            }
+            shards.push(shard);
+        }
+        let (data, code) = shards.split_at_mut(self.ec.source_num());
+        self.ec
+            .decode(&mut *data, code, erasures)
+            .map_err(into_io_error)?;
+        Ok(data.concat())
+    }
+}
+
+impl<R: Read> Read for ShardReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.out.len() {
+            if self.finished {
+                return Ok(0);
+            }
+            self.fill()?;
+        }
+        let len = buf.len().min(self.out.len() - self.pos);
+        buf[..len].copy_from_slice(&self.out[self.pos..self.pos + len]);
+        self.pos += len;
+        Ok(len)
+    }
+}
@@ -0,0 +1,69 @@
+//! This module provides helpers to split a byte stream into source data blocks and join them back.
+//!
+//! An [`ErasureCode`](crate::erasure::ErasureCode) works on equal-length blocks, while the input is
+//! usually arbitrary bytes, such as the content of a file. The helpers pad the input to a multiple
+//! of the source number, and strip the padding after reconstruction.
+use alloc::vec::Vec;
+
+/// Stripe groups the helpers to convert between a byte stream and the source data blocks of a stripe.
+///
+/// # Examples
+/// ```rust
+/// # use erasure_isa_l::erasure::ErasureCode;
+/// # use erasure_isa_l::stripe::Stripe;
+/// # use std::num::NonZeroUsize;
+/// let k = NonZeroUsize::new(4).unwrap();
+/// let m = NonZeroUsize::new(2).unwrap();
+/// let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+/// let input = b"an arbitrary length of bytes".to_vec();
+/// let (mut data, orig_len) = Stripe::split(&input, k.get());
+/// let mut parity = ec.encode_to_owned(&data).expect("Encoding failed");
+/// // Lose the first block and recover it
+/// data[0].fill(0);
+/// ec.decode(&mut data, &mut parity, [0]).expect("Decoding failed");
+/// assert_eq!(Stripe::join(&data, orig_len), input);
+/// ```
+pub struct Stripe;
+
+impl Stripe {
+    /// Splits `data` into `k` equal-length shards, padding the last ones with zeros.
+    ///
+    /// # Returns
+    /// The `k` shards of `data.len().div_ceil(k)` bytes each, and the original length of `data`,
+    /// which is needed by [`join`](Self::join) to strip the padding.
+    ///
+    /// # Panics
+    /// Panics if `k` is zero.
+    pub fn split(data: &[u8], k: usize) -> (Vec<Vec<u8>>, usize) {
+        assert!(k > 0, "the number of shards must be positive");
+        let shard_len = data.len().div_ceil(k);
+        let shards = (0..k)
+            .map(|i| {
+                let start = (i * shard_len).min(data.len());
+                let end = ((i + 1) * shard_len).min(data.len());
+                let mut shard = Vec::with_capacity(shard_len);
+                shard.extend_from_slice(&data[start..end]);
+                shard.resize(shard_len, 0);
+                shard
+            })
+            .collect();
+        (shards, data.len())
+    }
+
+    /// Concatenates the `shards` and truncates the result to `orig_len` bytes, reversing [`split`](Self::split).
+    ///
+    /// Only the source data blocks are expected, any bytes beyond `orig_len` are dropped.
+    /// If the shards hold less than `orig_len` bytes, all of them are returned.
+    pub fn join<T: AsRef<[u8]>>(shards: impl AsRef<[T]>, orig_len: usize) -> Vec<u8> {
+        let mut data = Vec::with_capacity(orig_len);
+        for shard in shards.as_ref() {
+            let remaining = orig_len - data.len();
+            if remaining == 0 {
+                break;
+            }
+            let shard = shard.as_ref();
+            data.extend_from_slice(&shard[..shard.len().min(remaining)]);
+        }
+        data
+    }
+}
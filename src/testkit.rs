@@ -0,0 +1,86 @@
+//! This module provides conformance checks of an [`ErasureCode`] for the downstream tests.
+//!
+//! The checks exercise a codec end to end with random inputs, so that a codec built from a custom
+//! matrix by [`ErasureCode::with_matrix`] can be checked the same way as the built-in ones.
+//! It is enabled by the `testkit` feature.
+use alloc::format;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use rand::{Rng, RngExt};
+
+use crate::Error;
+use crate::erasure::ErasureCode;
+
+/// Runs a randomized encode, erase and decode cycle on `ec`.
+///
+/// Random source blocks of `block_len` bytes are encoded, and the code blocks are cross-checked with
+/// [`ErasureCode::encode_reference`]. Then a random number of distinct blocks, from 1 up to `code_num()`,
+/// are erased and decoded, and the recovered blocks are compared with the original ones.
+///
+/// # Errors
+/// The following errors can occur:
+/// * `Error::Other` - If the code blocks differ from the reference, or a recovered block differs from the original.
+/// * Any error of [`encode`](ErasureCode::encode) or [`decode`](ErasureCode::decode), such as
+///   `Error::Singular` if the matrix of the codec is not invertible for the erasures.
+///
+/// # Examples
+/// ```rust
+/// # use erasure_isa_l::erasure::ErasureCode;
+/// # use erasure_isa_l::testkit;
+/// # use std::num::NonZeroUsize;
+/// let k = NonZeroUsize::new(2).unwrap();
+/// let m = NonZeroUsize::new(1).unwrap();
+/// let ec = ErasureCode::with_matrix(k, m, &[1, 0, 0, 1, 1, 1]).unwrap();
+/// let mut rng = rand::rng();
+/// for _ in 0..16 {
+///     testkit::round_trip(&ec, 1024, &mut rng).expect("round trip failed");
+/// }
+/// ```
+pub fn round_trip<R: Rng + ?Sized>(
+    ec: &ErasureCode,
+    block_len: usize,
+    rng: &mut R,
+) -> Result<(), Error> {
+    let (k, m, n) = (ec.source_num(), ec.code_num(), ec.block_num());
+    let data = (0..k)
+        .map(|_| {
+            let mut block = vec![0_u8; block_len];
+            rng.fill_bytes(&mut block);
+            block
+        })
+        .collect::<Vec<_>>();
+    let mut parity = vec![vec![0_u8; block_len]; m];
+    ec.encode(&data, &mut parity)?;
+    let mut reference = vec![vec![0_u8; block_len]; m];
+    ec.encode_reference(&data, &mut reference)?;
+    if let Some(p) = (0..m).find(|&p| parity[p] != reference[p]) {
+        return Err(Error::other(format!(
+            "code block {} differs from the reference",
+            k + p
+        )));
+    }
+
+    // erase distinct blocks by a partial shuffle of the indices
+    let num_erasures = rng.random_range(1..=m);
+    let mut indices = (0..n).collect::<Vec<_>>();
+    for i in 0..num_erasures {
+        let j = rng.random_range(i..n);
+        indices.swap(i, j);
+    }
+    let erasures = &indices[..num_erasures];
+    let mut blocks = data.iter().chain(&parity).cloned().collect::<Vec<_>>();
+    erasures.iter().for_each(|&e| blocks[e].fill(0));
+    let (erased_data, erased_parity) = blocks.split_at_mut(k);
+    ec.decode(erased_data, erased_parity, erasures)?;
+    if let Some(&e) = erasures
+        .iter()
+        .find(|&&e| blocks[e] != if e < k { &data[e] } else { &parity[e - k] }[..])
+    {
+        return Err(Error::other(format!(
+            "recovered block {} differs from the original, erasures {:?}",
+            e, erasures
+        )));
+    }
+    Ok(())
+}
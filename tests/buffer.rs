@@ -0,0 +1,43 @@
+use std::num::NonZeroUsize;
+
+use erasure_isa_l::buffer::{ALIGNMENT, AlignedBuf, aligned_block};
+use erasure_isa_l::erasure::ErasureCode;
+
+#[test]
+fn aligned_block_test() {
+    for len in [0, 1, ALIGNMENT - 1, ALIGNMENT, ALIGNMENT + 1, 4096] {
+        let mut buf = aligned_block(len);
+        assert_eq!(buf.len(), len);
+        assert!(buf.iter().all(|b| *b == 0));
+        assert_eq!(buf.as_ptr() as usize % ALIGNMENT, 0);
+        buf.fill(0xAB);
+        let cloned = buf.clone();
+        assert_eq!(cloned, buf);
+        assert_eq!(cloned.as_ptr() as usize % ALIGNMENT, 0);
+        assert_eq!(AlignedBuf::from(&vec![0xAB; len][..]), buf);
+    }
+}
+
+#[test]
+fn aligned_encode_decode() {
+    const BLOCK_LEN: usize = 1000;
+    let k = NonZeroUsize::new(4).unwrap();
+    let m = NonZeroUsize::new(2).unwrap();
+    let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    let data = (0..k.get())
+        .map(|i| AlignedBuf::from(&[i as u8 + 1; BLOCK_LEN][..]))
+        .collect::<Vec<_>>();
+    let mut parity = (0..m.get())
+        .map(|_| aligned_block(BLOCK_LEN))
+        .collect::<Vec<_>>();
+    ec.encode(&data, &mut parity).expect("Encoding failed");
+
+    let mut erased_data = data.clone();
+    erased_data[1].fill(0);
+    let mut erased_parity = parity.clone();
+    erased_parity[0].fill(0);
+    ec.decode(&mut erased_data, &mut erased_parity, [1, 4])
+        .expect("Decoding failed");
+    assert_eq!(erased_data, data);
+    assert_eq!(erased_parity, parity);
+}
@@ -0,0 +1,118 @@
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+
+use erasure_isa_l::cache::{LruDecodeCache, SharedDecodeCache};
+use erasure_isa_l::erasure::ErasureCode;
+
+const BLOCK_LEN: usize = 64;
+const K: usize = 4;
+const M: usize = 2;
+const THREADS: usize = 8;
+const ROUNDS: usize = 50;
+
+fn make_ec() -> ErasureCode {
+    ErasureCode::with_cauchy(NonZeroUsize::new(K).unwrap(), NonZeroUsize::new(M).unwrap()).unwrap()
+}
+
+#[test]
+fn shared_decode_cache() {
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_cauchy(k, m).unwrap();
+    let data = (0..K)
+        .map(|_| rand::random_iter().take(BLOCK_LEN).collect::<Vec<u8>>())
+        .collect::<Vec<_>>();
+    let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+
+    // overlapping erasure sets among the threads, some of them in different orders
+    let patterns: Vec<Vec<usize>> = vec![vec![0], vec![1, 4], vec![4, 1], vec![2, 5], vec![3]];
+    let cache = SharedDecodeCache::new();
+    std::thread::scope(|s| {
+        for t in 0..THREADS {
+            let (ec, cache, data, parity, patterns) = (&ec, &cache, &data, &parity, &patterns);
+            s.spawn(move || {
+                for r in 0..ROUNDS {
+                    let erasures = &patterns[(t + r) % patterns.len()];
+                    let mut erased_data = data.clone();
+                    let mut erased_parity = parity.clone();
+                    for &e in erasures {
+                        match e.checked_sub(K) {
+                            Some(p) => erased_parity[p].fill(0),
+                            None => erased_data[e].fill(0),
+                        }
+                    }
+                    cache
+                        .decode(ec, &mut erased_data, &mut erased_parity, erasures.clone())
+                        .expect("Decoding failed");
+                    assert_eq!(&erased_data, data);
+                    assert_eq!(&erased_parity, parity);
+                }
+            });
+        }
+    });
+    // the same erasure set in different orders shares a table
+    assert_eq!(cache.len(), 4);
+
+    // failures are not cached
+    let res = cache.get_or_make(&ec, vec![0, 1, 2]);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::TooManyErasures(..))
+    ));
+    assert_eq!(cache.len(), 4);
+
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn lru_decode_cache() {
+    let ec = make_ec();
+    let mut cache = LruDecodeCache::new(NonZeroUsize::new(2).unwrap());
+    let first = cache.get_or_make(&ec, [1, 0]).unwrap();
+    // the erasures are sorted and deduplicated as the key
+    assert!(Arc::ptr_eq(
+        &first,
+        &cache.get_or_make(&ec, [0, 1, 1]).unwrap()
+    ));
+    cache.get_or_make(&ec, [2]).unwrap();
+    cache.get_or_make(&ec, [0, 1]).unwrap();
+    cache.get_or_make(&ec, [3]).unwrap();
+    assert_eq!(cache.len(), 2);
+    assert!(cache.contains([0, 1]));
+    assert!(!cache.contains([2]));
+    assert!(cache.get_or_make(&ec, [K + M]).is_err());
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn codec_cache() {
+    let mut ec = make_ec();
+    assert_eq!(ec.cache_capacity(), 0);
+    ec.set_cache_capacity(4);
+    assert_eq!(ec.cache_capacity(), 4);
+    let data = (0..K).map(|i| vec![i as u8; BLOCK_LEN]).collect::<Vec<_>>();
+    let parity = ec.encode_to_owned(&data).unwrap();
+    for erasures in [vec![0], vec![K], vec![0], vec![1, K + 1], vec![K + 1, 1]] {
+        let stripe = data.iter().chain(&parity).collect::<Vec<_>>();
+        for (i, block) in ec.decode_to_owned(&data, &parity, &erasures).unwrap() {
+            assert_eq!(&block, stripe[i]);
+        }
+        let mut erased_data = data.clone();
+        let mut erased_parity = parity.clone();
+        erasures.iter().for_each(|&e| {
+            if e < K {
+                erased_data[e].fill(0);
+            } else {
+                erased_parity[e - K].fill(0);
+            }
+        });
+        ec.decode(&mut erased_data, &mut erased_parity, &erasures)
+            .unwrap();
+        assert_eq!(erased_data, data);
+        assert_eq!(erased_parity, parity);
+    }
+    ec.set_cache_capacity(0);
+    assert_eq!(ec.cache_capacity(), 0);
+}
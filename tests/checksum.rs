@@ -0,0 +1,85 @@
+use std::num::NonZeroUsize;
+
+use erasure_isa_l::Error;
+use erasure_isa_l::checksum::ChecksumedCode;
+use erasure_isa_l::erasure::ErasureCode;
+
+const BLOCK_LEN: usize = 64;
+const K: usize = 4;
+const M: usize = 2;
+
+fn make_code() -> ChecksumedCode {
+    let ec = ErasureCode::with_reed_solomon(
+        NonZeroUsize::new(K).unwrap(),
+        NonZeroUsize::new(M).unwrap(),
+    )
+    .unwrap();
+    ChecksumedCode::new(ec)
+}
+
+fn make_blocks(code: &ChecksumedCode) -> (Vec<Vec<u8>>, Vec<Vec<u8>>, Vec<u32>) {
+    let data = (0..K)
+        .map(|_| (0..BLOCK_LEN).map(|_| rand::random()).collect())
+        .collect::<Vec<Vec<u8>>>();
+    let mut parity = vec![vec![0_u8; BLOCK_LEN]; M];
+    let checksums = code.encode_with_checksums(&data, &mut parity).unwrap();
+    (data, parity, checksums)
+}
+
+#[test]
+fn encode_with_checksums() {
+    let code = make_code();
+    let (data, parity, checksums) = make_blocks(&code);
+    assert_eq!(checksums.len(), K + M);
+    assert_eq!(parity, code.codec().encode_to_owned(&data).unwrap());
+    for (block, checksum) in data.iter().chain(parity.iter()).zip(&checksums) {
+        assert_eq!(ChecksumedCode::checksum(block), *checksum);
+    }
+    assert!(code.verify(&data, &parity, &checksums).unwrap().is_empty());
+}
+
+#[test]
+fn verify() {
+    let code = make_code();
+    let (mut data, mut parity, checksums) = make_blocks(&code);
+    data[2][0] ^= 0x01;
+    parity[1][BLOCK_LEN - 1] ^= 0x10;
+    assert_eq!(
+        code.verify(&data, &parity, &checksums).unwrap(),
+        vec![2, K + 1]
+    );
+    let res = code.verify(&data, &parity, &checksums[1..]);
+    assert_eq!(res, Err(Error::InvalidArguments(String::new())));
+    let res = code.verify(&data[1..], &parity, &checksums);
+    assert_eq!(res, Err(Error::InvalidArguments(String::new())));
+}
+
+#[test]
+fn decode() {
+    let code = make_code();
+    let (data, parity, checksums) = make_blocks(&code);
+
+    // intact blocks are left as is
+    let (mut d, mut p) = (data.clone(), parity.clone());
+    assert!(code.decode(&mut d, &mut p, &checksums).unwrap().is_empty());
+    assert_eq!((&d, &p), (&data, &parity));
+
+    // up to `M` corrupted blocks are corrected
+    for corrupted in [[0, 1], [1, K], [K, K + 1]] {
+        let (mut d, mut p) = (data.clone(), parity.clone());
+        for i in corrupted {
+            let block = if i < K { &mut d[i] } else { &mut p[i - K] };
+            block[i] ^= 0xff;
+        }
+        assert_eq!(code.decode(&mut d, &mut p, &checksums).unwrap(), corrupted);
+        assert_eq!((&d, &p), (&data, &parity));
+    }
+
+    // more corrupted blocks than `M` are not correctable
+    let (mut d, mut p) = (data.clone(), parity.clone());
+    d.iter_mut().take(M + 1).for_each(|b| b[0] ^= 0xff);
+    assert_eq!(
+        code.decode(&mut d, &mut p, &checksums),
+        Err(Error::TooManyErasures(M + 1, M))
+    );
+}
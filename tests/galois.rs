@@ -48,3 +48,165 @@ fn mad() {
     erasure_isa_l::galois::dot_prod(&table, &source_update, &mut expected).unwrap();
     assert_eq!(dest, expected);
 }
+
+#[test]
+fn vect_dot_prod_multi() {
+    use erasure_isa_l::{ec, gf};
+    const LEN: usize = 64;
+    let source = [
+        (0..).take(LEN).collect::<Vec<u8>>(),
+        (32..).take(LEN).collect::<Vec<u8>>(),
+    ];
+    let coefs = [[3_u8, 4], [5, 6], [0, 1]];
+    let tables = coefs.map(|c| {
+        let mut table = vec![0_u8; 32 * 2];
+        ec::init_tables(2, 1, &c, &mut table);
+        table
+    });
+    let tables = tables.iter().map(Vec::as_slice).collect::<Vec<_>>();
+    let mut dest = [[0_u8; LEN]; 3];
+    let src = source.iter().map(|s| s.as_ptr()).collect::<Vec<_>>();
+    let mut dest_ptrs = dest.iter_mut().map(|d| d.as_mut_ptr()).collect::<Vec<_>>();
+    gf::vect_dot_prod_multi(LEN as i32, 2, &tables, &src, &mut dest_ptrs);
+    for (c, d) in coefs.iter().zip(&dest) {
+        let expected = source[0]
+            .iter()
+            .zip(&source[1])
+            .map(|(a, b)| gf::mul(*a, c[0]) ^ gf::mul(*b, c[1]))
+            .collect::<Vec<u8>>();
+        assert_eq!(d.as_slice(), expected);
+    }
+}
+
+#[test]
+fn vect_mul() {
+    use erasure_isa_l::gf;
+    const LEN: usize = 64;
+    let source = (0..).take(LEN).collect::<Vec<u8>>();
+    let mut table = [0_u8; 32];
+    gf::vect_mul_init(0x1D, &mut table);
+    let mut dest = vec![0_u8; LEN];
+    assert!(gf::vect_mul(LEN as i32, &table, &source, &mut dest));
+    let expected = source
+        .iter()
+        .map(|a| gf::mul(*a, 0x1D))
+        .collect::<Vec<u8>>();
+    assert_eq!(dest, expected);
+    // the length must be aligned to 32 bytes
+    assert!(!gf::vect_mul(LEN as i32 - 1, &table, &source, &mut dest));
+    // and must not be negative
+    assert!(!gf::vect_mul(-32, &table, &source, &mut dest));
+    assert_eq!(dest, expected);
+}
+
+#[test]
+fn xor() {
+    use erasure_isa_l::gf;
+    let a = (0..).take(64).collect::<Vec<u8>>();
+    let b = (100..).take(64).collect::<Vec<u8>>();
+    let mut dest = vec![0_u8; 64];
+    gf::xor(&a, &b, &mut dest).unwrap();
+    assert!(
+        dest.iter()
+            .zip(a.iter().zip(&b))
+            .all(|(d, (a, b))| *d == a ^ b)
+    );
+    // xor back into the original
+    gf::xor_into(&mut dest, &b).unwrap();
+    assert_eq!(dest, a);
+
+    assert!(gf::xor(&a, &b[1..], &mut dest).is_err());
+    assert!(gf::xor(&a, &b, &mut dest[1..]).is_err());
+    assert!(gf::xor_into(&mut dest, &b[1..]).is_err());
+}
+
+#[test]
+fn mat_mul() {
+    use erasure_isa_l::gf;
+    let a = [1, 2, 3, 4, 5, 6];
+    // the identity is neutral
+    let mut out = [0_u8; 6];
+    gf::mat_mul(&a, 2, 3, &[1, 0, 0, 0, 1, 0, 0, 0, 1], 3, &mut out).unwrap();
+    assert_eq!(out, a);
+    // the product agrees with the naive sum of the products
+    let b = [7, 8, 9, 10, 11, 12];
+    let mut out = [0_u8; 4];
+    gf::mat_mul(&a, 2, 3, &b, 2, &mut out).unwrap();
+    for i in 0..2 {
+        for j in 0..2 {
+            let expected = (0..3).fold(0, |acc, l| acc ^ gf::mul(a[i * 3 + l], b[l * 2 + j]));
+            assert_eq!(out[i * 2 + j], expected);
+        }
+    }
+    // a matrix times its inverse is the identity
+    let input = [3, 7, 9, 11];
+    let mut inverse = [0_u8; 4];
+    assert!(gf::invert_matrix(&mut input.clone(), &mut inverse, 2));
+    gf::mat_mul(&input, 2, 2, &inverse, 2, &mut out).unwrap();
+    assert_eq!(out, [1, 0, 0, 1]);
+
+    assert!(gf::mat_mul(&a, 3, 3, &b, 2, &mut out).is_err());
+    assert!(gf::mat_mul(&a, 2, 3, &b[1..], 2, &mut out).is_err());
+    assert!(gf::mat_mul(&a, 2, 3, &b, 2, &mut out[1..]).is_err());
+    // the size of a shape must not overflow
+    assert!(gf::mat_mul(&[], usize::MAX, 2, &[], 0, &mut []).is_err());
+}
+
+#[test]
+fn rs_matrix_with_base() {
+    use erasure_isa_l::erasure::ErasureCode;
+    use erasure_isa_l::gf;
+    use std::num::NonZeroUsize;
+    // base 2 is the generator of isa-l
+    for (m, k) in [(1, 1), (6, 4), (14, 10), (255, 3)] {
+        assert_eq!(gf::rs_matrix_with_base(m, k, 2), gf::rs_matrix(m, k));
+    }
+    let (n, k) = (7_usize, 4_usize);
+    let base = 0x1D;
+    let matrix = gf::rs_matrix_with_base(n as i32, k as i32, base);
+    for i in 0..n {
+        for j in 0..k {
+            let expected = match i.checked_sub(k) {
+                None => u8::from(i == j),
+                // base^(r * j)
+                Some(r) => (0..r * j).fold(1, |acc, _| gf::mul(acc, base)),
+            };
+            assert_eq!(matrix[i * k + j], expected, "row {}, column {}", i, j);
+        }
+    }
+    // the matrix encodes and decodes like any other
+    let ec = ErasureCode::with_matrix(
+        NonZeroUsize::new(k).unwrap(),
+        NonZeroUsize::new(n - k).unwrap(),
+        &matrix,
+    )
+    .unwrap();
+    let data = vec![vec![0x5A_u8; 64]; k];
+    let parity = ec.encode_to_owned(&data).unwrap();
+    let survivors = (0..k)
+        .skip(1)
+        .map(|i| (i, data[i].as_slice()))
+        .chain([(k, parity[0].as_slice())])
+        .collect::<Vec<_>>();
+    assert_eq!(
+        ec.decode_alloc(&survivors, &[0]).unwrap(),
+        vec![(0, data[0].clone())]
+    );
+}
+
+#[test]
+fn block_too_small() {
+    use erasure_isa_l::Error;
+    use erasure_isa_l::galois::{
+        DOT_PROD_MIN_LEN, GaloisFiledTable, MUL_ADD_MIN_LEN, dot_prod, mul_add,
+    };
+    let table = GaloisFiledTable::try_from_matrix(&[3, 4], 1, 2).unwrap();
+    let len = DOT_PROD_MIN_LEN - 1;
+    let source = vec![vec![0_u8; len]; 2];
+    let res = dot_prod(&table, &source, &mut vec![0_u8; len]);
+    assert_eq!(res, Err(Error::BlockTooSmall(len, DOT_PROD_MIN_LEN)));
+
+    let len = MUL_ADD_MIN_LEN - 1;
+    let res = mul_add(&table, 2, 1, &vec![0_u8; len], &mut vec![0_u8; len]);
+    assert_eq!(res, Err(Error::BlockTooSmall(len, MUL_ADD_MIN_LEN)));
+}
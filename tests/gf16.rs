@@ -0,0 +1,115 @@
+#![cfg(feature = "gf16")]
+use std::num::NonZeroUsize;
+
+use erasure_isa_l::Error;
+use erasure_isa_l::gf16::{self, ErasureCode16};
+use rand::seq::index;
+
+const BLOCK_LEN: usize = 64;
+
+/// Multiplies bit by bit, as an oracle of the table based `gf16::mul`.
+fn mul_bitwise(a: u16, b: u16) -> u16 {
+    let (mut a, mut b) = (a as u32, b);
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        a <<= 1;
+        if a & 0x10000 != 0 {
+            a ^= gf16::PRIMITIVE_POLY;
+        }
+        b >>= 1;
+    }
+    product as u16
+}
+
+#[test]
+fn field() {
+    // the generator spans all the non-zero elements
+    let mut seen = vec![false; gf16::FIELD_ORDER];
+    for n in 0..u16::MAX {
+        let a = gf16::exp(n);
+        assert!(!seen[a as usize]);
+        seen[a as usize] = true;
+        assert_eq!(gf16::log(a), Some(n));
+    }
+    assert_eq!(gf16::log(0), None);
+    for _ in 0..4096 {
+        let (a, b) = (rand::random(), rand::random());
+        assert_eq!(gf16::mul(a, b), mul_bitwise(a, b), "{a} * {b}");
+    }
+    for a in 1..=u16::MAX {
+        assert_eq!(gf16::mul(a, gf16::inv(a)), 1, "1 / {a}");
+    }
+    assert_eq!(gf16::inv(0), 0);
+}
+
+#[test]
+fn matrix() {
+    let (rows, k) = (12, 8);
+    let cauchy = gf16::cauchy1_matrix(rows, k);
+    for i in 0..k {
+        assert_eq!(
+            &cauchy[i * k..(i + 1) * k],
+            &gf16::cauchy1_matrix(k, k)[i * k..(i + 1) * k]
+        );
+    }
+    // any `k` rows of the Cauchy matrix are invertible
+    for _ in 0..16 {
+        let mut chosen = index::sample(&mut rand::rng(), rows, k).into_vec();
+        chosen.sort_unstable();
+        let sub = chosen
+            .iter()
+            .flat_map(|i| &cauchy[i * k..(i + 1) * k])
+            .copied()
+            .collect::<Vec<_>>();
+        let inverse = gf16::invert_matrix(&sub, k).unwrap();
+        assert_eq!(
+            gf16::mat_mul(&sub, k, k, &inverse, k),
+            gf16::cauchy1_matrix(k, k)
+        );
+    }
+    assert_eq!(gf16::invert_matrix(&[1, 2, 1, 2], 2), None);
+}
+
+#[test]
+fn encode_decode() {
+    let nz = |n| NonZeroUsize::new(n).unwrap();
+    assert!(matches!(
+        ErasureCode16::new(nz(65530), nz(6)),
+        Err(Error::InvalidArguments(_))
+    ));
+    // wider than the 255 blocks of GF(2^8)
+    let (k, m) = (300, 4);
+    let ec = ErasureCode16::new(nz(k), nz(m)).unwrap();
+    assert_eq!(ec.block_num(), k + m);
+    assert_eq!(ec.parity_matrix(), &gf16::cauchy1_matrix(k + m, k)[k * k..]);
+    let data = (0..k)
+        .map(|_| (0..BLOCK_LEN).map(|_| rand::random()).collect())
+        .collect::<Vec<Vec<u8>>>();
+    let parity = ec.encode_to_owned(&data).unwrap();
+    for erased in 1..=m {
+        let mut erasures = index::sample(&mut rand::rng(), k + m, erased).into_vec();
+        let (mut d, mut p) = (data.clone(), parity.clone());
+        for i in &erasures {
+            let block = if *i < k { &mut d[*i] } else { &mut p[*i - k] };
+            block.fill(0);
+        }
+        let recovered = ec.decode(&mut d, &mut p, &erasures).unwrap();
+        erasures.sort_unstable();
+        assert_eq!(recovered, erasures);
+        assert_eq!((&d, &p), (&data, &parity));
+    }
+
+    let (mut d, mut p) = (data.clone(), parity.clone());
+    let res = ec.decode(&mut d, &mut p, (0..=m).collect::<Vec<_>>());
+    assert!(matches!(res, Err(Error::TooManyErasures(5, 4))));
+    let res = ec.decode(&mut d, &mut p, [k + m]);
+    assert!(matches!(res, Err(Error::InvalidArguments(_))));
+    let odd = vec![vec![0_u8; BLOCK_LEN - 1]; k];
+    assert!(matches!(
+        ec.encode_to_owned(&odd),
+        Err(Error::InvalidArguments(_))
+    ));
+}
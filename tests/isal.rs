@@ -52,95 +52,2370 @@ fn invert_matrix() {
     assert_eq!(output, expected);
 }
 
+#[test]
+fn allocating_matrix() {
+    use erasure_isa_l::gf;
+
+    let (n, k) = (K + M, K);
+    let mut expected = vec![0_u8; n * k];
+    gf::gen_rs_matrix(&mut expected, n as i32, k as i32);
+    assert_eq!(gf::rs_matrix(n as i32, k as i32), expected);
+    gf::gen_cauchy1_matrix(&mut expected, n as i32, k as i32);
+    assert_eq!(gf::cauchy1_matrix(n as i32, k as i32), expected);
+}
+
+#[test]
+fn invert_matrix_diagnostic() {
+    use erasure_isa_l::gf;
+
+    // agrees with isa-l on the non-singular sub matrices of a Cauchy matrix
+    let (n, k) = (K + M, K);
+    let mut encode_matrix = vec![0_u8; n * k];
+    gf::gen_cauchy1_matrix(&mut encode_matrix, n as i32, k as i32);
+    for skip in 0..n - 1 {
+        let input = encode_matrix
+            .chunks_exact(k)
+            .enumerate()
+            .filter(|(i, _)| *i != skip && *i != skip + 1)
+            .flat_map(|(_, row)| row)
+            .copied()
+            .collect::<Vec<_>>();
+        let mut expected = vec![0_u8; k * k];
+        assert!(gf::invert_matrix(
+            input.clone().as_mut_slice(),
+            &mut expected,
+            k as i32
+        ));
+        assert_eq!(gf::invert_matrix_diagnostic(&input, k).unwrap(), expected);
+    }
+
+    // the third row is a multiple of the first one
+    #[rustfmt::skip]
+    let input: Vec<u8> = vec![
+        0x01, 0x02, 0x03,
+        0x00, 0x01, 0x04,
+        0x02, 0x04, 0x06,
+    ];
+    assert_eq!(
+        gf::invert_matrix_diagnostic(&input, 3),
+        Err(gf::InversionError { pivot_row: 2 })
+    );
+}
+
 #[test]
 #[ignore = "This test is for debuging"]
 fn fast_test() {
     use erasure_isa_l::erasure::ErasureCode;
 
     let k = NonZeroUsize::new(K).unwrap();
-    let m = NonZeroUsize::new(M).unwrap();
-    let code = ErasureCode::with_reed_solomon(k, m).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let code = ErasureCode::with_reed_solomon(k, m).unwrap();
+
+    // Further tests can be added here to validate encoding and decoding
+    // Prepare input data: k blocks of BLOCK_LEN bytes
+    let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+
+    // Prepare parity blocks (m blocks of BLOCK_LEN bytes, initially zeroed)
+    let mut parity: Vec<Vec<u8>> = vec![vec![0u8; BLOCK_LEN]; m.get()];
+
+    // Encode: fill parity blocks
+    code.encode(&data, &mut parity).expect("Encoding failed");
+
+    // Erase some blocks
+    let erausure = [2, 5];
+    let mut erased_data: Vec<Vec<u8>> = data.clone();
+    erased_data.get_mut(2).unwrap().fill(0);
+    let mut erased_parity: Vec<Vec<u8>> = parity.clone();
+    erased_parity.get_mut(1).unwrap().fill(0);
+
+    // Decode
+    code.decode(&mut erased_data, &mut erased_parity, erausure)
+        .expect("Decoding failed");
+
+    // Check that recovered data matches original
+    assert_eq!(erased_parity, parity);
+    assert_eq!(erased_data, data);
+}
+
+#[test]
+fn general_test_ec() {
+    use erasure_isa_l::erasure::ErasureCode;
+    let k_m_pair: [(usize, usize); 5] = [(4, 2), (6, 2), (9, 3), (10, 4), (16, 4)];
+    for (k, m) in k_m_pair {
+        let k = NonZeroUsize::new(k).unwrap();
+        let m = NonZeroUsize::new(m).unwrap();
+        let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+        general_test(ec).expect("General test failed for Reed-Solomon code");
+    }
+
+    for (k, m) in k_m_pair {
+        let k = NonZeroUsize::new(k).unwrap();
+        let m = NonZeroUsize::new(m).unwrap();
+        let ec = ErasureCode::with_cauchy(k, m).unwrap();
+        general_test(ec).expect("General test failed for Cauchy code");
+    }
+}
+
+#[test]
+fn encode_matrix() {
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_cauchy(k, m).unwrap();
+    let matrix = ec.encode_matrix();
+    assert_eq!(matrix.len(), (K + M) * K);
+
+    // the top is the identity matrix
+    for (i, row) in matrix.chunks_exact(K).take(K).enumerate() {
+        for (j, coef) in row.iter().enumerate() {
+            assert_eq!(*coef, u8::from(i == j));
+        }
+    }
+
+    // the code blocks are encoded by the parity rows
+    let data = make_rand_blk(K, BLOCK_LEN);
+    let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    for (row, code) in matrix.chunks_exact(K).skip(K).zip(parity.iter()) {
+        let expected = (0..BLOCK_LEN)
+            .map(|b| {
+                row.iter().zip(data.iter()).fold(0, |acc, (coef, d)| {
+                    acc ^ erasure_isa_l::gf::mul(*coef, d[b])
+                })
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(code, &expected);
+    }
+}
+
+#[test]
+fn with_matrix() {
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let cauchy = ErasureCode::with_cauchy(k, m).unwrap();
+    let ec = ErasureCode::with_matrix(k, m, cauchy.encode_matrix()).unwrap();
+    assert!(ec.compatible_with(&cauchy));
+    general_test(ec).expect("General test failed for user supplied matrix");
+
+    // wrong size
+    let res = ErasureCode::with_matrix(k, m, &cauchy.encode_matrix()[1..]);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+
+    // non-identity top
+    let mut matrix = cauchy.encode_matrix().to_vec();
+    matrix[1] = 1;
+    let res = ErasureCode::with_matrix(k, m, &matrix);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+
+    // invalid geometry
+    let res = ErasureCode::with_matrix(NonZeroUsize::new(255).unwrap(), m, &[]);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+}
+
+#[test]
+fn with_vandermonde() {
+    use erasure_isa_l::gf;
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_vandermonde(k, m).unwrap();
+    let mut expected = vec![0_u8; (K + M) * K];
+    gf::gen_rs_matrix(&mut expected, (K + M) as i32, K as i32);
+    assert_eq!(ec.encode_matrix(), expected.as_slice());
+    assert!(ec.compatible_with(&ErasureCode::with_reed_solomon(k, m).unwrap()));
+    general_test(ec).expect("General test failed for Vandermonde code");
+}
+
+#[test]
+fn reed_solomon_adjusted() {
+    use erasure_isa_l::Error;
+    let n = NonZeroUsize::new(6).unwrap();
+    let patterns = (0_usize..(1 << 12))
+        .filter(|mask| mask.count_ones() == 6)
+        .map(|mask| (0..12).filter(|i| mask & (1 << i) != 0).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    // the plain matrix of (6, 6) is not invertable for some erasure patterns
+    let vandermonde = ErasureCode::with_vandermonde(n, n).unwrap();
+    let singular = patterns
+        .iter()
+        .find(|p| !vandermonde.is_recoverable(p))
+        .unwrap();
+    assert_eq!(
+        vandermonde.make_decode_table(singular).err(),
+        Some(Error::Singular(singular.clone()))
+    );
+    // the reed solomon matrix is kept as is by default
+    let rs = ErasureCode::with_reed_solomon(n, n).unwrap();
+    assert_eq!(rs.encode_matrix(), vandermonde.encode_matrix());
+    // while the adjusted one is invertable for all of them
+    let adjusted = ErasureCode::with_adjusted_reed_solomon(n, n).unwrap();
+    assert_ne!(adjusted.encode_matrix(), vandermonde.encode_matrix());
+    assert!(patterns.iter().all(|p| adjusted.is_recoverable(p)));
+    general_test(adjusted).expect("General test failed for adjusted Reed-Solomon code");
+    // the pairs known to be invertable are never adjusted
+    let (k, m) = (NonZeroUsize::new(K).unwrap(), NonZeroUsize::new(M).unwrap());
+    assert_eq!(
+        ErasureCode::with_adjusted_reed_solomon(k, m)
+            .unwrap()
+            .encode_matrix(),
+        ErasureCode::with_reed_solomon(k, m)
+            .unwrap()
+            .encode_matrix()
+    );
+    // the larger pairs are checked too, while a pair which cannot be adjusted is rejected
+    for (k, m) in [(7, 5), (20, 4)] {
+        let ec = ErasureCode::with_adjusted_reed_solomon(
+            NonZeroUsize::new(k).unwrap(),
+            NonZeroUsize::new(m).unwrap(),
+        )
+        .unwrap();
+        assert!(
+            (0_usize..(1 << (k + m)))
+                .filter(|mask| mask.count_ones() as usize == m)
+                .map(|mask| (0..k + m)
+                    .filter(|i| mask & (1 << i) != 0)
+                    .collect::<Vec<_>>())
+                .all(|p| ec.is_recoverable(&p))
+        );
+    }
+    assert!(matches!(
+        ErasureCode::with_adjusted_reed_solomon(
+            NonZeroUsize::new(10).unwrap(),
+            NonZeroUsize::new(6).unwrap()
+        ),
+        Err(Error::InternalError(_))
+    ));
+}
+
+#[test]
+fn builder() {
+    use erasure_isa_l::erasure::MatrixKind;
+    let n = |v: usize| NonZeroUsize::new(v).unwrap();
+    for (kind, expected) in [
+        (
+            MatrixKind::ReedSolomon,
+            ErasureCode::with_reed_solomon(n(K), n(M)),
+        ),
+        (
+            MatrixKind::AdjustedReedSolomon,
+            ErasureCode::with_adjusted_reed_solomon(n(K), n(M)),
+        ),
+        (
+            MatrixKind::Vandermonde,
+            ErasureCode::with_vandermonde(n(K), n(M)),
+        ),
+        (MatrixKind::Cauchy, ErasureCode::with_cauchy(n(K), n(M))),
+    ] {
+        let ec = ErasureCode::builder()
+            .source_num(K)
+            .code_num(M)
+            .matrix_kind(kind)
+            .build()
+            .unwrap();
+        assert_eq!(ec.encode_matrix(), expected.unwrap().encode_matrix());
+        assert_eq!(ec.cache_capacity(), 0);
+    }
+
+    // defaults to reed solomon
+    let ec = ErasureCode::builder()
+        .source_num(K)
+        .code_num(M)
+        .cache_capacity(8)
+        .build()
+        .unwrap();
+    let expected = ErasureCode::with_reed_solomon(n(K), n(M)).unwrap();
+    assert_eq!(ec.encode_matrix(), expected.encode_matrix());
+    assert_eq!(ec.cache_capacity(), 8);
+
+    // the geometry must be set and valid
+    for builder in [
+        ErasureCode::builder().source_num(K),
+        ErasureCode::builder().code_num(M),
+        ErasureCode::builder().source_num(255).code_num(1),
+    ] {
+        assert!(matches!(
+            builder.build(),
+            Err(erasure_isa_l::Error::InvalidArguments(_))
+        ));
+    }
+}
+
+#[test]
+fn invalid_geometry() {
+    let n = |v: usize| NonZeroUsize::new(v).unwrap();
+    for (k, m) in [(200, 100), (255, 1), (1, 255), (usize::MAX, 1)] {
+        let res = ErasureCode::with_cauchy(n(k), n(m));
+        assert!(matches!(
+            res,
+            Err(erasure_isa_l::Error::InvalidArguments(_))
+        ));
+        let res = ErasureCode::with_reed_solomon(n(k), n(m));
+        assert!(matches!(
+            res,
+            Err(erasure_isa_l::Error::InvalidArguments(_))
+        ));
+    }
+    assert!(ErasureCode::with_cauchy(n(254), n(1)).is_ok());
+}
+
+#[test]
+fn decode_table() {
+    use erasure_isa_l::erasure::ErasureCode;
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+
+    let orig_data = make_rand_blk(K, BLOCK_LEN);
+    let mut orig_parity = make_zero_blk(M, BLOCK_LEN);
+    ec.encode(&orig_data, &mut orig_parity)
+        .expect("Encoding failed");
+    let orig_parity = orig_parity;
+
+    let erasures_list = [vec![2], vec![5], vec![0, 4]];
+    for erasures in &erasures_list {
+        let mut data = orig_data.clone();
+        let mut parity = orig_parity.clone();
+        // Set erased blocks to zero
+        for &e in erasures {
+            if e < K {
+                data[e] = vec![0_u8; BLOCK_LEN];
+            } else {
+                parity[e - K] = vec![0_u8; BLOCK_LEN];
+            }
+        }
+        let table = ec
+            .make_decode_table(erasures)
+            .expect("Failed to get decode table");
+        let recovered = ec
+            .decode_with_table(&mut data, &mut parity, &table, erasures)
+            .expect("Decoding with table failed");
+        let mut expected = erasures.clone();
+        expected.sort_unstable();
+        expected.dedup();
+        assert_eq!(recovered, expected);
+        assert_eq!(table.erasures(), expected.as_slice());
+
+        // Check that recovered data matches original
+        assert_eq!(data, orig_data);
+        assert_eq!(parity, orig_parity);
+    }
+
+    // a table generated for other erasures is rejected
+    let table = ec.make_decode_table([0, 4]).unwrap();
+    let mut data = orig_data.clone();
+    let mut parity = orig_parity.clone();
+    let res = ec.decode_with_table(&mut data, &mut parity, &table, [0, 5]);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+    assert!(
+        ec.decode_with_table(&mut data, &mut parity, &table, [4, 0, 0])
+            .is_ok()
+    );
+}
+
+#[test]
+fn erasures_argument_forms() {
+    use erasure_isa_l::erasure::ErasureCode;
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    let orig_data = make_rand_blk(K, BLOCK_LEN);
+    let orig_parity = ec.encode_to_owned(&orig_data).unwrap();
+    let erased = || {
+        let (mut data, mut parity) = (orig_data.clone(), orig_parity.clone());
+        data[1].fill(0);
+        parity[0].fill(0);
+        (data, parity)
+    };
+    let erasures = vec![1, 4];
+
+    // an array, a slice, a borrowed and an owned vector
+    let (mut d, mut p) = erased();
+    assert_eq!(ec.decode(&mut d, &mut p, [1, 4]).unwrap(), erasures);
+    assert_eq!((d, p), (orig_data.clone(), orig_parity.clone()));
+    let (mut d, mut p) = erased();
+    assert_eq!(ec.decode(&mut d, &mut p, &erasures[..]).unwrap(), erasures);
+    assert_eq!((d, p), (orig_data.clone(), orig_parity.clone()));
+    let (mut d, mut p) = erased();
+    assert_eq!(ec.decode(&mut d, &mut p, &erasures).unwrap(), erasures);
+    assert_eq!((d, p), (orig_data.clone(), orig_parity.clone()));
+    let (mut d, mut p) = erased();
+    assert_eq!(
+        ec.decode(&mut d, &mut p, erasures.clone()).unwrap(),
+        erasures
+    );
+    assert_eq!((d, p), (orig_data.clone(), orig_parity.clone()));
+
+    // the same forms for the decode tables
+    for table in [
+        ec.make_decode_table([1, 4]).unwrap(),
+        ec.make_decode_table(&erasures[..]).unwrap(),
+        ec.make_decode_table(&erasures).unwrap(),
+        ec.make_decode_table(erasures.clone()).unwrap(),
+    ] {
+        let (mut d, mut p) = erased();
+        ec.decode_with_table(&mut d, &mut p, &table, [1, 4])
+            .unwrap();
+        assert_eq!((d, p), (orig_data.clone(), orig_parity.clone()));
+    }
+}
+
+#[test]
+fn decode_table_unsorted_erasures() {
+    use erasure_isa_l::erasure::ErasureCode;
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    let orig_data = make_rand_blk(K, BLOCK_LEN);
+    let orig_parity = ec.encode_to_owned(&orig_data).unwrap();
+
+    // duplicated and unsorted erasures are sorted and deduplicated
+    let erasures = [4, 0, 4];
+    let mut data = orig_data.clone();
+    let mut parity = orig_parity.clone();
+    data[0].fill(0);
+    parity[0].fill(0);
+    let table = ec.make_decode_table(erasures).unwrap();
+    assert_eq!(table.erasures(), [0, 4]);
+    let recovered = ec
+        .decode_with_table(&mut data, &mut parity, &table, erasures)
+        .unwrap();
+    assert_eq!(recovered, vec![0, 4]);
+    assert_eq!(data, orig_data);
+    assert_eq!(parity, orig_parity);
+}
+
+#[test]
+fn compatible_with() {
+    use erasure_isa_l::erasure::ErasureCode;
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+
+    let rs = ErasureCode::with_reed_solomon(k, m).unwrap();
+    let cauchy = ErasureCode::with_cauchy(k, m).unwrap();
+    assert!(rs.compatible_with(&ErasureCode::with_reed_solomon(k, m).unwrap()));
+    assert!(cauchy.compatible_with(&ErasureCode::with_cauchy(k, m).unwrap()));
+
+    // different matrix
+    assert!(!rs.compatible_with(&cauchy));
+    assert!(!cauchy.compatible_with(&rs));
+    // different geometry
+    let wider = ErasureCode::with_cauchy(k, NonZeroUsize::new(M + 1).unwrap()).unwrap();
+    assert!(!cauchy.compatible_with(&wider));
+    let narrower = ErasureCode::with_cauchy(NonZeroUsize::new(K - 1).unwrap(), m).unwrap();
+    assert!(!cauchy.compatible_with(&narrower));
+}
+
+#[test]
+fn decode_one_into() {
+    use erasure_isa_l::erasure::ErasureCode;
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_cauchy(k, m).unwrap();
+
+    let data = make_rand_blk(K, BLOCK_LEN);
+    let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    let stripe = data.iter().chain(parity.iter()).collect::<Vec<_>>();
+
+    for erasure in 0..K + M {
+        let survivors = stripe
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != erasure)
+            .map(|(i, blk)| (i, blk.as_slice()))
+            .collect::<Vec<_>>();
+        let orig_accumulator = make_rand_blk(1, BLOCK_LEN).pop().unwrap();
+        let mut accumulator = orig_accumulator.clone();
+        ec.decode_one_into(&survivors, erasure, &mut accumulator)
+            .expect("Decoding failed");
+        let expected = orig_accumulator
+            .iter()
+            .zip(stripe[erasure].iter())
+            .map(|(a, b)| a ^ b)
+            .collect::<Vec<_>>();
+        assert_eq!(accumulator, expected);
+    }
+
+    // not enough survivors
+    let survivors = (0..K - 1)
+        .map(|i| (i, data[i].as_slice()))
+        .collect::<Vec<_>>();
+    let mut accumulator = vec![0_u8; BLOCK_LEN];
+    let res = ec.decode_one_into(&survivors, K - 1, &mut accumulator);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::TooManyErasures(..))
+    ));
+
+    // accumulator length mismatch
+    let survivors = (0..K).map(|i| (i, data[i].as_slice())).collect::<Vec<_>>();
+    let mut accumulator = vec![0_u8; BLOCK_LEN - 1];
+    let res = ec.decode_one_into(&survivors, K, &mut accumulator);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(..))
+    ));
+}
+
+#[test]
+fn decode_one_to_writer() {
+    use erasure_isa_l::erasure::ErasureCode;
+    // larger than a single chunk, and not a multiple of it
+    const LARGE_BLOCK_LEN: usize = 150 * 1024 + 7;
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+
+    let data = make_rand_blk(K, LARGE_BLOCK_LEN);
+    let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    let stripe = data.iter().chain(parity.iter()).collect::<Vec<_>>();
+
+    for erasure in [0, K - 1, K, K + M - 1] {
+        let survivors = stripe
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != erasure)
+            .map(|(i, blk)| (i, blk.as_slice()))
+            .collect::<Vec<_>>();
+        let mut out = Vec::new();
+        ec.decode_one_to_writer(&survivors, erasure, LARGE_BLOCK_LEN, &mut out)
+            .expect("Decoding failed");
+        assert_eq!(&out, stripe[erasure]);
+    }
+
+    // writer errors are propagated
+    struct FailWriter;
+    impl std::io::Write for FailWriter {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("broken sink"))
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    let survivors = (1..=K)
+        .map(|i| (i, stripe[i].as_slice()))
+        .collect::<Vec<_>>();
+    let res = ec.decode_one_to_writer(&survivors, 0, LARGE_BLOCK_LEN, FailWriter);
+    assert!(matches!(res, Err(erasure_isa_l::Error::Io(..))));
+
+    // block length mismatch
+    let res = ec.decode_one_to_writer(&survivors, 0, LARGE_BLOCK_LEN - 1, Vec::new());
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(..))
+    ));
+}
+
+#[test]
+fn encode_rows_and_verify_parity() {
+    use erasure_isa_l::erasure::ErasureCode;
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(3).unwrap();
+    let ec = ErasureCode::with_cauchy(k, m).unwrap();
+
+    let data = make_rand_blk(K, BLOCK_LEN);
+    let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+
+    for start in 0..3 {
+        for end in start..=3 {
+            let mut code = make_zero_blk(end - start, BLOCK_LEN);
+            ec.encode_rows(&data, start..end, &mut code)
+                .expect("Encoding failed");
+            assert_eq!(&code, &parity[start..end]);
+        }
+    }
+    let res = ec.encode_rows(&data, 2..4, make_zero_blk(2, BLOCK_LEN));
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(..))
+    ));
+
+    for (i, p) in parity.iter().enumerate() {
+        assert!(ec.verify_parity(&data, i, p).unwrap());
+        let mut corrupted = p.clone();
+        corrupted[BLOCK_LEN / 2] ^= 0x5A;
+        assert!(!ec.verify_parity(&data, i, &corrupted).unwrap());
+    }
+    let res = ec.verify_parity(&data, 3, &parity[0]);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(..))
+    ));
+    let res = ec.verify_parity(&data, usize::MAX, &parity[0]);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(..))
+    ));
+    let res = ec.verify_parity(&data, 0, &parity[0][1..]);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(..))
+    ));
+}
+
+#[test]
+fn verify() {
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_cauchy(k, m).unwrap();
+
+    let data = make_rand_blk(K, BLOCK_LEN);
+    let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    assert!(ec.verify(&data, &parity).unwrap());
+
+    for p in 0..M {
+        let mut corrupted = parity.clone();
+        corrupted[p][BLOCK_LEN - 1] ^= 0x80;
+        assert!(!ec.verify(&data, &corrupted).unwrap());
+    }
+    let mut corrupted = data.clone();
+    corrupted[0][0] ^= 1;
+    assert!(!ec.verify(&corrupted, &parity).unwrap());
+
+    // invalid buffers
+    let res = ec.verify(&data, &parity[..M - 1]);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+    let mut short = parity.clone();
+    short[0].pop();
+    let res = ec.verify(&data, &short);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+}
+
+#[test]
+fn encode_decode_len() {
+    use erasure_isa_l::erasure::ErasureCode;
+    const CAPACITY: usize = BLOCK_LEN * 2;
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_cauchy(k, m).unwrap();
+
+    let data = make_rand_blk(K, CAPACITY);
+    let mut parity = vec![vec![0xFF_u8; CAPACITY]; M];
+    ec.encode_len(&data, &mut parity, BLOCK_LEN)
+        .expect("Encoding failed");
+    let truncated = data.iter().map(|d| &d[..BLOCK_LEN]).collect::<Vec<_>>();
+    let expected = ec.encode_to_owned(&truncated).expect("Encoding failed");
+    for (p, e) in parity.iter().zip(expected.iter()) {
+        assert_eq!(&p[..BLOCK_LEN], e.as_slice());
+        // bytes beyond len are untouched
+        assert!(p[BLOCK_LEN..].iter().all(|b| *b == 0xFF));
+    }
+
+    let mut erased_data = data.clone();
+    erased_data[1][..BLOCK_LEN].fill(0);
+    let mut erased_parity = parity.clone();
+    erased_parity[0][..BLOCK_LEN].fill(0);
+    let recovered = ec
+        .decode_len(
+            &mut erased_data,
+            &mut erased_parity,
+            vec![K, 1, K],
+            BLOCK_LEN,
+        )
+        .expect("Decoding failed");
+    assert_eq!(recovered, vec![1, K]);
+    assert_eq!(erased_data, data);
+    assert_eq!(erased_parity, parity);
+
+    // block shorter than len
+    let res = ec.encode_len(&data, &mut parity, CAPACITY + 1);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(..))
+    ));
+    let res = ec.decode_len(&mut erased_data, &mut erased_parity, vec![1], CAPACITY + 1);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(..))
+    ));
+}
+
+#[test]
+fn plan_decode_zoned() {
+    use erasure_isa_l::erasure::ErasureCode;
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    let data = make_rand_blk(K, BLOCK_LEN);
+    let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    let stripe = data.iter().chain(parity.iter()).collect::<Vec<_>>();
+
+    let zone_num = |survivors: &[usize], zones: &[u32]| {
+        let mut contacted = survivors.iter().map(|&i| zones[i]).collect::<Vec<_>>();
+        contacted.dedup();
+        contacted.len()
+    };
+
+    // zone 2 alone does not have enough survivors, but zone 1 and 2 do
+    let zones = [0, 1, 1, 2, 2, 2];
+    let plan = ec.plan_decode_zoned(vec![0], &zones).unwrap();
+    assert_eq!(plan.erasures(), &[0]);
+    assert_eq!(plan.survivors().len(), K);
+    assert_eq!(zone_num(plan.survivors(), &zones), 2);
+    assert!(plan.survivors().iter().all(|&i| zones[i] != 0));
+
+    // the planned survivors recover the erased block
+    let survivors = plan
+        .survivors()
+        .iter()
+        .map(|&i| (i, stripe[i].as_slice()))
+        .collect::<Vec<_>>();
+    let mut recovered = vec![0_u8; BLOCK_LEN];
+    ec.decode_one_into(&survivors, 0, &mut recovered)
+        .expect("Decoding failed");
+    assert_eq!(&recovered, stripe[0]);
+
+    // a single zone is enough
+    let zones = [7, 7, 7, 7, 3, 3];
+    let plan = ec.plan_decode_zoned(vec![5], &zones).unwrap();
+    assert_eq!(plan.survivors(), &[0, 1, 2, 3]);
+
+    // every block in its own zone
+    let zones = [0, 1, 2, 3, 4, 5];
+    let plan = ec.plan_decode_zoned(vec![1, 4], &zones).unwrap();
+    assert_eq!(plan.survivors(), &[0, 2, 3, 5]);
+
+    let res = ec.plan_decode_zoned(vec![0], &zones[1..]);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(..))
+    ));
+}
+
+#[test]
+fn encode_overlapping_buffers() {
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    let source = make_rand_blk(K, BLOCK_LEN);
+    let expected = ec.encode_to_owned(&source).unwrap();
+
+    // adjacent blocks of a single buffer do not overlap
+    let mut stripe = source.concat();
+    stripe.resize((K + M) * BLOCK_LEN, 0);
+    let (data, code) = stripe.split_at_mut(K * BLOCK_LEN);
+    let data = data.chunks_exact(BLOCK_LEN).collect::<Vec<_>>();
+    let mut code = code.chunks_exact_mut(BLOCK_LEN).collect::<Vec<_>>();
+    ec.encode(&data, &mut code).unwrap();
+    assert_eq!(code, expected);
+}
+
+#[test]
+fn shortened_code() {
+    use erasure_isa_l::Error;
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_cauchy(k, m).unwrap();
+    for s in 1..=K {
+        let data = make_rand_blk(s, BLOCK_LEN);
+        let mut parity = make_zero_blk(M, BLOCK_LEN);
+        ec.encode_shortened(&data, &mut parity).unwrap();
+        let mut padded = data.clone();
+        padded.resize(K, vec![0_u8; BLOCK_LEN]);
+        assert_eq!(parity, ec.encode_to_owned(&padded).unwrap());
+
+        // every pattern of up to M erasures among the present blocks
+        let present = (0..s).chain(K..K + M).collect::<Vec<_>>();
+        for a in 0..present.len() {
+            for b in a..present.len() {
+                let erasures = [present[a], present[b]];
+                let (mut erased_data, mut erased_parity) = (data.clone(), parity.clone());
+                for e in erasures {
+                    match e {
+                        e if e < K => erased_data[e].fill(0xFF),
+                        e => erased_parity[e - K].fill(0xFF),
+                    }
+                }
+                let recovered = ec
+                    .decode_shortened(&mut erased_data, &mut erased_parity, erasures)
+                    .unwrap();
+                let mut expected = erasures.to_vec();
+                expected.dedup();
+                assert_eq!(recovered, expected);
+                assert_eq!(erased_data, data);
+                assert_eq!(erased_parity, parity);
+            }
+        }
+
+        let (mut erased_data, mut erased_parity) = (data.clone(), parity.clone());
+        // the absent blocks are never erased
+        for absent in s..K {
+            let res = ec.decode_shortened(&mut erased_data, &mut erased_parity, [absent]);
+            assert!(matches!(res, Err(Error::InvalidArguments(_))));
+        }
+        let res = ec.decode_shortened(&mut erased_data, &mut erased_parity, [K + M]);
+        assert!(matches!(res, Err(Error::InvalidArguments(_))));
+        let res = ec.decode_shortened(&mut erased_data, &mut erased_parity, [0, K, K + 1]);
+        assert!(matches!(res, Err(Error::TooManyErasures(..))));
+    }
+
+    let mut parity = make_zero_blk(M, BLOCK_LEN);
+    // no data block, or more than the source number
+    let res = ec.encode_shortened(make_zero_blk(0, BLOCK_LEN), &mut parity);
+    assert!(matches!(res, Err(Error::InvalidArguments(_))));
+    let res = ec.encode_shortened(make_zero_blk(K + 1, BLOCK_LEN), &mut parity);
+    assert!(matches!(res, Err(Error::InvalidArguments(_))));
+    // blocks of different lengths
+    let res = ec.encode_shortened(make_zero_blk(1, BLOCK_LEN + 1), &mut parity);
+    assert!(matches!(res, Err(Error::InvalidArguments(_))));
+}
+
+#[test]
+fn encode_contiguous() {
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_cauchy(k, m).unwrap();
+
+    let data = make_rand_blk(1, K * BLOCK_LEN).pop().unwrap();
+    let mut parity = make_zero_blk(M, BLOCK_LEN);
+    ec.encode_contiguous(&data, &mut parity)
+        .expect("Encoding failed");
+    let blocks = data.chunks(BLOCK_LEN).collect::<Vec<_>>();
+    assert_eq!(parity, ec.encode_to_owned(&blocks).unwrap());
+
+    // the data length is not a multiple of the source number
+    let res = ec.encode_contiguous(&data[1..], &mut parity);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+    // the code blocks do not match the shard length
+    let res = ec.encode_contiguous(&data[K..], &mut parity);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+}
+
+#[test]
+fn encode_padded() {
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_cauchy(k, m).unwrap();
+
+    let max_len = 4 * BLOCK_LEN;
+    for lens in [
+        [max_len; K],
+        [max_len, 10, max_len - 1, 0],
+        [0, 0, 0, max_len],
+        [1, max_len, 70, 200],
+    ] {
+        let data = lens
+            .iter()
+            .map(|&len| make_rand_blk(1, len).pop().unwrap())
+            .collect::<Vec<_>>();
+        let mut parity = make_rand_blk(M, max_len);
+        ec.encode_padded(&data, &mut parity)
+            .expect("Encoding failed");
+        let padded = data
+            .iter()
+            .map(|d| {
+                let mut d = d.clone();
+                d.resize(max_len, 0);
+                d
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(parity, ec.encode_to_owned(&padded).unwrap());
+    }
+
+    let data = [vec![1_u8; BLOCK_LEN], vec![1_u8; 10], vec![], vec![]];
+    let res = ec.encode_padded(&data, make_zero_blk(M, BLOCK_LEN + 1));
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+    let res = ec.encode_padded(&data[1..], make_zero_blk(M, BLOCK_LEN));
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+}
+
+#[test]
+fn encode_arrays() {
+    let ec = ErasureCode::with_cauchy(NonZeroUsize::new(K).unwrap(), NonZeroUsize::new(M).unwrap())
+        .unwrap();
+    let data = make_rand_blk(K, BLOCK_LEN);
+    let data: [&[u8]; K] = std::array::from_fn(|i| data[i].as_slice());
+    let mut parity = make_zero_blk(M, BLOCK_LEN);
+    let [p, q] = parity.as_mut_slice() else {
+        unreachable!()
+    };
+    ec.encode_arrays(&data, &mut [p, q])
+        .expect("Encoding failed");
+    assert_eq!(parity, ec.encode_to_owned(data).unwrap());
+
+    // the array lengths must match the geometry
+    let mut p = vec![0_u8; BLOCK_LEN];
+    let res = ec.encode_arrays(&data, &mut [&mut p]);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+    let mut q = vec![0_u8; BLOCK_LEN];
+    let res = ec.encode_arrays(&[data[0]], &mut [&mut p, &mut q]);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+}
+
+#[test]
+fn encode_single() {
+    let ec = ErasureCode::with_cauchy(NonZeroUsize::new(K).unwrap(), NonZeroUsize::new(M).unwrap())
+        .unwrap();
+    for len in [1, 31, BLOCK_LEN] {
+        let data = make_rand_blk(K, len);
+        let parity = ec.encode_to_owned(&data).unwrap();
+        for (i, p) in parity.iter().enumerate() {
+            let mut out = vec![0_u8; len];
+            ec.encode_single(i, &data, &mut out)
+                .expect("Encoding failed");
+            assert_eq!(&out, p);
+        }
+    }
+
+    let data = make_rand_blk(K, BLOCK_LEN);
+    for (index, len) in [(M, BLOCK_LEN), (0, BLOCK_LEN - 1)] {
+        let res = ec.encode_single(index, &data, &mut vec![0_u8; len]);
+        assert!(matches!(
+            res,
+            Err(erasure_isa_l::Error::InvalidArguments(_))
+        ));
+    }
+}
+
+#[test]
+fn short_blocks() {
+    use erasure_isa_l::gf;
+    // every length below the minimum of the SIMD kernels matches the scalar arithmetic
+    for ec in [
+        ErasureCode::with_cauchy(NonZeroUsize::new(K).unwrap(), NonZeroUsize::new(M).unwrap()),
+        ErasureCode::with_reed_solomon(
+            NonZeroUsize::new(K).unwrap(),
+            NonZeroUsize::new(M).unwrap(),
+        ),
+    ] {
+        let ec = ec.unwrap();
+        let matrix = ec.encode_matrix();
+        for len in 1..BLOCK_LEN {
+            let data = make_rand_blk(K, len);
+            let parity = ec.encode_to_owned(&data).unwrap();
+            for (p, block) in parity.iter().enumerate() {
+                let expected = (0..len)
+                    .map(|b| {
+                        (0..K).fold(0, |acc, i| {
+                            acc ^ gf::mul(matrix[K * (K + p) + i], data[i][b])
+                        })
+                    })
+                    .collect::<Vec<u8>>();
+                assert_eq!(block, &expected);
+            }
+            let mut erased_data = data.clone();
+            erased_data[0].fill(0);
+            let mut erased_parity = parity.clone();
+            erased_parity[1].fill(0);
+            ec.decode(&mut erased_data, &mut erased_parity, [0, K + 1])
+                .expect("Decoding failed");
+            assert_eq!(erased_data, data);
+            assert_eq!(erased_parity, parity);
+        }
+    }
+}
+
+#[test]
+fn encode_raid6() {
+    let n = |v: usize| NonZeroUsize::new(v).unwrap();
+    for k in [1, 2, K, 13] {
+        let ec = ErasureCode::with_reed_solomon(n(k), n(2)).unwrap();
+        // cover both the words and the remaining bytes
+        for len in [0, 7, BLOCK_LEN, BLOCK_LEN + 3] {
+            let data = make_rand_blk(k, len);
+            let mut parity = make_rand_blk(2, len);
+            ec.encode_raid6(&data, &mut parity)
+                .expect("Encoding failed");
+            assert_eq!(parity, ec.encode_to_owned(&data).unwrap());
+        }
+    }
+
+    for ec in [
+        ErasureCode::with_cauchy(n(K), n(2)).unwrap(),
+        ErasureCode::with_reed_solomon(n(K), n(3)).unwrap(),
+    ] {
+        let data = make_rand_blk(K, BLOCK_LEN);
+        let res = ec.encode_raid6(&data, make_zero_blk(ec.code_num(), BLOCK_LEN));
+        assert!(matches!(
+            res,
+            Err(erasure_isa_l::Error::InvalidArguments(_))
+        ));
+    }
+}
+
+#[test]
+fn xor_parity() {
+    let n = |v: usize| NonZeroUsize::new(v).unwrap();
+    for k in [1, K, 13] {
+        let ec = ErasureCode::with_reed_solomon(n(k), n(1)).unwrap();
+        let data = make_rand_blk(k, BLOCK_LEN + 3);
+        let mut parity = make_rand_blk(1, BLOCK_LEN + 3);
+        ec.encode(&data, &mut parity).expect("Encoding failed");
+        let xor = data.iter().fold(vec![0u8; BLOCK_LEN + 3], |acc, d| {
+            acc.iter().zip(d).map(|(a, b)| a ^ b).collect()
+        });
+        assert_eq!(parity, vec![xor]);
+        for erased in 0..=k {
+            let mut erased_data = data.clone();
+            let mut erased_parity = parity.clone();
+            erased_data
+                .iter_mut()
+                .chain(erased_parity.iter_mut())
+                .nth(erased)
+                .unwrap()
+                .fill(0);
+            let res = ec
+                .decode(&mut erased_data, &mut erased_parity, [erased])
+                .expect("Decoding failed");
+            assert_eq!(res, vec![erased]);
+            assert_eq!(erased_data, data);
+            assert_eq!(erased_parity, parity);
+        }
+    }
+}
+
+#[test]
+fn update_short_delta() {
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+
+    let mut data = make_rand_blk(K, BLOCK_LEN);
+    let mut parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    // deltas shorter than the 64 bytes required by the SIMD kernels
+    for (index, len) in [(0, 1), (1, 16), (K - 1, 63)] {
+        let range = BLOCK_LEN - len..BLOCK_LEN;
+        let delta = make_rand_blk(1, len).pop().unwrap();
+        let parity_slice = parity
+            .iter_mut()
+            .map(|p| &mut p[range.clone()])
+            .collect::<Vec<_>>();
+        ec.update(index, &delta, parity_slice)
+            .expect("Update failed");
+        data[index][range]
+            .iter_mut()
+            .zip(&delta)
+            .for_each(|(d, x)| *d ^= x);
+        assert_eq!(parity, ec.encode_to_owned(&data).unwrap());
+    }
+}
+
+#[test]
+fn compute_delta() {
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_cauchy(k, m).unwrap();
+
+    let mut data = make_rand_blk(K, BLOCK_LEN);
+    let mut parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    let new = make_rand_blk(1, BLOCK_LEN).pop().unwrap();
+    let delta = ErasureCode::compute_delta(&data[1], &new).unwrap();
+    let mut delta_into = vec![0_u8; BLOCK_LEN];
+    ErasureCode::compute_delta_into(&data[1], &new, &mut delta_into).unwrap();
+    assert_eq!(delta, delta_into);
+    ec.update(1, &delta, &mut parity).expect("Update failed");
+    data[1] = new;
+    assert_eq!(parity, ec.encode_to_owned(&data).unwrap());
+
+    assert!(ErasureCode::compute_delta(&data[0], &data[1][1..]).is_err());
+    assert!(ErasureCode::compute_delta_into(&data[0], &data[1], &mut delta_into[1..]).is_err());
+}
+
+#[test]
+fn update_many() {
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_cauchy(k, m).unwrap();
+
+    let mut data = make_rand_blk(K, BLOCK_LEN);
+    let mut parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    let deltas = make_rand_blk(3, BLOCK_LEN);
+    // the same index may be updated twice
+    let updates = [(0, deltas[0].as_slice()), (3, &deltas[1]), (0, &deltas[2])];
+    ec.update_many(&updates, &mut parity)
+        .expect("Update failed");
+    for (index, delta) in updates {
+        data[index].iter_mut().zip(delta).for_each(|(d, x)| *d ^= x);
+    }
+    assert_eq!(parity, ec.encode_to_owned(&data).unwrap());
+
+    // nothing is updated if any update is invalid
+    let orig_parity = parity.clone();
+    let short = vec![0xFF_u8; BLOCK_LEN - 1];
+    let res = ec.update_many(&[(0, &deltas[0]), (1, &short)], &mut parity);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+    let res = ec.update_many(&[(0, &deltas[0]), (K, &deltas[1])], &mut parity);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+    assert_eq!(parity, orig_parity);
+}
+
+#[test]
+fn apply_writes() {
+    use erasure_isa_l::Error;
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_cauchy(k, m).unwrap();
+
+    let orig = make_rand_blk(K, BLOCK_LEN);
+    let parity = ec.encode_to_owned(&orig).expect("Encoding failed");
+    let deltas = make_rand_blk(K, BLOCK_LEN);
+    let write = |n: usize| {
+        let mut data = orig.clone();
+        for (d, delta) in data.iter_mut().zip(&deltas).take(n) {
+            d.iter_mut().zip(delta).for_each(|(d, x)| *d ^= x);
+        }
+        data
+    };
+    // both a few and most of the blocks are changed
+    for n in [1, K - 1, K] {
+        let data = write(n);
+        let changed = (0..n)
+            .map(|i| (i, deltas[i].as_slice()))
+            .collect::<Vec<_>>();
+        let mut code = parity.clone();
+        ec.apply_writes(&data, &changed, &mut code)
+            .expect("Update failed");
+        assert_eq!(code, ec.encode_to_owned(&data).unwrap());
+    }
+
+    let data = write(1);
+    let expected = ec.encode_to_owned(&data).unwrap();
+    // the ratio decides whether the deltas or the data are used
+    let changed = [(0, deltas[1].as_slice())];
+    let mut code = parity.clone();
+    ec.apply_writes_with_ratio(&data, &changed, &mut code, 0.0)
+        .expect("Update failed");
+    assert_eq!(code, expected);
+    let mut code = parity.clone();
+    ec.apply_writes_with_ratio(&data, &changed, &mut code, 2.0)
+        .expect("Update failed");
+    assert_ne!(code, expected);
+
+    let res = ec.apply_writes(&data[1..], &changed, &mut code);
+    assert_eq!(res, Err(Error::InvalidArguments(String::new())));
+    let res = ec.apply_writes(&data, &[(K, deltas[0].as_slice())], &mut code);
+    assert_eq!(res, Err(Error::InvalidArguments(String::new())));
+}
+
+#[test]
+fn parities_affected_by() {
+    use erasure_isa_l::erasure::ErasureCode;
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    for ec in [
+        ErasureCode::with_reed_solomon(k, m).unwrap(),
+        ErasureCode::with_cauchy(k, m).unwrap(),
+    ] {
+        for i in 0..K {
+            assert_eq!(
+                ec.parities_affected_by(i).unwrap(),
+                (0..M).collect::<Vec<_>>()
+            );
+        }
+        assert!(matches!(
+            ec.parities_affected_by(K),
+            Err(erasure_isa_l::Error::InvalidArguments(..))
+        ));
+    }
+}
+
+#[test]
+fn decode_target() {
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_cauchy(k, m).unwrap();
+
+    let data = make_rand_blk(K, BLOCK_LEN);
+    let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    let stripe = data.iter().chain(parity.iter()).collect::<Vec<_>>();
+
+    // a lost data block and a surviving parity block to be regenerated
+    let survivors = [1, 2, 3, 4, 5]
+        .iter()
+        .map(|&i| (i, stripe[i].as_slice()))
+        .collect::<Vec<_>>();
+    let produced = ec
+        .decode_target(&survivors, &[5, 0])
+        .expect("Decoding failed");
+    assert_eq!(
+        produced,
+        vec![(5, stripe[5].clone()), (0, stripe[0].clone())]
+    );
+
+    // nothing to produce
+    assert!(ec.decode_target(&survivors, &[]).unwrap().is_empty());
+
+    // not enough survivors
+    let res = ec.decode_target(&survivors[..K - 1], &[0]);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::TooManyErasures(..))
+    ));
+
+    // duplicated or out of range produce indices
+    let res = ec.decode_target(&survivors, &[0, 0]);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+    let res = ec.decode_target(&survivors, &[K + M]);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+}
+
+#[test]
+fn decode_readonly() {
+    use erasure_isa_l::Error;
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+
+    let data = make_rand_blk(K, BLOCK_LEN);
+    let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    let stripe = data.iter().chain(parity.iter()).collect::<Vec<_>>();
+
+    for erasures in [vec![0], vec![3, 1], vec![2, 5], vec![4, 5]] {
+        let survivors = (0..K + M)
+            .filter(|i| !erasures.contains(i))
+            .map(|i| (i, stripe[i].as_slice()))
+            .collect::<Vec<_>>();
+        let mut buffers = vec![vec![0xFF_u8; BLOCK_LEN]; erasures.len()];
+        let mut outputs = erasures
+            .iter()
+            .copied()
+            .zip(buffers.iter_mut().map(Vec::as_mut_slice))
+            .collect::<Vec<_>>();
+        ec.decode_readonly(&survivors, &mut outputs)
+            .expect("Decoding failed");
+        for (e, buffer) in erasures.iter().zip(&buffers) {
+            assert_eq!(buffer, stripe[*e]);
+        }
+    }
+
+    let survivors = (1..K + M)
+        .map(|i| (i, stripe[i].as_slice()))
+        .collect::<Vec<_>>();
+    // nothing to recover
+    ec.decode_readonly(&survivors, &mut []).unwrap();
+    // the output buffer of a wrong length is left untouched
+    let mut short = vec![0_u8; BLOCK_LEN - 1];
+    let res = ec.decode_readonly(&survivors, &mut [(0, &mut short)]);
+    assert!(matches!(res, Err(Error::InvalidArguments(_))));
+    assert!(short.iter().all(|b| *b == 0));
+    // duplicated outputs
+    let (mut a, mut b) = (vec![0_u8; BLOCK_LEN], vec![0_u8; BLOCK_LEN]);
+    let res = ec.decode_readonly(&survivors, &mut [(0, &mut a), (0, &mut b)]);
+    assert!(matches!(res, Err(Error::InvalidArguments(_))));
+    // not enough survivors
+    let res = ec.decode_readonly(&survivors[..K - 1], &mut [(0, &mut a)]);
+    assert!(matches!(res, Err(Error::TooManyErasures(..))));
+}
+
+#[test]
+fn degraded_read_cost() {
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+
+    assert_eq!(ec.degraded_read_cost(0, &[0]).unwrap(), 1);
+    assert_eq!(ec.degraded_read_cost(K, &[0, 1, K]).unwrap(), 1);
+    assert_eq!(ec.degraded_read_cost(0, &[1, 2, 3, 4, 5]).unwrap(), K);
+    // duplicated indices are counted once
+    let res = ec.degraded_read_cost(0, &[1, 1, 2, 3]);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::TooManyErasures(..))
+    ));
+    let res = ec.degraded_read_cost(K + M, &[0, 1, 2, 3]);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+    let res = ec.degraded_read_cost(0, &[1, 2, 3, K + M]);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+}
+
+#[test]
+fn try_recover_block() {
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_cauchy(k, m).unwrap();
+
+    let data = make_rand_blk(K, BLOCK_LEN);
+    let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    let stripe = data.iter().chain(parity.iter()).collect::<Vec<_>>();
+    let survivors_of = |indices: &[usize]| {
+        indices
+            .iter()
+            .map(|&i| (i, stripe[i].as_slice()))
+            .collect::<Vec<_>>()
+    };
+
+    // enough survivors recover any block
+    let survivors = survivors_of(&[1, 2, 3, 5]);
+    for (target, expected) in stripe.iter().enumerate() {
+        let block = ec.try_recover_block(&survivors, target).unwrap();
+        assert_eq!(block.as_ref(), Some(*expected));
+    }
+
+    // fewer survivors only recover the blocks they span
+    let survivors = survivors_of(&[0, 2, 4]);
+    assert_eq!(
+        ec.try_recover_block(&survivors, 2).unwrap().as_ref(),
+        Some(stripe[2])
+    );
+    assert_eq!(ec.try_recover_block(&survivors, 1).unwrap(), None);
+    assert_eq!(ec.try_recover_block(&survivors, 5).unwrap(), None);
+    assert_eq!(ec.try_recover_block(&[], 0).unwrap(), None);
+
+    // invalid arguments
+    let res = ec.try_recover_block(&survivors, K + M);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+    let res = ec.try_recover_block(&survivors_of(&[0, 0]), 1);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+}
+
+#[test]
+fn decode_mut_slice_views() {
+    let ec = ErasureCode::with_reed_solomon(
+        NonZeroUsize::new(K).unwrap(),
+        NonZeroUsize::new(M).unwrap(),
+    )
+    .unwrap();
+    // all the blocks live in one slab, and are handed out as `&mut [u8]` views
+    let mut slab = make_rand_blk(1, (K + M) * BLOCK_LEN).pop().unwrap();
+    let (data, parity) = slab.split_at_mut(K * BLOCK_LEN);
+    let data_views: Vec<&mut [u8]> = data.chunks_mut(BLOCK_LEN).collect();
+    let mut parity_views: Vec<&mut [u8]> = parity.chunks_mut(BLOCK_LEN).collect();
+    ec.encode(&data_views, &mut parity_views)
+        .expect("Encoding failed");
+    let orig = slab.clone();
+
+    slab[BLOCK_LEN..2 * BLOCK_LEN].fill(0);
+    slab[K * BLOCK_LEN..(K + 1) * BLOCK_LEN].fill(0);
+    let (data, parity) = slab.split_at_mut(K * BLOCK_LEN);
+    let mut data_views: Vec<&mut [u8]> = data.chunks_mut(BLOCK_LEN).collect();
+    let mut parity_views: Vec<&mut [u8]> = parity.chunks_mut(BLOCK_LEN).collect();
+    ec.decode(&mut data_views, &mut parity_views, [1, K])
+        .expect("Decoding failed");
+    let table = ec.make_decode_table([1, K]).unwrap();
+    ec.decode_with_table(&mut data_views, &mut parity_views, &table, [1, K])
+        .expect("Decoding failed");
+    assert_eq!(slab, orig);
+}
+
+#[test]
+fn decode_from_available() {
+    use erasure_isa_l::Error;
+    let ec = ErasureCode::with_cauchy(NonZeroUsize::new(K).unwrap(), NonZeroUsize::new(M).unwrap())
+        .unwrap();
+    let data = make_rand_blk(K, BLOCK_LEN);
+    let parity = ec.encode_to_owned(&data).unwrap();
+    // lose the first data block and the last parity block
+    let available = (1..K + M - 1).rev().chain([2]).collect::<Vec<_>>();
+    let mut erased_data = data.clone();
+    erased_data[0].fill(0);
+    let mut erased_parity = parity.clone();
+    erased_parity[M - 1].fill(0);
+    let res = ec.decode_from_available(&mut erased_data, &mut erased_parity, &available);
+    assert_eq!(res, Ok(vec![0, K + M - 1]));
+    assert_eq!(erased_data, data);
+    assert_eq!(erased_parity, parity);
+
+    // nothing to recover
+    let all = (0..K + M).collect::<Vec<_>>();
+    let res = ec.decode_from_available(&mut erased_data, &mut erased_parity, &all);
+    assert_eq!(res, Ok(vec![]));
+
+    let res = ec.decode_from_available(&mut erased_data, &mut erased_parity, &all[..K - 1]);
+    assert_eq!(res, Err(Error::TooManyErasures(M + 1, M)));
+    let res = ec.decode_from_available(&mut erased_data, &mut erased_parity, [K + M]);
+    assert_eq!(res, Err(Error::InvalidArguments(String::new())));
+}
+
+#[test]
+fn decode_subset() {
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_cauchy(k, m).unwrap();
+
+    let data = make_rand_blk(K, BLOCK_LEN);
+    let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    let erasures = [0, K + 1];
+    for wanted in [vec![], vec![0], vec![K + 1], vec![K + 1, 0, 0]] {
+        let mut erased_data = data.clone();
+        let mut erased_parity = parity.clone();
+        erased_data[0].fill(0);
+        erased_parity[1].fill(0);
+        let recovered = ec
+            .decode_subset(&mut erased_data, &mut erased_parity, erasures, &wanted)
+            .expect("Decoding failed");
+        let mut expected = wanted.clone();
+        expected.sort_unstable();
+        expected.dedup();
+        assert_eq!(recovered, expected);
+        // only the wanted blocks are recovered
+        assert_eq!(erased_data[0] == data[0], wanted.contains(&0));
+        assert_eq!(erased_parity[1] == parity[1], wanted.contains(&(K + 1)));
+    }
+
+    let mut data = data;
+    let mut parity = parity;
+    let res = ec.decode_subset(&mut data, &mut parity, erasures, [1]);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+}
+
+#[test]
+fn decode_data_only() {
+    use erasure_isa_l::Error;
+    let ec = ErasureCode::with_cauchy(NonZeroUsize::new(K).unwrap(), NonZeroUsize::new(M).unwrap())
+        .unwrap();
+    let data = make_rand_blk(K, BLOCK_LEN);
+    let parity = ec.encode_to_owned(&data).unwrap();
+
+    for erasures in [vec![0, K + 1], vec![K + 1, 2, 2], vec![K, K + 1], vec![]] {
+        let mut erased_data = data.clone();
+        let mut erased_parity = parity.clone();
+        erased_data
+            .iter_mut()
+            .chain(erased_parity.iter_mut())
+            .enumerate()
+            .filter(|(i, _)| erasures.contains(i))
+            .for_each(|(_, b)| b.fill(0));
+        let expected_parity = erased_parity.clone();
+        let recovered = ec
+            .decode_data_only(&mut erased_data, &mut erased_parity, &erasures)
+            .expect("Decoding failed");
+        let mut expected = erasures
+            .iter()
+            .copied()
+            .filter(|e| *e < K)
+            .collect::<Vec<_>>();
+        expected.dedup();
+        assert_eq!(recovered, expected);
+        assert_eq!(erased_data, data);
+        // the erased parity blocks are not repaired
+        assert_eq!(erased_parity, expected_parity);
+    }
+
+    let mut erased_data = data.clone();
+    let mut erased_parity = parity.clone();
+    let res = ec.decode_data_only(&mut erased_data, &mut erased_parity, [0, K, K + 1]);
+    assert_eq!(res, Err(Error::TooManyErasures(M + 1, M)));
+}
+
+#[test]
+fn decode_mixed_erasures() {
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M + 1).unwrap();
+    for ec in [
+        ErasureCode::with_reed_solomon(k, m).unwrap(),
+        ErasureCode::with_cauchy(k, m).unwrap(),
+    ] {
+        let data = make_rand_blk(K, BLOCK_LEN);
+        let parity = ec.encode_to_owned(&data).unwrap();
+        let n = K + M + 1;
+        for mask in 1_u32..(1 << n) {
+            let erasures = (0..n).filter(|i| mask & (1 << i) != 0).collect::<Vec<_>>();
+            if erasures.len() > M + 1 || erasures[0] >= K || erasures[erasures.len() - 1] < K {
+                continue;
+            }
+            let mut erased_data = data.clone();
+            let mut erased_parity = parity.clone();
+            erased_data
+                .iter_mut()
+                .chain(erased_parity.iter_mut())
+                .enumerate()
+                .filter(|(i, _)| erasures.contains(i))
+                .for_each(|(_, b)| b.fill(0));
+            // the matrix path derives the rows of all the erased blocks from the inverted matrix
+            let mut matrix_data = erased_data.clone();
+            let mut matrix_parity = erased_parity.clone();
+            ec.decode_subset(&mut matrix_data, &mut matrix_parity, &erasures, &erasures)
+                .expect("Decoding failed");
+            ec.decode(&mut erased_data, &mut erased_parity, &erasures)
+                .expect("Decoding failed");
+            assert_eq!(erased_data, matrix_data, "erasures {:?}", erasures);
+            assert_eq!(erased_parity, matrix_parity, "erasures {:?}", erasures);
+            assert_eq!(erased_data, data);
+            assert_eq!(erased_parity, parity);
+        }
+    }
+}
 
-    // Further tests can be added here to validate encoding and decoding
-    // Prepare input data: k blocks of BLOCK_LEN bytes
-    let data: Vec<Vec<u8>> = (0..k.get()).map(|i| vec![i as u8; BLOCK_LEN]).collect();
+#[test]
+fn combine_parity() {
+    use erasure_isa_l::Error;
+    let ec = ErasureCode::with_reed_solomon(
+        NonZeroUsize::new(K).unwrap(),
+        NonZeroUsize::new(M).unwrap(),
+    )
+    .unwrap();
+    let a = make_rand_blk(K, BLOCK_LEN);
+    let b = make_rand_blk(K, BLOCK_LEN);
+    let a_code = ec.encode_to_owned(&a).unwrap();
+    let b_code = ec.encode_to_owned(&b).unwrap();
+    let xored = a
+        .iter()
+        .zip(&b)
+        .map(|(a, b)| a.iter().zip(b).map(|(a, b)| a ^ b).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let mut combined = make_zero_blk(M, BLOCK_LEN);
+    ec.combine_parity(&a_code, &b_code, &mut combined).unwrap();
+    assert_eq!(combined, ec.encode_to_owned(&xored).unwrap());
 
-    // Prepare parity blocks (m blocks of BLOCK_LEN bytes, initially zeroed)
-    let mut parity: Vec<Vec<u8>> = vec![vec![0u8; BLOCK_LEN]; m.get()];
+    let res = ec.combine_parity(&a_code, &b_code[..1], &mut combined);
+    assert_eq!(res, Err(Error::InvalidArguments(String::new())));
+    let mut short = make_zero_blk(M, BLOCK_LEN / 2);
+    let res = ec.combine_parity(&a_code, &b_code, &mut short);
+    assert_eq!(res, Err(Error::InvalidArguments(String::new())));
+}
 
-    // Encode: fill parity blocks
-    code.encode(&data, &mut parity).expect("Encoding failed");
+#[test]
+fn decode_no_erasure() {
+    use erasure_isa_l::Error;
+    let ec = ErasureCode::with_reed_solomon(
+        NonZeroUsize::new(K).unwrap(),
+        NonZeroUsize::new(M).unwrap(),
+    )
+    .unwrap();
+    let mut data = make_rand_blk(K, BLOCK_LEN);
+    let mut parity = ec.encode_to_owned(&data).unwrap();
+    let (orig_data, orig_parity) = (data.clone(), parity.clone());
+    assert_eq!(ec.decode(&mut data, &mut parity, []), Ok(vec![]));
+    let table = ec.make_decode_table([]).unwrap();
+    assert_eq!(
+        ec.decode_with_table(&mut data, &mut parity, &table, []),
+        Ok(vec![])
+    );
+    assert_eq!(
+        ec.decode_len(&mut data, &mut parity, [], BLOCK_LEN),
+        Ok(vec![])
+    );
+    assert_eq!(data, orig_data);
+    assert_eq!(parity, orig_parity);
 
-    // Erase some blocks
-    let erausure = [2, 5];
-    let mut erased_data: Vec<Vec<u8>> = data.clone();
-    erased_data.get_mut(2).unwrap().fill(0);
-    let mut erased_parity: Vec<Vec<u8>> = parity.clone();
-    erased_parity.get_mut(1).unwrap().fill(0);
+    // the buffers are still validated
+    let res = ec.decode(&mut data[1..], &mut parity, []);
+    assert_eq!(res, Err(Error::InvalidArguments(String::new())));
+    data[0].push(0);
+    let res = ec.decode(&mut data, &mut parity, []);
+    assert_eq!(res, Err(Error::InvalidArguments(String::new())));
+}
 
-    // Decode
-    code.decode(&mut erased_data, &mut erased_parity, erausure.to_vec())
-        .expect("Decoding failed");
+#[test]
+fn decode_batch() {
+    use erasure_isa_l::Error;
+    let ec = ErasureCode::with_cauchy(NonZeroUsize::new(K).unwrap(), NonZeroUsize::new(M).unwrap())
+        .unwrap();
+    let orig = [BLOCK_LEN, 2 * BLOCK_LEN, 1]
+        .into_iter()
+        .map(|len| {
+            let data = make_rand_blk(K, len);
+            let parity = ec.encode_to_owned(&data).unwrap();
+            (data, parity)
+        })
+        .collect::<Vec<_>>();
+    for erasures in [vec![0, K + 1], vec![K], vec![]] {
+        let mut stripes = orig.clone();
+        for (data, parity) in &mut stripes {
+            data.iter_mut()
+                .chain(parity.iter_mut())
+                .enumerate()
+                .filter(|(i, _)| erasures.contains(i))
+                .for_each(|(_, b)| b.fill(0));
+        }
+        assert_eq!(ec.decode_batch(&mut stripes, &erasures), Ok(erasures));
+        assert_eq!(stripes, orig);
+    }
 
-    // Check that recovered data matches original
-    assert_eq!(erased_parity, parity);
-    assert_eq!(erased_data, data);
+    // an invalid stripe leaves all the stripes untouched
+    let mut stripes = orig.clone();
+    stripes[0].0[0].fill(0);
+    stripes[2].1.pop();
+    let res = ec.decode_batch(&mut stripes, [0]);
+    assert_eq!(res, Err(Error::InvalidArguments(String::new())));
+    assert_eq!(stripes[0].0[0], vec![0; BLOCK_LEN]);
 }
 
 #[test]
-fn general_test_ec() {
-    use erasure_isa_l::erasure::ErasureCode;
-    let k_m_pair: [(usize, usize); 5] = [(4, 2), (6, 2), (9, 3), (10, 4), (16, 4)];
-    for (k, m) in k_m_pair {
-        let k = NonZeroUsize::new(k).unwrap();
-        let m = NonZeroUsize::new(m).unwrap();
-        let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
-        general_test(ec).expect("General test failed for Reed-Solomon code");
+fn encode_decode_stripe() {
+    use erasure_isa_l::Error;
+    let ec = ErasureCode::with_cauchy(NonZeroUsize::new(K).unwrap(), NonZeroUsize::new(M).unwrap())
+        .unwrap();
+    let data = make_rand_blk(K, BLOCK_LEN);
+    let parity = ec.encode_to_owned(&data).unwrap();
+    let mut stripe = data.clone();
+    stripe.extend(make_zero_blk(M, BLOCK_LEN));
+    ec.encode_stripe(&mut stripe).expect("Encoding failed");
+    assert_eq!(stripe[..K], data);
+    assert_eq!(stripe[K..], parity);
+
+    let orig = stripe.clone();
+    stripe[0].fill(0);
+    stripe[K + 1].fill(0);
+    assert_eq!(
+        ec.decode_stripe(&mut stripe, [K + 1, 0]),
+        Ok(vec![0, K + 1])
+    );
+    assert_eq!(stripe, orig);
+
+    let res = ec.encode_stripe(&mut stripe[1..]);
+    assert_eq!(res, Err(Error::InvalidArguments(String::new())));
+    let res = ec.decode_stripe(&mut stripe[..K], [0]);
+    assert_eq!(res, Err(Error::InvalidArguments(String::new())));
+}
+
+#[test]
+fn encode_iter() {
+    use erasure_isa_l::Error;
+    let ec = ErasureCode::with_cauchy(NonZeroUsize::new(K).unwrap(), NonZeroUsize::new(M).unwrap())
+        .unwrap();
+    let data = make_rand_blk(K, BLOCK_LEN);
+    let parity = ec.encode_to_owned(&data).unwrap();
+    let blocks = ec.encode_iter(&data).collect::<Result<Vec<_>, _>>();
+    assert_eq!(blocks, Ok(parity));
+
+    // the iterator ends after the first error
+    let mut iter = ec.encode_iter(&data[1..]);
+    assert_eq!(
+        iter.next(),
+        Some(Err(Error::InvalidArguments(String::new())))
+    );
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+fn encode_interleaved() {
+    use erasure_isa_l::Error;
+    let ec = ErasureCode::with_cauchy(NonZeroUsize::new(K).unwrap(), NonZeroUsize::new(M).unwrap())
+        .unwrap();
+    let interleave = |blocks: &[Vec<u8>], stride: usize| -> Vec<u8> {
+        let len = blocks[0].len();
+        (0..len / stride)
+            .flat_map(|r| {
+                blocks
+                    .iter()
+                    .flat_map(move |b| &b[r * stride..(r + 1) * stride])
+            })
+            .copied()
+            .collect()
+    };
+    for (stride, len) in [
+        (1, BLOCK_LEN),
+        (3, 3 * 5000),
+        (64, 64 * 100),
+        (10_000, 20_000),
+    ] {
+        let data = make_rand_blk(K, len);
+        let parity = ec.encode_to_owned(&data).unwrap();
+        let mut code_buf = vec![0_u8; len * M];
+        ec.encode_interleaved(&interleave(&data, stride), stride, &mut code_buf)
+            .expect("Encoding failed");
+        assert_eq!(code_buf, interleave(&parity, stride), "stride {}", stride);
     }
 
-    for (k, m) in k_m_pair {
-        let k = NonZeroUsize::new(k).unwrap();
-        let m = NonZeroUsize::new(m).unwrap();
-        let ec = ErasureCode::with_cauchy(k, m).unwrap();
-        general_test(ec).expect("General test failed for Cauchy code");
+    let buf = [0_u8; K * BLOCK_LEN];
+    let mut code_buf = [0_u8; M * BLOCK_LEN];
+    for (buf_len, stride, code_len) in [
+        (K * BLOCK_LEN, 0, M * BLOCK_LEN),
+        (K * BLOCK_LEN - 1, 1, M * BLOCK_LEN),
+        (K * BLOCK_LEN, 3, M * BLOCK_LEN),
+        (K * BLOCK_LEN, 1, M * BLOCK_LEN - 1),
+        (K * BLOCK_LEN, usize::MAX, M * BLOCK_LEN),
+    ] {
+        let res = ec.encode_interleaved(&buf[..buf_len], stride, &mut code_buf[..code_len]);
+        assert_eq!(res, Err(Error::InvalidArguments(String::new())));
     }
+    // the scratch is bounded by the buffers instead of the stride
+    assert_eq!(ec.encode_interleaved(&[], usize::MAX / K, &mut []), Ok(()));
 }
 
 #[test]
-fn decode_table() {
-    use erasure_isa_l::erasure::ErasureCode;
+fn decode_best_effort() {
+    let n = NonZeroUsize::new(6).unwrap();
+    let ec = ErasureCode::with_vandermonde(n, n).unwrap();
+    let data = make_rand_blk(6, BLOCK_LEN);
+    let parity = ec.encode_to_owned(&data).unwrap();
+    let patterns = |erased: u32| {
+        (0_usize..(1 << 12))
+            .filter(move |mask| mask.count_ones() == erased)
+            .map(|mask| (0..12).filter(|i| mask & (1 << i) != 0).collect::<Vec<_>>())
+    };
+    let check = |erasures: &[usize], complete: bool| {
+        let (mut d, mut p) = (data.clone(), parity.clone());
+        for i in erasures {
+            let block = if *i < 6 { &mut d[*i] } else { &mut p[*i - 6] };
+            block.fill(0);
+        }
+        let outcome = ec.decode_best_effort(&mut d, &mut p, erasures).unwrap();
+        assert_eq!(outcome.is_complete(), complete, "{erasures:?}");
+        let mut all = [outcome.recovered(), outcome.unrecovered()].concat();
+        all.sort_unstable();
+        assert_eq!(all, erasures);
+        for (i, (block, orig)) in d
+            .iter()
+            .chain(&p)
+            .zip(data.iter().chain(&parity))
+            .enumerate()
+        {
+            if outcome.unrecovered().contains(&i) {
+                assert!(block.iter().all(|b| *b == 0));
+            } else {
+                assert_eq!(block, orig, "block {i} of {erasures:?}");
+            }
+        }
+        outcome
+    };
+
+    // another selection of the survivors recovers the erasures where `decode` fails
+    let singular = patterns(5).find(|p| !ec.is_recoverable(p)).unwrap();
+    check(&singular, true);
+    // with no alternative selection, the erasures are reported as unrecovered
+    let singular = patterns(6).find(|p| !ec.is_recoverable(p)).unwrap();
+    check(&singular, false);
+    assert_eq!(check(&[0, 7], true).recovered(), &[0, 7]);
+
+    // both parities only cover the first two blocks, erasing 0 and 2 leaves 0 recoverable
     let k = NonZeroUsize::new(K).unwrap();
     let m = NonZeroUsize::new(M).unwrap();
-    let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    #[rustfmt::skip]
+    let matrix = [
+        1, 0, 0, 0,
+        0, 1, 0, 0,
+        0, 0, 1, 0,
+        0, 0, 0, 1,
+        1, 1, 0, 0,
+        2, 2, 0, 0,
+    ];
+    let ec = ErasureCode::with_matrix(k, m, &matrix).unwrap();
+    let data = make_rand_blk(K, BLOCK_LEN);
+    let mut parity = ec.encode_to_owned(&data).unwrap();
+    let mut erased = data.clone();
+    erased[0].fill(0);
+    erased[2].fill(0);
+    let outcome = ec
+        .decode_best_effort(&mut erased, &mut parity, [2, 0])
+        .unwrap();
+    assert_eq!(outcome.recovered(), &[0]);
+    assert_eq!(outcome.unrecovered(), &[2]);
+    assert_eq!(erased[0], data[0]);
+    assert!(erased[2].iter().all(|b| *b == 0));
+}
 
-    let orig_data = make_rand_blk(K, BLOCK_LEN);
-    let mut orig_parity = make_zero_blk(M, BLOCK_LEN);
-    ec.encode(&orig_data, &mut orig_parity)
-        .expect("Encoding failed");
-    let orig_parity = orig_parity;
+#[test]
+fn encode_autopad() {
+    use erasure_isa_l::Error;
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_cauchy(k, m).unwrap();
+    assert_eq!(ec.optimal_block_len(0), 64);
+    assert_eq!(ec.optimal_block_len(64), 64);
+    assert_eq!(ec.optimal_block_len(65), 128);
 
-    let erasures_list = [vec![2], vec![5], vec![0, 4]];
-    for erasures in &erasures_list {
-        let mut data = orig_data.clone();
-        let mut parity = orig_parity.clone();
-        // Set erased blocks to zero
-        for &e in erasures {
-            if e < K {
-                data[e] = vec![0_u8; BLOCK_LEN];
-            } else {
-                parity[e - K] = vec![0_u8; BLOCK_LEN];
+    for len in [1, BLOCK_LEN, BLOCK_LEN + 1] {
+        let data = make_rand_blk(K, len);
+        let mut padded = data.clone();
+        let (parity, real_len) = ec.encode_autopad(&mut padded).unwrap();
+        assert_eq!(real_len, len);
+        for (block, orig) in padded.iter().zip(&data) {
+            assert_eq!(block.len(), ec.optimal_block_len(len));
+            assert_eq!(&block[..len], orig.as_slice());
+            assert!(block[len..].iter().all(|b| *b == 0));
+        }
+        assert_eq!(parity, ec.encode_to_owned(&padded).unwrap());
+    }
+
+    let mut uneven = make_rand_blk(K, BLOCK_LEN);
+    uneven[1].pop();
+    let res = ec.encode_autopad(&mut uneven);
+    assert_eq!(res, Err(Error::InvalidArguments(String::new())));
+    let res = ec.encode_autopad(make_rand_blk(K - 1, BLOCK_LEN));
+    assert_eq!(res, Err(Error::InvalidArguments(String::new())));
+}
+
+#[test]
+fn from_parts() {
+    use erasure_isa_l::Error;
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let template = ErasureCode::with_reed_solomon(k, m).unwrap();
+    assert_eq!(template.encode_gf_table().len(), template.gf_table_len());
+    let matrix = template.encode_matrix().to_vec();
+    let gf_table = template.encode_gf_table().to_vec();
+    let kind = template.matrix_kind();
+    let ec = ErasureCode::from_parts(k, m, matrix.clone(), gf_table.clone(), kind).unwrap();
+    assert!(ec.compatible_with(&template));
+    assert_eq!(ec.key(), template.key());
+    general_test(ec).expect("General test failed for a codec from parts");
+    let ec = ErasureCode::from_parts(k, m, matrix.clone(), gf_table.clone(), None).unwrap();
+    assert_eq!(ec.matrix_kind(), None);
+
+    let res = ErasureCode::from_parts(k, m, matrix.clone(), gf_table[1..].to_vec(), kind);
+    assert!(matches!(res, Err(Error::InvalidArguments(_))));
+    let res = ErasureCode::from_parts(k, m, matrix[1..].to_vec(), gf_table.clone(), kind);
+    assert!(matches!(res, Err(Error::InvalidArguments(_))));
+    let mut not_systematic = matrix.clone();
+    not_systematic[1] = 1;
+    let res = ErasureCode::from_parts(k, m, not_systematic, gf_table.clone(), kind);
+    assert!(matches!(res, Err(Error::InvalidArguments(_))));
+    // the matrix must be the one of the kind
+    let cauchy = Some(erasure_isa_l::erasure::MatrixKind::Cauchy);
+    let res = ErasureCode::from_parts(k, m, matrix, gf_table, cauchy);
+    assert!(matches!(res, Err(Error::InvalidArguments(_))));
+}
+
+#[test]
+fn codec_key() {
+    use erasure_isa_l::erasure::{CodecKey, MatrixKind};
+    use std::collections::HashSet;
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let rs = ErasureCode::with_reed_solomon(k, m).unwrap();
+    let cauchy = ErasureCode::with_cauchy(k, m).unwrap();
+    let custom = ErasureCode::with_matrix(k, m, rs.encode_matrix()).unwrap();
+    let other = ErasureCode::with_matrix(k, m, cauchy.encode_matrix()).unwrap();
+    assert_eq!(rs.matrix_kind(), Some(MatrixKind::ReedSolomon));
+    assert_eq!(cauchy.matrix_kind(), Some(MatrixKind::Cauchy));
+    assert_eq!(custom.matrix_kind(), None);
+    assert_eq!(rs.key(), CodecKey::new(K, M, Some(MatrixKind::ReedSolomon)));
+    assert_eq!(
+        rs.key(),
+        ErasureCode::with_reed_solomon(k, m).unwrap().key()
+    );
+    assert_eq!(custom.key().source_num(), K);
+    assert_eq!(custom.key().code_num(), M);
+    assert_eq!(custom.key().matrix_kind(), None);
+    assert_ne!(custom.key(), CodecKey::new(K, M, None));
+    // the keys of user supplied matrices tell the matrices apart
+    assert_ne!(custom.key(), other.key());
+    assert_eq!(
+        custom.key(),
+        ErasureCode::with_matrix(k, m, rs.encode_matrix())
+            .unwrap()
+            .key()
+    );
+    let keys = [rs.key(), cauchy.key(), custom.key(), other.key(), rs.key()]
+        .into_iter()
+        .collect::<HashSet<_>>();
+    assert_eq!(keys.len(), 4);
+}
+
+#[test]
+fn decode_with_stats() {
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    let data = make_rand_blk(K, BLOCK_LEN);
+    let parity = ec.encode_to_owned(&data).unwrap();
+    for erasures in [vec![0], vec![1, 4], vec![2, 3], vec![4, 5]] {
+        let mut erased_data = data.clone();
+        let mut erased_parity = parity.clone();
+        for &e in &erasures {
+            match e {
+                e if e < K => erased_data[e].fill(0),
+                e => erased_parity[e - K].fill(0),
             }
         }
-        let table = ec
-            .make_decode_table(erasures.to_vec())
-            .expect("Failed to get decode table");
-        ec.decode_with_table(&mut data, &mut parity, &table, erasures.to_vec())
+        let (recovered, stats) = ec
+            .decode_with_stats(&mut erased_data, &mut erased_parity, &erasures)
+            .unwrap();
+        assert_eq!(recovered, erasures);
+        assert_eq!(erased_data, data);
+        assert_eq!(erased_parity, parity);
+        assert_eq!(stats.bytes_processed(), (BLOCK_LEN * erasures.len()) as u64);
+    }
+    // nothing to recover
+    let (mut data_copy, mut parity_copy) = (data.clone(), parity.clone());
+    let (recovered, stats) = ec
+        .decode_with_stats(&mut data_copy, &mut parity_copy, [])
+        .unwrap();
+    assert!(recovered.is_empty());
+    assert_eq!(stats, Default::default());
+    assert_eq!(stats.table_gen_ns(), 0);
+    assert_eq!(stats.compute_ns(), 0);
+}
+
+#[test]
+fn decode_alloc() {
+    use erasure_isa_l::Error;
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    let data = make_rand_blk(K, BLOCK_LEN);
+    let parity = ec.encode_to_owned(&data).unwrap();
+    let blocks = data.iter().chain(&parity).collect::<Vec<_>>();
+    for erasures in [
+        vec![],
+        vec![0],
+        vec![3, 1],
+        vec![2, 4],
+        vec![5, 4],
+        vec![1, 1],
+    ] {
+        let survivors = (0..K + M)
+            .filter(|i| !erasures.contains(i))
+            .map(|i| (i, blocks[i].as_slice()))
+            .collect::<Vec<_>>();
+        let mut expected = erasures
+            .iter()
+            .map(|&i| (i, blocks[i].clone()))
+            .collect::<Vec<_>>();
+        expected.sort();
+        expected.dedup();
+        assert_eq!(ec.decode_alloc(&survivors, &erasures).unwrap(), expected);
+    }
+    let survivors = (1..K + M)
+        .map(|i| (i, blocks[i].as_slice()))
+        .collect::<Vec<_>>();
+    // an erased block must not survive
+    let res = ec.decode_alloc(&survivors, &[0, 1]);
+    assert!(matches!(res, Err(Error::InvalidArguments(_))));
+    // too many erasures
+    let res = ec.decode_alloc(&survivors[..K - 1], &[0]);
+    assert!(matches!(res, Err(Error::TooManyErasures(..))));
+    let res = ec.decode_alloc(&survivors[3..], &[0, 1, 2]);
+    assert!(matches!(res, Err(Error::TooManyErasures(..))));
+}
+
+#[test]
+fn decode_duplicated_erasures() {
+    use erasure_isa_l::Error;
+    use erasure_isa_l::cache::SharedDecodeCache;
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let mut ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    let data = make_rand_blk(K, BLOCK_LEN);
+    let parity = ec.encode_to_owned(&data).unwrap();
+    let cache = SharedDecodeCache::new();
+    // deduplicated down to no more than the code number
+    for erasures in [vec![2, 2, 2], vec![3, 2, 3, 3, 2], vec![5, 0, 5, 0, 0]] {
+        let mut expected = erasures.clone();
+        expected.sort();
+        expected.dedup();
+        let mut table = ec.make_decode_table(&erasures).unwrap();
+        assert_eq!(table.erasures(), expected);
+        ec.regenerate_decode_table(&mut table, &erasures).unwrap();
+        assert_eq!(table.erasures(), expected);
+        assert!(ec.is_recoverable(&erasures));
+        assert!(matches!(
+            ec.make_decode_table_strict(&erasures),
+            Err(Error::InvalidArguments(_))
+        ));
+        for cache_capacity in [0, 4] {
+            ec.set_cache_capacity(cache_capacity);
+            let (mut erased_data, mut erased_parity) = (data.clone(), parity.clone());
+            let recovered = ec
+                .decode(&mut erased_data, &mut erased_parity, &erasures)
+                .unwrap();
+            assert_eq!(recovered, expected);
+        }
+        // the table of the duplicated erasures matches the deduplicated ones, and the other way around
+        let (mut erased_data, mut erased_parity) = (data.clone(), parity.clone());
+        let recovered = ec
+            .decode_with_table(&mut erased_data, &mut erased_parity, &table, &expected)
+            .unwrap();
+        assert_eq!(recovered, expected);
+        let table = ec.make_decode_table(&expected).unwrap();
+        let recovered = ec
+            .decode_with_table(&mut erased_data, &mut erased_parity, &table, &erasures)
+            .unwrap();
+        assert_eq!(recovered, expected);
+        let recovered = cache
+            .decode(&ec, &mut erased_data, &mut erased_parity, &erasures)
+            .unwrap();
+        assert_eq!(recovered, expected);
+        let owned = ec.decode_to_owned(&data, &parity, &erasures).unwrap();
+        assert_eq!(owned.iter().map(|(i, _)| *i).collect::<Vec<_>>(), expected);
+    }
+    // more distinct erasures than the code number, whatever the duplicates
+    for erasures in [vec![2, 3, 4], vec![2, 3, 2, 4, 3]] {
+        let (mut erased_data, mut erased_parity) = (data.clone(), parity.clone());
+        assert_eq!(
+            ec.decode(&mut erased_data, &mut erased_parity, &erasures),
+            Err(Error::TooManyErasures(3, M))
+        );
+        assert_eq!(
+            ec.make_decode_table(&erasures).err(),
+            Some(Error::TooManyErasures(3, M))
+        );
+        assert_eq!(
+            ec.decode_to_owned(&data, &parity, &erasures),
+            Err(Error::TooManyErasures(3, M))
+        );
+        assert!(!ec.is_recoverable(&erasures));
+    }
+    // the duplicated out-of-range index is still out of range
+    let res = ec.make_decode_table([2, K + M, K + M]);
+    assert!(matches!(res, Err(Error::InvalidArguments(_))));
+}
+
+#[test]
+fn parity_coefficients() {
+    use erasure_isa_l::Error;
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+    let data = make_rand_blk(K, BLOCK_LEN);
+    let parity = ec.encode_to_owned(&data).unwrap();
+    for (j, parity) in parity.iter().enumerate() {
+        let coefficients = ec.parity_coefficients(j).unwrap();
+        assert_eq!(
+            coefficients,
+            &ec.encode_matrix()[K * (K + j)..K * (K + j + 1)]
+        );
+        // the dot product of the coefficients and the source data is the parity
+        let expected = (0..BLOCK_LEN)
+            .map(|b| {
+                (0..K).fold(0, |acc, i| {
+                    acc ^ erasure_isa_l::gf::mul(coefficients[i], data[i][b])
+                })
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(&expected, parity);
+    }
+    assert!(matches!(
+        ec.parity_coefficients(M),
+        Err(Error::InvalidArguments(_))
+    ));
+}
+
+#[test]
+fn decode_to_owned() {
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_cauchy(k, m).unwrap();
+
+    let data = make_rand_blk(K, BLOCK_LEN);
+    let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+    let blocks = data.iter().chain(&parity).collect::<Vec<_>>();
+    // the erased blocks hold garbage, which is ignored
+    let mut erased_data = data.clone();
+    erased_data[1].fill(0xFF);
+    let mut erased_parity = parity.clone();
+    erased_parity[0].fill(0xFF);
+    let erasures = [K, 1, 1];
+    let recovered = ec
+        .decode_to_owned(&erased_data, &erased_parity, erasures)
+        .expect("Decoding failed");
+    assert_eq!(
+        recovered,
+        vec![(1, blocks[1].clone()), (K, blocks[K].clone())]
+    );
+    // the input buffers are untouched
+    assert_eq!(erased_data[1], vec![0xFF; BLOCK_LEN]);
+    assert_eq!(erased_parity[0], vec![0xFF; BLOCK_LEN]);
+
+    assert!(ec.decode_to_owned(&data, &parity, []).unwrap().is_empty());
+    let res = ec.decode_to_owned(&data[1..], &parity, [0]);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+}
+
+#[test]
+fn decode_resizing() {
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_cauchy(k, m).unwrap();
+
+    let data = make_rand_blk(K, BLOCK_LEN);
+    let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+
+    // empty and oversized erased buffers are resized
+    let mut erased_data = data.clone();
+    erased_data[0] = Vec::new();
+    erased_data[3] = vec![0; 2 * BLOCK_LEN];
+    let mut erased_parity = parity.clone();
+    ec.decode_resizing(&mut erased_data, &mut erased_parity, vec![3, 0])
+        .expect("Decoding failed");
+    assert_eq!(erased_data, data);
+    assert_eq!(erased_parity, parity);
+
+    // surviving blocks must agree on the length
+    let mut erased_data = data.clone();
+    erased_data[0] = Vec::new();
+    erased_data[1].pop();
+    let mut erased_parity = parity.clone();
+    let res = ec.decode_resizing(&mut erased_data, &mut erased_parity, vec![0]);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+}
+
+#[test]
+fn decode_matrix() {
+    use erasure_isa_l::gf;
+    let ec = ErasureCode::with_cauchy(NonZeroUsize::new(K).unwrap(), NonZeroUsize::new(M).unwrap())
+        .unwrap();
+    let data = make_rand_blk(K, BLOCK_LEN);
+    let parity = ec.encode_to_owned(&data).unwrap();
+    let blocks = data.iter().chain(&parity).collect::<Vec<_>>();
+    let erasures = [K, 1];
+    let matrix = ec.decode_matrix(&erasures).unwrap();
+    assert_eq!(matrix.len(), K * (K + M));
+    assert!(matrix[K * erasures.len()..].iter().all(|c| *c == 0));
+    let survivors = (0..K + M)
+        .filter(|i| !erasures.contains(i))
+        .take(K)
+        .collect::<Vec<_>>();
+    // the rows follow the sorted erasures
+    for (row, erasure) in matrix.chunks_exact(K).zip([1, K]) {
+        let recovered = (0..BLOCK_LEN)
+            .map(|b| {
+                row.iter()
+                    .zip(&survivors)
+                    .fold(0, |acc, (c, s)| acc ^ gf::mul(*c, blocks[*s][b]))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(&recovered, blocks[erasure]);
+    }
+
+    assert_eq!(
+        ec.decode_matrix(&[0, 1, 2]),
+        Err(erasure_isa_l::Error::TooManyErasures(3, M))
+    );
+    assert_eq!(
+        ec.decode_matrix(&[K + M]),
+        Err(erasure_isa_l::Error::InvalidArguments(String::new()))
+    );
+}
+
+#[test]
+fn is_recoverable() {
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_cauchy(k, m).unwrap();
+    for erasures in (0..K + M)
+        .map(|i| vec![i])
+        .chain([vec![0, K], vec![0, 0, K + M - 1]])
+    {
+        assert!(ec.is_recoverable(&erasures));
+    }
+    assert!(ec.is_recoverable(&[]));
+    assert!(!ec.is_recoverable(&(0..=M).collect::<Vec<_>>()));
+    assert!(!ec.is_recoverable(&[K + M]));
+
+    // a singular erasure pattern of a (k, m) code, whose decode matrix can not be inverted
+    let k = NonZeroUsize::new(4).unwrap();
+    let m = NonZeroUsize::new(2).unwrap();
+    let mut matrix = vec![0_u8; 6 * 4];
+    (0..4).for_each(|i| matrix[i * 4 + i] = 1);
+    matrix[16..20].copy_from_slice(&[1, 1, 0, 0]);
+    matrix[20..24].copy_from_slice(&[1, 1, 1, 1]);
+    let ec = ErasureCode::with_matrix(k, m, &matrix).unwrap();
+    assert!(!ec.is_recoverable(&[2, 3]));
+    assert!(ec.is_recoverable(&[0, 3]));
+}
+
+#[test]
+fn regenerate_decode_table() {
+    use erasure_isa_l::Error;
+    use erasure_isa_l::erasure::DecodeTable;
+    let ec = ErasureCode::with_reed_solomon(
+        NonZeroUsize::new(K).unwrap(),
+        NonZeroUsize::new(M).unwrap(),
+    )
+    .unwrap();
+    let data = make_rand_blk(K, BLOCK_LEN);
+    let parity = ec.encode_to_owned(&data).unwrap();
+
+    let mut table = DecodeTable::with_capacity(K, M);
+    assert!(table.erasures().is_empty());
+    for erasures in [vec![K + 1, 0], vec![3], vec![], vec![1, 2]] {
+        ec.regenerate_decode_table(&mut table, &erasures).unwrap();
+        let mut expected = erasures.clone();
+        expected.sort_unstable();
+        assert_eq!(table.erasures(), expected.as_slice());
+        let mut erased_data = data.clone();
+        let mut erased_parity = parity.clone();
+        erased_data
+            .iter_mut()
+            .chain(erased_parity.iter_mut())
+            .enumerate()
+            .filter(|(i, _)| erasures.contains(i))
+            .for_each(|(_, b)| b.fill(0));
+        ec.decode_with_table(&mut erased_data, &mut erased_parity, &table, &erasures)
             .expect("Decoding with table failed");
+        assert_eq!(erased_data, data);
+        assert_eq!(erased_parity, parity);
+    }
 
-        // Check that recovered data matches original
-        assert_eq!(data, orig_data);
-        assert_eq!(parity, orig_parity);
+    // the table is left unchanged on error
+    let res = ec.regenerate_decode_table(&mut table, [0, 1, 2]);
+    assert_eq!(res, Err(Error::TooManyErasures(3, M)));
+    assert_eq!(table.erasures(), &[1, 2]);
+    let mut other = DecodeTable::with_capacity(K + 1, M);
+    let res = ec.regenerate_decode_table(&mut other, [0]);
+    assert_eq!(res, Err(Error::InvalidArguments(String::new())));
+}
+
+#[test]
+fn decode_table_bytes() {
+    use erasure_isa_l::Error;
+    use erasure_isa_l::erasure::DecodeTable;
+    let ec = ErasureCode::with_reed_solomon(
+        NonZeroUsize::new(K).unwrap(),
+        NonZeroUsize::new(M).unwrap(),
+    )
+    .unwrap();
+    let data = make_rand_blk(K, BLOCK_LEN);
+    let parity = ec.encode_to_owned(&data).unwrap();
+    let table = ec.make_decode_table([K, 2]).unwrap();
+    assert_eq!(table.as_bytes().len(), 32 * K * 2);
+
+    let restored = DecodeTable::from_bytes(table.as_bytes().to_vec(), K, M, [2, K]).unwrap();
+    let mut erased_data = data.clone();
+    let mut erased_parity = parity.clone();
+    erased_data[2].fill(0);
+    erased_parity[0].fill(0);
+    ec.decode_with_table(&mut erased_data, &mut erased_parity, &restored, [2, K])
+        .expect("Decoding with table failed");
+    assert_eq!(erased_data, data);
+    assert_eq!(erased_parity, parity);
+
+    let bytes = table.as_bytes().to_vec();
+    for res in [
+        DecodeTable::from_bytes(bytes[..32].to_vec(), K, M, [2, K]),
+        DecodeTable::from_bytes(bytes.clone(), K, M, [K, 2]),
+        DecodeTable::from_bytes(bytes.clone(), K, M, [2, K + M]),
+        DecodeTable::from_bytes(bytes.clone(), K, 0, [2, K]),
+    ] {
+        assert!(matches!(res, Err(Error::InvalidArguments(_))));
+    }
+}
+
+#[test]
+fn make_decode_table_strict() {
+    use erasure_isa_l::Error;
+    let ec = ErasureCode::with_reed_solomon(
+        NonZeroUsize::new(K).unwrap(),
+        NonZeroUsize::new(M).unwrap(),
+    )
+    .unwrap();
+    let table = ec.make_decode_table_strict([K, 0]).unwrap();
+    assert_eq!(table.erasures(), &[0, K]);
+    // the lenient method accepts the duplicated indices
+    assert!(ec.make_decode_table([0, K, 0]).is_ok());
+    for erasures in [vec![0, K, 0], vec![K + M, K + M, 0]] {
+        let res = ec.make_decode_table_strict(&erasures);
+        assert!(
+            matches!(&res, Err(Error::InvalidArguments(msg)) if msg.contains("duplicated")),
+            "erasures {:?}",
+            erasures
+        );
     }
+    // only the indices out of range are reported
+    let res = ec.make_decode_table_strict([0, K + M]);
+    let expected = format!("erasure index out of range: {},", K + M);
+    assert!(matches!(res, Err(Error::InvalidArguments(msg)) if msg.starts_with(&expected)));
+    let res = ec.make_decode_table_strict([0, 1, 2]);
+    assert_eq!(res.err(), Some(Error::TooManyErasures(3, M)));
+}
+
+#[test]
+fn gf_table_len() {
+    use erasure_isa_l::ec;
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_cauchy(k, m).unwrap();
+    assert_eq!(ec.gf_table_len(), 32 * K * M);
+    assert_eq!(ec.decode_table_len(1), 32 * K);
+
+    // encode by the low-level API with the gf table sized by `gf_table_len`
+    let data = make_rand_blk(K, BLOCK_LEN);
+    let mut gf_tbls = vec![0_u8; ec.gf_table_len()];
+    ec::init_tables(
+        K as i32,
+        M as i32,
+        &ec.encode_matrix()[K * K..],
+        &mut gf_tbls,
+    );
+    let mut parity = make_zero_blk(M, BLOCK_LEN);
+    let data_ptrs = data.iter().map(|d| d.as_ptr()).collect::<Vec<_>>();
+    let mut parity_ptrs = parity
+        .iter_mut()
+        .map(|p| p.as_mut_ptr())
+        .collect::<Vec<_>>();
+    ec::encode_data(
+        BLOCK_LEN as i32,
+        K as i32,
+        M as i32,
+        &gf_tbls,
+        &data_ptrs,
+        &mut parity_ptrs,
+    );
+    assert_eq!(parity, ec.encode_to_owned(&data).unwrap());
+}
+
+#[test]
+fn error_eq() {
+    use erasure_isa_l::Error;
+    let ec = ErasureCode::with_cauchy(NonZeroUsize::new(K).unwrap(), NonZeroUsize::new(M).unwrap())
+        .unwrap();
+    let mut data = make_rand_blk(K, BLOCK_LEN);
+    let mut parity = make_zero_blk(M, BLOCK_LEN);
+    let res: erasure_isa_l::erasure::Result<Vec<usize>> =
+        ec.decode(&mut data, &mut parity, [0, 1, 2]);
+    assert_eq!(res, Err(Error::TooManyErasures(3, M)));
+    assert_ne!(res, Err(Error::TooManyErasures(3, M + 1)));
+
+    // the messages are not compared
+    let res = ec.decode(&mut data, &mut parity, [K + M]);
+    assert_eq!(res, Err(Error::InvalidArguments(String::new())));
+    assert_ne!(res, Err(Error::InternalError(String::new())));
+    assert_eq!(
+        Error::Io(std::io::ErrorKind::UnexpectedEof.into()),
+        Error::Io(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            "eof"
+        ))
+    );
 }
 
 #[test]
@@ -179,6 +2454,14 @@ fn fail_test() {
             Err(erasure_isa_l::Error::InvalidArguments(..))
         ));
     }
+    {
+        let no_data: [Vec<u8>; 0] = [];
+        let res = ec.encode_to_owned(no_data);
+        assert!(matches!(
+            res,
+            Err(erasure_isa_l::Error::InvalidArguments(..))
+        ));
+    }
 
     // Update
     {
@@ -227,8 +2510,8 @@ fn fail_test() {
         // too much erasures
         let mut erased_data = data.clone();
         let mut erased_parity = parity.clone();
-        let erasures = vec![0, 1, 2];
-        let res = ec.decode(&mut erased_data, &mut erased_parity, erasures.to_vec());
+        let erasures = [0, 1, 2];
+        let res = ec.decode(&mut erased_data, &mut erased_parity, erasures);
         assert!(matches!(
             res,
             Err(erasure_isa_l::Error::TooManyErasures(..))
@@ -238,9 +2521,9 @@ fn fail_test() {
         // buffer sizes mismatch
         let mut erased_data = data.clone();
         let mut erased_parity = parity.clone();
-        let erasures = vec![0, 1];
+        let erasures = [0, 1];
         erased_data[0].clear(); // Make data block empty
-        let res = ec.decode(&mut erased_data, &mut erased_parity, erasures.to_vec());
+        let res = ec.decode(&mut erased_data, &mut erased_parity, erasures);
         assert!(matches!(
             res,
             Err(erasure_isa_l::Error::InvalidArguments(..))
@@ -250,8 +2533,8 @@ fn fail_test() {
         // erasures out of bounds
         let mut erased_data = data.clone();
         let mut erased_parity = parity.clone();
-        let erasures = vec![10]; // 10 is out of bounds
-        let res = ec.decode(&mut erased_data, &mut erased_parity, erasures.to_vec());
+        let erasures = [10]; // 10 is out of bounds
+        let res = ec.decode(&mut erased_data, &mut erased_parity, erasures);
         assert!(matches!(
             res,
             Err(erasure_isa_l::Error::InvalidArguments(..))
@@ -261,21 +2544,13 @@ fn fail_test() {
         // not enough data blocks
         let mut erased_data = data.clone();
         let mut erased_parity = parity.clone();
-        let erasures = vec![0];
-        let res = ec.decode(
-            &mut erased_data[0..K - 1],
-            &mut erased_parity,
-            erasures.to_vec(),
-        );
+        let erasures = [0];
+        let res = ec.decode(&mut erased_data[0..K - 1], &mut erased_parity, erasures);
         assert!(matches!(
             res,
             Err(erasure_isa_l::Error::InvalidArguments(..))
         ));
-        let res = ec.decode(
-            &mut erased_data,
-            &mut erased_parity[0..M - 1],
-            erasures.to_vec(),
-        );
+        let res = ec.decode(&mut erased_data, &mut erased_parity[0..M - 1], erasures);
         assert!(matches!(
             res,
             Err(erasure_isa_l::Error::InvalidArguments(..))
@@ -285,8 +2560,8 @@ fn fail_test() {
     // DecodeTable
     {
         // too many erasures
-        let erasures = vec![0, 1, 2];
-        let res = ec.make_decode_table(erasures.to_vec());
+        let erasures = [0, 1, 2];
+        let res = ec.make_decode_table(erasures);
         assert!(matches!(
             res,
             Err(erasure_isa_l::Error::TooManyErasures(..))
@@ -294,8 +2569,8 @@ fn fail_test() {
     }
     {
         // erasures out of bounds
-        let erasures = vec![10]; // 10 is out of bounds
-        let res = ec.make_decode_table(erasures.to_vec());
+        let erasures = [10]; // 10 is out of bounds
+        let res = ec.make_decode_table(erasures);
         assert!(matches!(
             res,
             Err(erasure_isa_l::Error::InvalidArguments(..))
@@ -329,7 +2604,7 @@ fn general_test(ec: ErasureCode) -> Result<(), erasure_isa_l::Error> {
     let mut parity = make_zero_blk(m, blk_size);
     let data = orig_data.clone();
     for (i, update_data) in data.iter().enumerate() {
-        ec.update(i, &update_data, &mut parity)?;
+        ec.update(i, update_data, &mut parity)?;
     }
     assert_eq!(parity, orig_parity);
 
@@ -422,7 +2697,7 @@ fn general_test(ec: ErasureCode) -> Result<(), erasure_isa_l::Error> {
     let mut erasures = erasures.into_iter().collect::<Vec<_>>();
     erasures.sort_unstable();
     let (mut erased_data, mut erased_parity) = erased_stripe.split_at_mut(k);
-    ec.decode(&mut erased_data, &mut erased_parity, erasures.to_vec())?;
+    ec.decode(&mut erased_data, &mut erased_parity, erasures)?;
     assert_eq!(erased_data, orig_data);
     assert_eq!(erased_parity, orig_parity);
     Ok(())
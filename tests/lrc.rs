@@ -0,0 +1,125 @@
+use std::num::NonZeroUsize;
+
+use erasure_isa_l::Error;
+use erasure_isa_l::lrc::LrcCode;
+
+const BLOCK_LEN: usize = 64;
+const K: usize = 6;
+const L: usize = 2;
+const R: usize = 2;
+
+fn make_code() -> LrcCode {
+    LrcCode::new(
+        NonZeroUsize::new(K).unwrap(),
+        NonZeroUsize::new(L).unwrap(),
+        NonZeroUsize::new(R).unwrap(),
+    )
+    .unwrap()
+}
+
+fn make_blocks(code: &LrcCode) -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
+    let data = (0..K)
+        .map(|_| (0..BLOCK_LEN).map(|_| rand::random()).collect())
+        .collect::<Vec<Vec<u8>>>();
+    let mut parity = vec![vec![0_u8; BLOCK_LEN]; L + R];
+    code.encode(&data, &mut parity).unwrap();
+    (data, parity)
+}
+
+#[test]
+fn new() {
+    let nz = |n| NonZeroUsize::new(n).unwrap();
+    assert!(matches!(
+        LrcCode::new(nz(6), nz(4), nz(2)),
+        Err(Error::InvalidArguments(_))
+    ));
+    assert!(matches!(
+        LrcCode::new(nz(250), nz(5), nz(1)),
+        Err(Error::InvalidArguments(_))
+    ));
+    let code = make_code();
+    assert_eq!(code.group_len(), 3);
+    assert_eq!(code.block_num(), K + L + R);
+    assert_eq!(code.group_of(4), Some(1));
+    assert_eq!(code.group_of(K), Some(0));
+    assert_eq!(code.group_of(K + L), None);
+    assert_eq!(code.group_members(1), vec![3, 4, 5, K + 1]);
+}
+
+#[test]
+fn local_parity() {
+    let code = make_code();
+    let (mut data, parity) = make_blocks(&code);
+    // the local parity of a group does not depend on the other groups
+    data[0].iter_mut().for_each(|b| *b ^= 0xff);
+    let mut updated = vec![vec![0_u8; BLOCK_LEN]; L + R];
+    code.encode(&data, &mut updated).unwrap();
+    assert_ne!(updated[0], parity[0]);
+    assert_eq!(updated[1], parity[1]);
+    assert_ne!(updated[L], parity[L]);
+}
+
+#[test]
+fn local_repair() {
+    let code = make_code();
+    let (data, parity) = make_blocks(&code);
+    for lost in 0..K + L {
+        let (mut erased_data, mut erased_parity) = (data.clone(), parity.clone());
+        if lost < K {
+            erased_data[lost].fill(0);
+        } else {
+            erased_parity[lost - K].fill(0);
+        }
+        // corrupt a block out of the group, which a local repair never reads
+        let group = code.group_of(lost).unwrap();
+        let other = code.group_members(1 - group)[0];
+        erased_data[other].fill(0xaa);
+        assert_eq!(
+            code.decode(&mut erased_data, &mut erased_parity, [lost])
+                .unwrap(),
+            vec![lost]
+        );
+        erased_data[other].clone_from(&data[other]);
+        assert_eq!(erased_data, data);
+        assert_eq!(erased_parity, parity);
+    }
+}
+
+#[test]
+fn decode() {
+    let code = make_code();
+    let (data, parity) = make_blocks(&code);
+    let n = K + L + R;
+    let check = |erasures: &[usize]| {
+        let (mut erased_data, mut erased_parity) = (data.clone(), parity.clone());
+        for &i in erasures {
+            if i < K {
+                erased_data[i].fill(0);
+            } else if i < n {
+                erased_parity[i - K].fill(0);
+            }
+        }
+        let res = code.decode(&mut erased_data, &mut erased_parity, erasures);
+        if res.is_ok() {
+            assert_eq!(erased_data, data);
+            assert_eq!(erased_parity, parity);
+        }
+        res
+    };
+    // any R + 1 erasures are recoverable
+    for a in 0..n {
+        for b in a + 1..n {
+            for c in b + 1..n {
+                assert!(check(&[a, b, c]).is_ok(), "erasures {:?}", [a, b, c]);
+            }
+        }
+    }
+    // one erasure in each group plus R more erasures
+    assert!(check(&[0, 3, K + L, K + L + 1]).is_ok());
+    assert!(check(&[1, 2, 5, K]).is_ok());
+    // a group is repaired locally, then the other one by the global parities
+    assert!(check(&[0, 1, 2, 3]).is_ok());
+    // a whole group with its local parity is lost
+    assert_eq!(check(&[0, 1, 2, K]), Err(Error::TooManyErasures(4, R + 1)));
+    assert!(matches!(check(&[n]), Err(Error::InvalidArguments(_))));
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "rayon")]
+use std::num::NonZeroUsize;
+
+use erasure_isa_l::erasure::ErasureCode;
+
+const K: usize = 4;
+const M: usize = 2;
+
+#[test]
+fn encode_parallel() {
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_cauchy(k, m).unwrap();
+
+    for len in [0, 1, 31, 32, 1000, 64 * 1024 + 7] {
+        let data = (0..K)
+            .map(|_| rand::random_iter().take(len).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+        let mut parity = vec![vec![0_u8; len]; M];
+        ec.encode_parallel(&data, &mut parity)
+            .expect("Encoding failed");
+        assert_eq!(parity, ec.encode_to_owned(&data).unwrap());
+    }
+
+    // invalid buffers
+    let data = vec![vec![0_u8; 64]; K];
+    let mut parity = vec![vec![0_u8; 63]; M];
+    let res = ec.encode_parallel(&data, &mut parity);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+}
+
+#[test]
+fn decode_batch_parallel() {
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_reed_solomon(k, m).unwrap();
+
+    let orig = (0..64)
+        .map(|_| {
+            let data = (0..K)
+                .map(|_| rand::random_iter().take(1000).collect::<Vec<u8>>())
+                .collect::<Vec<_>>();
+            let parity = ec.encode_to_owned(&data).unwrap();
+            (data, parity)
+        })
+        .collect::<Vec<_>>();
+    let mut stripes = orig.clone();
+    for (data, parity) in &mut stripes {
+        data[1].fill(0);
+        parity[0].fill(0);
+    }
+    let recovered = ec
+        .decode_batch_parallel(&mut stripes, [K, 1])
+        .expect("Decoding failed");
+    assert_eq!(recovered, vec![1, K]);
+    assert_eq!(stripes, orig);
+}
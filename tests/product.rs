@@ -0,0 +1,88 @@
+use std::num::NonZeroUsize;
+
+use erasure_isa_l::erasure::ErasureCode;
+use erasure_isa_l::product::ProductCode;
+
+const BLOCK_LEN: usize = 64;
+
+fn make_code(row: (usize, usize), col: (usize, usize)) -> ProductCode {
+    let nz = |n| NonZeroUsize::new(n).unwrap();
+    ProductCode::new(
+        ErasureCode::with_cauchy(nz(row.0), nz(row.1)).unwrap(),
+        ErasureCode::with_cauchy(nz(col.0), nz(col.1)).unwrap(),
+    )
+}
+
+fn make_grid(code: &ProductCode) -> Vec<Vec<u8>> {
+    let mut grid = (0..code.block_num())
+        .map(|_| rand::random_iter().take(BLOCK_LEN).collect::<Vec<u8>>())
+        .collect::<Vec<_>>();
+    code.encode(&mut grid).expect("Encoding failed");
+    grid
+}
+
+fn erase(grid: &[Vec<u8>], cols: usize, erasures: &[(usize, usize)]) -> Vec<Vec<u8>> {
+    let mut grid = grid.to_vec();
+    for &(r, c) in erasures {
+        grid[r * cols + c].fill(0);
+    }
+    grid
+}
+
+#[test]
+fn encode() {
+    let code = make_code((3, 2), (2, 1));
+    let grid = make_grid(&code);
+    let cols = code.cols();
+    // every row and every column is a codeword
+    for row in grid.chunks(cols) {
+        let parity = code.row_code().encode_to_owned(&row[..3]).unwrap();
+        assert_eq!(&parity, &row[3..]);
+    }
+    for c in 0..cols {
+        let col = (0..code.rows())
+            .map(|r| grid[r * cols + c].clone())
+            .collect::<Vec<_>>();
+        let parity = code.col_code().encode_to_owned(&col[..2]).unwrap();
+        assert_eq!(&parity, &col[2..]);
+    }
+}
+
+#[test]
+fn decode() {
+    let code = make_code((3, 1), (3, 1));
+    let grid = make_grid(&code);
+    let cols = code.cols();
+
+    // the whole first row and another block in the first column:
+    // neither the row code nor the column code can recover it alone
+    let mut erasures = (0..cols).map(|c| (0, c)).collect::<Vec<_>>();
+    erasures.push((1, 0));
+    let mut erased = erase(&grid, cols, &erasures);
+    code.decode(&mut erased, &erasures)
+        .expect("Decoding failed");
+    assert_eq!(erased, grid);
+
+    // a whole column
+    let erasures = (0..code.rows()).map(|r| (r, 2)).collect::<Vec<_>>();
+    let mut erased = erase(&grid, cols, &erasures);
+    code.decode(&mut erased, &erasures)
+        .expect("Decoding failed");
+    assert_eq!(erased, grid);
+
+    // a 2 x 2 rectangle is unrecoverable
+    let erasures = [(0, 0), (0, 1), (1, 0), (1, 1)];
+    let mut erased = erase(&grid, cols, &erasures);
+    let res = code.decode(&mut erased, &erasures);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::TooManyErasures(4, 3))
+    ));
+
+    // out of range
+    let res = code.decode(&mut erased, &[(code.rows(), 0)]);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(..))
+    ));
+}
@@ -0,0 +1,117 @@
+use std::num::NonZeroUsize;
+
+use erasure_isa_l::erasure::ErasureCode;
+use erasure_isa_l::{gf, reference};
+use rand::RngExt;
+
+#[test]
+fn arithmetic() {
+    for a in 0..=u8::MAX {
+        for b in 0..=u8::MAX {
+            assert_eq!(reference::mul(a, b), gf::mul(a, b), "{a} * {b}");
+        }
+        assert_eq!(reference::inv(a), gf::inv(a), "1 / {a}");
+    }
+}
+
+#[test]
+fn field() {
+    assert_eq!(gf::FIELD_ORDER, 256);
+    assert_eq!(
+        gf::mul(gf::GENERATOR, 0x80),
+        (gf::PRIMITIVE_POLY & 0xFF) as u8
+    );
+    assert_eq!(gf::exp(0), 1);
+    assert_eq!(gf::exp(255), 1);
+    assert_eq!(gf::log(0), None);
+    // the generator spans all the non-zero elements
+    let mut seen = [false; gf::FIELD_ORDER];
+    for n in 0..u8::MAX {
+        let a = gf::exp(n);
+        assert!(!seen[a as usize]);
+        seen[a as usize] = true;
+        assert_eq!(gf::log(a), Some(n));
+    }
+    for a in 1..=u8::MAX {
+        for b in [1, 2, 3, 0x53, 0xFF] {
+            let (la, lb) = (gf::log(a).unwrap() as usize, gf::log(b).unwrap() as usize);
+            assert_eq!(gf::mul(a, b), gf::exp(((la + lb) % 255) as u8));
+        }
+        assert_eq!(
+            gf::inv(a),
+            gf::exp(((255 - gf::log(a).unwrap() as usize) % 255) as u8)
+        );
+    }
+}
+
+#[test]
+fn invert_matrix() {
+    let mut rng = rand::rng();
+    for n in 1..8 {
+        for _ in 0..64 {
+            let matrix = (0..n * n).map(|_| rng.random()).collect::<Vec<u8>>();
+            let mut input = matrix.clone();
+            let mut output = vec![0_u8; n * n];
+            let invertible = gf::invert_matrix(&mut input, &mut output, n as i32);
+            match reference::invert_matrix(&matrix, n) {
+                Some(inverse) => {
+                    assert!(invertible);
+                    assert_eq!(inverse, output);
+                }
+                None => assert!(!invertible),
+            }
+        }
+    }
+}
+
+#[test]
+fn cross_check() {
+    let mut rng = rand::rng();
+    for _ in 0..64 {
+        let k = rng.random_range(1..12);
+        let m = rng.random_range(1..5);
+        let len = rng.random_range(1..300);
+        let ec = if rng.random() {
+            ErasureCode::with_cauchy(NonZeroUsize::new(k).unwrap(), NonZeroUsize::new(m).unwrap())
+        } else {
+            ErasureCode::with_reed_solomon(
+                NonZeroUsize::new(k).unwrap(),
+                NonZeroUsize::new(m).unwrap(),
+            )
+        }
+        .unwrap();
+        let data = (0..k)
+            .map(|_| rand::random_iter().take(len).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+        let parity = ec.encode_to_owned(&data).unwrap();
+        let mut reference_parity = vec![vec![0_u8; len]; m];
+        ec.encode_reference(&data, &mut reference_parity)
+            .expect("Encoding failed");
+        assert_eq!(reference_parity, parity);
+
+        let erasures = (0..rng.random_range(1..=m))
+            .map(|_| rng.random_range(0..k + m))
+            .collect::<Vec<_>>();
+        let mut erased_data = data.clone();
+        let mut erased_parity = parity.clone();
+        erased_data
+            .iter_mut()
+            .chain(erased_parity.iter_mut())
+            .enumerate()
+            .filter(|(i, _)| erasures.contains(i))
+            .for_each(|(_, b)| b.fill(0));
+        let mut reference_data = erased_data.clone();
+        let mut reference_parity = erased_parity.clone();
+        let recovered = ec
+            .decode(&mut erased_data, &mut erased_parity, &erasures)
+            .expect("Decoding failed");
+        let reference_recovered = ec
+            .decode_reference(&mut reference_data, &mut reference_parity, &erasures)
+            .expect("Decoding failed");
+        assert_eq!(reference_recovered, recovered);
+        assert_eq!(reference_data, data);
+        assert_eq!(reference_parity, parity);
+        assert_eq!(erased_data, data);
+        assert_eq!(erased_parity, parity);
+    }
+}
@@ -0,0 +1,99 @@
+#![cfg(feature = "serde")]
+use std::num::NonZeroUsize;
+
+use erasure_isa_l::erasure::{DecodeTable, ErasureCode};
+
+const BLOCK_LEN: usize = 64;
+const K: usize = 4;
+const M: usize = 2;
+
+#[test]
+fn decode_table_serde() {
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let ec = ErasureCode::with_cauchy(k, m).unwrap();
+    let data = (0..K)
+        .map(|_| rand::random_iter().take(BLOCK_LEN).collect::<Vec<u8>>())
+        .collect::<Vec<_>>();
+    let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+
+    let erasures = vec![1, 4];
+    let table = ec.make_decode_table(erasures.clone()).unwrap();
+    let json = serde_json::to_string(&table).unwrap();
+    let table: DecodeTable = serde_json::from_str(&json).unwrap();
+
+    let mut erased_data = data.clone();
+    erased_data[1].fill(0);
+    let mut erased_parity = parity.clone();
+    erased_parity[0].fill(0);
+    ec.decode_with_table(&mut erased_data, &mut erased_parity, &table, erasures)
+        .expect("Decoding failed");
+    assert_eq!(erased_data, data);
+    assert_eq!(erased_parity, parity);
+
+    // a table can not be used with a mismatched codec
+    let other = ErasureCode::with_cauchy(NonZeroUsize::new(K + 1).unwrap(), m).unwrap();
+    let mut data = vec![vec![0_u8; BLOCK_LEN]; K + 1];
+    let mut parity = vec![vec![0_u8; BLOCK_LEN]; M];
+    let res = other.decode_with_table(&mut data, &mut parity, &table, vec![1, 5]);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+
+    // corrupted header
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value["magic"] = serde_json::json!([0, 0, 0, 0]);
+    assert!(serde_json::from_value::<DecodeTable>(value).is_err());
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value["version"] = serde_json::json!(u32::MAX);
+    assert!(serde_json::from_value::<DecodeTable>(value).is_err());
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value["table"].as_array_mut().unwrap().pop();
+    assert!(serde_json::from_value::<DecodeTable>(value).is_err());
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value["erasures"] = serde_json::json!([4, 1]);
+    assert!(serde_json::from_value::<DecodeTable>(value).is_err());
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value["erasures"] = serde_json::json!([1]);
+    assert!(serde_json::from_value::<DecodeTable>(value).is_err());
+}
+
+#[test]
+fn decode_table_serde_mismatched_matrix() {
+    let k = NonZeroUsize::new(K).unwrap();
+    let m = NonZeroUsize::new(M).unwrap();
+    let cauchy = ErasureCode::with_cauchy(k, m).unwrap();
+    let rs = ErasureCode::with_reed_solomon(k, m).unwrap();
+    let erasures = vec![1, 4];
+    let json = serde_json::to_string(&cauchy.make_decode_table(erasures.clone()).unwrap()).unwrap();
+    let table: DecodeTable = serde_json::from_str(&json).unwrap();
+
+    // a table of the same geometry but another matrix is rejected
+    let mut data = vec![vec![0_u8; BLOCK_LEN]; K];
+    let mut parity = vec![vec![0_u8; BLOCK_LEN]; M];
+    let res = rs.decode_with_table(&mut data, &mut parity, &table, erasures.clone());
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+
+    // so is a table of a user supplied matrix loaded into another one
+    let custom = ErasureCode::with_matrix(k, m, cauchy.encode_matrix()).unwrap();
+    let other = ErasureCode::with_matrix(k, m, rs.encode_matrix()).unwrap();
+    let json = serde_json::to_string(&custom.make_decode_table(erasures.clone()).unwrap()).unwrap();
+    let table: DecodeTable = serde_json::from_str(&json).unwrap();
+    custom
+        .decode_with_table(&mut data, &mut parity, &table, erasures.clone())
+        .expect("Decoding failed");
+    let res = other.decode_with_table(&mut data, &mut parity, &table, erasures);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+
+    // a corrupted matrix kind
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value["matrix_kind"] = serde_json::json!(u8::MAX);
+    assert!(serde_json::from_value::<DecodeTable>(value).is_err());
+}
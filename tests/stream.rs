@@ -0,0 +1,259 @@
+use std::io::{Read, Write};
+use std::num::NonZeroUsize;
+
+use erasure_isa_l::erasure::ErasureCode;
+use erasure_isa_l::stream::{
+    self, DecodeReader, EncodeWriter, FileStripeEncoder, ShardReader, ShardWriter,
+};
+
+const SHARD_LEN: usize = 64;
+const K: usize = 4;
+const M: usize = 2;
+/// The length of a shard record, including the header.
+const RECORD_LEN: usize = 16 + SHARD_LEN;
+
+fn make_ec() -> ErasureCode {
+    ErasureCode::with_cauchy(NonZeroUsize::new(K).unwrap(), NonZeroUsize::new(M).unwrap()).unwrap()
+}
+
+fn write_shards(ec: &ErasureCode, input: &[u8]) -> Vec<u8> {
+    let mut writer = ShardWriter::new(ec, SHARD_LEN, Vec::new()).unwrap();
+    // write in uneven pieces to cross the stripe boundaries
+    for piece in input.chunks(100) {
+        writer.write_all(piece).unwrap();
+    }
+    writer.finish().unwrap()
+}
+
+fn read_shards(ec: &ErasureCode, records: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut output = Vec::new();
+    ShardReader::new(ec, records).read_to_end(&mut output)?;
+    Ok(output)
+}
+
+#[test]
+fn shard_round_trip() {
+    let ec = make_ec();
+    let stripe_len = K * SHARD_LEN;
+    for len in [0, 1, stripe_len - 1, stripe_len, 3 * stripe_len + 17] {
+        let input = rand::random_iter().take(len).collect::<Vec<u8>>();
+        let records = write_shards(&ec, &input);
+        assert_eq!(records.len() % RECORD_LEN, 16 + 8);
+        assert_eq!(read_shards(&ec, &records).unwrap(), input);
+    }
+}
+
+#[test]
+fn shard_corruption() {
+    let ec = make_ec();
+    let input = rand::random_iter()
+        .take(2 * K * SHARD_LEN + 5)
+        .collect::<Vec<u8>>();
+    let records = write_shards(&ec, &input);
+    let record_offset = |stripe: usize, index: usize| ((stripe * (K + M)) + index) * RECORD_LEN;
+    let payload_offset = |stripe: usize, index: usize| record_offset(stripe, index) + 16;
+
+    // up to M corrupted shards per stripe are recovered
+    let mut corrupted = records.clone();
+    corrupted[payload_offset(0, 1)] ^= 0xFF;
+    corrupted[payload_offset(0, K)] ^= 0xFF;
+    corrupted[payload_offset(2, 0) + 3] ^= 0xFF;
+    assert_eq!(read_shards(&ec, &corrupted).unwrap(), input);
+
+    // a corrupted header is treated as an erasure too
+    let mut corrupted = records.clone();
+    corrupted[record_offset(1, 2) + 4] ^= 0xFF;
+    corrupted[record_offset(1, K) + 8] ^= 0xFF;
+    assert_eq!(read_shards(&ec, &corrupted).unwrap(), input);
+    // unless the shard length is not known yet
+    let mut corrupted = records.clone();
+    corrupted[record_offset(0, 0) + 4] ^= 0xFF;
+    let err = read_shards(&ec, &corrupted).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    // too many corrupted shards
+    let mut corrupted = records.clone();
+    (0..=M).for_each(|i| corrupted[payload_offset(1, i)] ^= 0xFF);
+    let err = read_shards(&ec, &corrupted).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    // missing trailer
+    let err = read_shards(&ec, &records[..records.len() - 24]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn shard_len_limit() {
+    let ec = make_ec();
+    let input = vec![7_u8; K * SHARD_LEN];
+    let records = write_shards(&ec, &input);
+    let mut output = Vec::new();
+    let mut reader = ShardReader::new(&ec, records.as_slice()).max_shard_len(SHARD_LEN - 1);
+    let err = reader.read_to_end(&mut output).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    // a huge length under a valid header crc is rejected before the allocation
+    let mut forged = records.clone();
+    forged[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+    let header_crc = crc32fast::hash(&forged[..12]);
+    forged[12..16].copy_from_slice(&header_crc.to_le_bytes());
+    let err = read_shards(&ec, &forged).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn encode_writer() {
+    let ec = make_ec();
+    let input = (0..1000).map(|i| (i * 7) as u8).collect::<Vec<_>>();
+    let mut stripes: Vec<Vec<Vec<u8>>> = Vec::new();
+    let mut writer = EncodeWriter::new(&ec, SHARD_LEN, |shards: &[&[u8]]| {
+        stripes.push(shards.iter().map(|s| s.to_vec()).collect());
+        Ok(())
+    })
+    .unwrap();
+    for piece in input.chunks(100) {
+        writer.write_all(piece).unwrap();
+    }
+    assert_eq!(writer.finish().unwrap(), input.len() as u64);
+
+    assert_eq!(stripes.len(), input.len().div_ceil(K * SHARD_LEN));
+    for stripe in &stripes {
+        assert_eq!(stripe.len(), K + M);
+        assert_eq!(stripe[K..], ec.encode_to_owned(&stripe[..K]).unwrap());
+    }
+    let mut output = stripes
+        .iter()
+        .flat_map(|s| s[..K].concat())
+        .collect::<Vec<_>>();
+    // the final stripe is padded with zeros
+    assert!(output[input.len()..].iter().all(|b| *b == 0));
+    output.truncate(input.len());
+    assert_eq!(output, input);
+}
+
+#[test]
+fn encode_writer_flush_and_drop() {
+    let ec = make_ec();
+    let mut stripes = 0;
+    {
+        let mut writer = EncodeWriter::new(&ec, SHARD_LEN, |shards: &[&[u8]]| {
+            assert_eq!(shards.len(), K + M);
+            stripes += 1;
+            Ok(())
+        })
+        .unwrap();
+        writer.write_all(&[1_u8; 10]).unwrap();
+        // flushing emits the partial stripe, and an empty one is not emitted
+        writer.flush().unwrap();
+        writer.flush().unwrap();
+        // dropping emits the final partial stripe
+        writer.write_all(&[2_u8; 10]).unwrap();
+    }
+    assert_eq!(stripes, 2);
+
+    let res = EncodeWriter::new(&ec, 0, |_: &[&[u8]]| Ok(()));
+    assert!(res.is_err());
+}
+
+/// Encodes the input by an `EncodeWriter` into a stream for each shard index.
+fn encode_streams(ec: &ErasureCode, input: &[u8]) -> Vec<Vec<u8>> {
+    let mut streams = vec![Vec::new(); ec.block_num()];
+    let mut writer = EncodeWriter::new(ec, SHARD_LEN, |shards: &[&[u8]]| {
+        streams
+            .iter_mut()
+            .zip(shards)
+            .for_each(|(s, shard)| s.extend_from_slice(shard));
+        Ok(())
+    })
+    .unwrap();
+    writer.write_all(input).unwrap();
+    writer.finish().unwrap();
+    streams
+}
+
+#[test]
+fn decode_reader() {
+    let ec = make_ec();
+    for len in [0, 1, K * SHARD_LEN, 1000] {
+        let input = (0..len).map(|i| (i * 13) as u8).collect::<Vec<_>>();
+        let streams = encode_streams(&ec, &input);
+        for available in [vec![0, 1, 2, 3], vec![0, 1, 2, 3, 4, 5], vec![5, 0, 4, 3]] {
+            let sources = available.iter().map(|&i| (i, streams[i].as_slice()));
+            let mut reader = DecodeReader::new(&ec, SHARD_LEN, sources, len as u64).unwrap();
+            let mut output = Vec::new();
+            reader.read_to_end(&mut output).unwrap();
+            assert_eq!(output, input);
+        }
+    }
+}
+
+#[test]
+fn decode_reader_failure() {
+    let ec = make_ec();
+    let input = vec![3_u8; 1000];
+    let streams = encode_streams(&ec, &input);
+    let source = |i: usize| (i, streams[i].as_slice());
+
+    // too many unavailable streams
+    let res = DecodeReader::new(&ec, SHARD_LEN, [1, 2, 3].map(source), 1000);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::TooManyErasures(3, M))
+    ));
+    // duplicated or out of range indices
+    let res = DecodeReader::new(&ec, SHARD_LEN, [0, 1, 2, 3, 3].map(source), 1000);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+    let res = DecodeReader::new(&ec, SHARD_LEN, [(K + M, &[][..])], 1000);
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+
+    // a shard stream ends early
+    let mut truncated = [1, 2, 3, 4].map(source);
+    truncated[3].1 = &streams[4][..SHARD_LEN];
+    let mut reader = DecodeReader::new(&ec, SHARD_LEN, truncated, 1000).unwrap();
+    let err = reader.read_to_end(&mut Vec::new()).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn file_stripe_encoder() {
+    let ec = make_ec();
+    let input = (0..1000).map(|i| (i * 7) as u8).collect::<Vec<_>>();
+    let dir = std::env::temp_dir().join(format!("erasure-isa-l-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let paths = (0..K + M)
+        .map(|i| dir.join(format!("shard.{i}")))
+        .collect::<Vec<_>>();
+    let encoder = FileStripeEncoder::create(&ec, SHARD_LEN, &paths).unwrap();
+    let mut sidecar = Vec::new();
+    assert_eq!(
+        encoder.encode(input.as_slice(), &mut sidecar).unwrap(),
+        1000
+    );
+    assert_eq!(stream::read_sidecar(sidecar.as_slice()).unwrap(), 1000);
+
+    // the shard files hold the same shards as an `EncodeWriter` produces
+    let shards = paths
+        .iter()
+        .map(|p| std::fs::read(p).unwrap())
+        .collect::<Vec<_>>();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(shards, encode_streams(&ec, &input));
+    let sources = [0, 2, 3, 5].map(|i| (i, shards[i].as_slice()));
+    let mut reader = DecodeReader::new(&ec, SHARD_LEN, sources, 1000).unwrap();
+    let mut output = Vec::new();
+    reader.read_to_end(&mut output).unwrap();
+    assert_eq!(output, input);
+
+    let mut writers = vec![Vec::new(); K + M - 1];
+    let res = FileStripeEncoder::new(&ec, SHARD_LEN, writers.iter_mut());
+    assert!(matches!(
+        res,
+        Err(erasure_isa_l::Error::InvalidArguments(_))
+    ));
+}
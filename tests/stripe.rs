@@ -0,0 +1,21 @@
+use erasure_isa_l::stripe::Stripe;
+
+#[test]
+fn split_join() {
+    for (len, k) in [(0, 3), (1, 3), (10, 1), (12, 4), (13, 4), (100, 7)] {
+        let input = (0..len).map(|i| i as u8).collect::<Vec<_>>();
+        let (shards, orig_len) = Stripe::split(&input, k);
+        assert_eq!(orig_len, len);
+        assert_eq!(shards.len(), k);
+        assert!(shards.iter().all(|s| s.len() == len.div_ceil(k)));
+        // the padding is zeros
+        assert!(shards.concat()[len..].iter().all(|b| *b == 0));
+        assert_eq!(Stripe::join(&shards, orig_len), input);
+    }
+}
+
+#[test]
+#[should_panic]
+fn split_zero_shards() {
+    Stripe::split(b"data", 0);
+}
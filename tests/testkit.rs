@@ -0,0 +1,36 @@
+#![cfg(feature = "testkit")]
+use std::num::NonZeroUsize;
+
+use erasure_isa_l::erasure::ErasureCode;
+use erasure_isa_l::testkit;
+
+#[test]
+fn round_trip() {
+    let mut rng = rand::rng();
+    for (k, m) in [(1, 1), (4, 2), (10, 4), (16, 4)] {
+        let k = NonZeroUsize::new(k).unwrap();
+        let m = NonZeroUsize::new(m).unwrap();
+        let cauchy = ErasureCode::with_cauchy(k, m).unwrap();
+        let custom = ErasureCode::with_matrix(k, m, cauchy.encode_matrix()).unwrap();
+        for ec in [
+            ErasureCode::with_reed_solomon(k, m).unwrap(),
+            cauchy,
+            custom,
+        ] {
+            for block_len in [1, 31, 1024] {
+                testkit::round_trip(&ec, block_len, &mut rng).expect("round trip failed");
+            }
+        }
+    }
+}
+
+#[test]
+fn round_trip_singular() {
+    // the parity rows are identical, so erasing two source blocks is not recoverable
+    let k = NonZeroUsize::new(2).unwrap();
+    let m = NonZeroUsize::new(2).unwrap();
+    let ec = ErasureCode::with_matrix(k, m, &[1, 0, 0, 1, 1, 1, 1, 1]).unwrap();
+    let mut rng = rand::rng();
+    let failed = (0..256).any(|_| testkit::round_trip(&ec, 64, &mut rng).is_err());
+    assert!(failed);
+}
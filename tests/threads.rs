@@ -0,0 +1,104 @@
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+
+use erasure_isa_l::erasure::ErasureCode;
+
+const K: usize = 4;
+const M: usize = 2;
+const BLOCK_LEN: usize = 1024;
+const THREADS: usize = 8;
+
+#[test]
+fn share_codec_between_threads() {
+    let mut ec = ErasureCode::with_reed_solomon(
+        NonZeroUsize::new(K).unwrap(),
+        NonZeroUsize::new(M).unwrap(),
+    )
+    .unwrap();
+    // the decode cache is the only interior mutability
+    ec.set_cache_capacity(4);
+    let ec = Arc::new(ec);
+
+    let handles = (0..THREADS)
+        .map(|t| {
+            let ec = Arc::clone(&ec);
+            std::thread::spawn(move || {
+                for round in 0..32 {
+                    let data = (0..K)
+                        .map(|_| rand::random_iter().take(BLOCK_LEN).collect::<Vec<u8>>())
+                        .collect::<Vec<_>>();
+                    let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+                    let erasures = [(t + round) % (K + M), (t + round + 1) % (K + M)];
+                    let mut erased_data = data.clone();
+                    let mut erased_parity = parity.clone();
+                    for &e in &erasures {
+                        match e {
+                            e if e < K => erased_data[e].fill(0),
+                            e => erased_parity[e - K].fill(0),
+                        }
+                    }
+                    ec.decode(&mut erased_data, &mut erased_parity, erasures)
+                        .expect("Decoding failed");
+                    assert_eq!(erased_data, data);
+                    assert_eq!(erased_parity, parity);
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    handles
+        .into_iter()
+        .for_each(|h| h.join().expect("thread panicked"));
+}
+
+#[test]
+fn share_decode_table_between_threads() {
+    let ec = ErasureCode::with_cauchy(NonZeroUsize::new(K).unwrap(), NonZeroUsize::new(M).unwrap())
+        .unwrap();
+    let table = ec.make_decode_table([0, K]).unwrap();
+    std::thread::scope(|s| {
+        for _ in 0..THREADS {
+            s.spawn(|| {
+                let data = (0..K)
+                    .map(|_| rand::random_iter().take(BLOCK_LEN).collect::<Vec<u8>>())
+                    .collect::<Vec<_>>();
+                let parity = ec.encode_to_owned(&data).expect("Encoding failed");
+                let mut erased_data = data.clone();
+                erased_data[0].fill(0);
+                let mut erased_parity = parity.clone();
+                erased_parity[0].fill(0);
+                ec.decode_with_table(&mut erased_data, &mut erased_parity, &table, [0, K])
+                    .expect("Decoding failed");
+                assert_eq!(erased_data, data);
+                assert_eq!(erased_parity, parity);
+            });
+        }
+    });
+}
+
+#[test]
+fn encode_context_per_thread() {
+    let ec = ErasureCode::with_cauchy(NonZeroUsize::new(K).unwrap(), NonZeroUsize::new(M).unwrap())
+        .unwrap();
+    std::thread::scope(|s| {
+        for _ in 0..THREADS {
+            s.spawn(|| {
+                let mut ctx = ec.encode_context();
+                assert!(std::ptr::eq(ctx.codec(), &ec));
+                let mut parity = vec![vec![0_u8; BLOCK_LEN]; M];
+                for _ in 0..32 {
+                    let data = (0..K)
+                        .map(|_| rand::random_iter().take(BLOCK_LEN).collect::<Vec<u8>>())
+                        .collect::<Vec<_>>();
+                    ctx.encode(&data, &mut parity).expect("Encoding failed");
+                    assert_eq!(parity, ec.encode_to_owned(&data).unwrap());
+                }
+                // the buffers are still checked
+                let res = ctx.encode(&parity, &mut vec![vec![0_u8; BLOCK_LEN]; M]);
+                assert!(matches!(
+                    res,
+                    Err(erasure_isa_l::Error::InvalidArguments(_))
+                ));
+            });
+        }
+    });
+}